@@ -0,0 +1,301 @@
+//! `pixi://` links which open a workspace or run one of its tasks:
+//!
+//! - `pixi://open?path=<workspace>`
+//! - `pixi://run?path=<workspace>&task=<task>[&environment=<environment>]`
+//!
+//! Any web page or document can open these links, so run links only work for workspaces that are
+//! open or recent, and the task only starts once the user confirms it.
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use miette::IntoDiagnostic;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::oneshot;
+use url::Url;
+
+use crate::{
+    error::Error,
+    manifest::DEFAULT_ENVIRONMENT,
+    pty::{self, PtyInvocation, PtyInvocationKind, PtyTaskInvocation},
+    recent,
+    state::AppState,
+    utils, window,
+};
+
+pub const SCHEME: &str = "pixi";
+
+#[derive(Debug, PartialEq)]
+pub enum DeepLink {
+    Open {
+        path: PathBuf,
+    },
+    Run {
+        path: PathBuf,
+        task: String,
+        environment: Option<String>,
+    },
+}
+
+impl DeepLink {
+    /// Returns `None` if the URL isn't a valid `pixi://` link
+    pub fn parse(url: &Url) -> Option<Self> {
+        if url.scheme() != SCHEME {
+            return None;
+        }
+
+        // `pixi://open?...` has the action as host, `pixi:open?...` as path
+        let action = match url.host_str() {
+            Some(host) if !host.is_empty() => host,
+            _ => url.path().trim_matches('/'),
+        };
+
+        let mut path = None;
+        let mut task = None;
+        let mut environment = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "path" => path = Some(PathBuf::from(value.as_ref())),
+                "task" => task = Some(value.into_owned()),
+                "environment" => environment = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        // There's no sensible directory to resolve relative paths against
+        let path = path.filter(|path| path.is_absolute())?;
+
+        match action {
+            "open" => Some(Self::Open { path }),
+            "run" => Some(Self::Run {
+                path,
+                task: task.filter(|task| !task.is_empty())?,
+                environment: environment.filter(|environment| !environment.is_empty()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl DeepLink {
+    pub fn to_url(&self) -> Url {
+        let (action, path) = match self {
+            Self::Open { path } => ("open", path),
+            Self::Run { path, .. } => ("run", path),
+        };
+        let mut url = Url::parse(&format!("{SCHEME}://{action}")).expect("valid URL");
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("path", &path.to_string_lossy());
+            if let Self::Run {
+                task, environment, ..
+            } = self
+            {
+                query.append_pair("task", task);
+                if let Some(environment) = environment {
+                    query.append_pair("environment", environment);
+                }
+            }
+        }
+        url
+    }
+}
+
+/// Whether a command line argument is a `pixi://` link rather than a path
+pub fn is_deep_link(arg: &Path) -> bool {
+    arg.to_str()
+        .is_some_and(|arg| arg.starts_with(&format!("{SCHEME}:")))
+}
+
+pub fn open_url<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let Some(link) = DeepLink::parse(url) else {
+        warn!("Ignoring malformed link: {url}");
+        return;
+    };
+    info!("Opening link: {url}");
+
+    match link {
+        DeepLink::Open { path } => window::ensure_workspace_window(app, &path),
+        DeepLink::Run {
+            path,
+            task,
+            environment,
+        } => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = confirm_and_run_task(&app, &path, &task, environment).await {
+                    warn!("Failed to run task {task} of {}: {e}", path.display());
+                }
+            });
+        }
+    }
+}
+
+async fn confirm_and_run_task<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+    task: &str,
+    environment: Option<String>,
+) -> Result<(), Error> {
+    if window::workspace_window(app, path).is_none() && !recent::contains(app, path)? {
+        return Err(miette::miette!(
+            "Links can only run tasks of open or recent workspaces, {} is neither",
+            path.display()
+        )
+        .into());
+    }
+    if !confirm_run(app, path, task, environment.as_deref()).await? {
+        info!("Running task {task} of {} was declined", path.display());
+        return Ok(());
+    }
+
+    window::ensure_workspace_window(app, path);
+    run_task(app, path, task.to_string(), environment).await
+}
+
+/// Asks the user whether the task of the link should run
+async fn confirm_run<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+    task: &str,
+    environment: Option<&str>,
+) -> Result<bool, Error> {
+    let environment = environment.unwrap_or(DEFAULT_ENVIRONMENT);
+    let (tx, rx) = oneshot::channel();
+
+    app.dialog()
+        .message(format!(
+            "A link wants to run the task '{task}' in the {environment} environment of the \
+             workspace\n\n{}\n\nOnly run it if you opened the link yourself.",
+            path.display()
+        ))
+        .title("Run task?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Run".into(),
+            "Cancel".into(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+
+    Ok(rx.await.into_diagnostic()?)
+}
+
+async fn run_task<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+    task: String,
+    environment: Option<String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(path.to_path_buf())?;
+    let window = window::workspace_window(app, path)
+        .ok_or_else(|| miette::miette!("No window is open for {}", path.display()))?;
+
+    let root = workspace.root().to_string_lossy().into_owned();
+    // Same id as the frontend uses, so the task shows up as running
+    let id = format!(
+        "{}/task-{root}-{}-{task}",
+        window.label(),
+        environment.as_deref().unwrap_or(DEFAULT_ENVIRONMENT)
+    );
+    let invocation = PtyInvocation {
+        cwd: root,
+        manifest: workspace
+            .workspace
+            .provenance
+            .absolute_path()
+            .to_string_lossy()
+            .into_owned(),
+        term: None,
+        colorterm: None,
+        kind: PtyInvocationKind::Task(PtyTaskInvocation {
+            task,
+            environment,
+            args: Vec::new(),
+            cwd_override: None,
+            notify_on_exit: true,
+        }),
+    };
+
+    pty::pty_create(
+        window.as_ref().window(),
+        app.state::<AppState>(),
+        id,
+        invocation,
+        80,
+        24,
+    )
+    .await
+}
+
+// The links in the tests use Unix paths
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn parse(link: &str) -> Option<DeepLink> {
+        DeepLink::parse(&Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn parse_open() {
+        assert_eq!(
+            parse("pixi://open?path=%2Fhome%2Fuser%2Fmy%20project"),
+            Some(DeepLink::Open {
+                path: PathBuf::from("/home/user/my project")
+            })
+        );
+    }
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(
+            parse("pixi://run?path=/project&task=test&environment=py312"),
+            Some(DeepLink::Run {
+                path: PathBuf::from("/project"),
+                task: "test".into(),
+                environment: Some("py312".into()),
+            })
+        );
+        assert_eq!(
+            parse("pixi:run?task=lint&path=/project"),
+            Some(DeepLink::Run {
+                path: PathBuf::from("/project"),
+                task: "lint".into(),
+                environment: None,
+            })
+        );
+    }
+
+    #[test]
+    fn links_round_trip() {
+        let links = [
+            DeepLink::Open {
+                path: PathBuf::from("/home/user/my project"),
+            },
+            DeepLink::Run {
+                path: PathBuf::from("/project"),
+                task: "build & test".into(),
+                environment: Some("py312".into()),
+            },
+            DeepLink::Run {
+                path: PathBuf::from("/project"),
+                task: "lint".into(),
+                environment: None,
+            },
+        ];
+        for link in links {
+            assert_eq!(DeepLink::parse(&link.to_url()), Some(link));
+        }
+    }
+
+    #[test]
+    fn malformed_links_are_rejected() {
+        assert_eq!(parse("pixi://open"), None);
+        assert_eq!(parse("pixi://open?path=relative/dir"), None);
+        assert_eq!(parse("pixi://run?path=/project"), None);
+        assert_eq!(parse("pixi://delete?path=/project"), None);
+        assert_eq!(parse("https://open?path=/project"), None);
+    }
+}
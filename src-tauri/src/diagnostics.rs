@@ -0,0 +1,162 @@
+//! Information about the app and the system it runs on, for bug reports.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+use pixi_api::rattler_conda_types::Platform;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::pixi::{self, workspace::clean};
+
+/// Walking a huge cache stops after this long, its size is only partial then
+const CACHE_WALK_LIMIT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize)]
+pub struct SystemInfo {
+    /// E.g. `linux`, `macos` or `windows`
+    pub os: &'static str,
+    /// E.g. `x86_64` or `aarch64`
+    pub arch: &'static str,
+    pub app_version: &'static str,
+    /// Version of the bundled pixi
+    pub pixi_version: &'static str,
+    pub tauri_version: &'static str,
+    /// `None` if the webview doesn't report its version
+    pub webview_version: Option<String>,
+    /// The conda platform, e.g. `linux-64`
+    pub platform: String,
+}
+
+#[tauri::command]
+pub fn system_info() -> SystemInfo {
+    SystemInfo {
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        app_version: pixi::app_version(),
+        pixi_version: pixi_api::PIXI_VERSION,
+        tauri_version: tauri::VERSION,
+        webview_version: tauri::webview_version()
+            .inspect_err(|e| log::debug!("Failed to get the webview version: {e}"))
+            .ok(),
+        platform: Platform::current().to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CacheInfo {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// Packages extracted in the `pkgs` directory
+    pub package_count: usize,
+    /// Whether walking the cache took too long, `size_bytes` only counts the files seen until then
+    pub partial: bool,
+}
+
+/// Where pixi caches the packages and how much space they take, see `clean_cache` to free it
+#[tauri::command]
+pub async fn cache_info() -> Result<CacheInfo, Error> {
+    let path =
+        clean::cache_dir().ok_or_else(|| miette::miette!("Could not find the cache directory"))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        cache_info_until(path, Instant::now() + CACHE_WALK_LIMIT)
+    })
+    .await
+    .into_diagnostic()
+    .map_err(Error::from)
+}
+
+fn cache_info_until(path: PathBuf, deadline: Instant) -> CacheInfo {
+    let (size_bytes, complete) = size_until(&path, deadline);
+    let package_count = std::fs::read_dir(path.join("pkgs")).map_or(0, |entries| {
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .count()
+    });
+
+    CacheInfo {
+        path,
+        size_bytes,
+        package_count,
+        partial: !complete,
+    }
+}
+
+/// Size of the files in `dir` without following symlinks, and whether all of them were counted
+/// before the deadline
+fn size_until(dir: &Path, deadline: Instant) -> (u64, bool) {
+    let mut size = 0;
+    // Caches are deep, so the directories are walked without recursion
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if Instant::now() >= deadline {
+                return (size, false);
+            }
+            // `DirEntry::metadata` doesn't follow symlinks
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => dirs.push(entry.path()),
+                Ok(metadata) if metadata.is_file() => size += metadata.len(),
+                _ => {}
+            }
+        }
+    }
+
+    (size, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_size_and_packages_are_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        let package = cache.join("pkgs").join("python-3.12.0-h1234_0");
+        std::fs::create_dir_all(package.join("bin")).unwrap();
+        std::fs::write(package.join("bin").join("python"), [0; 10]).unwrap();
+        std::fs::write(
+            cache.join("pkgs").join("python-3.12.0-h1234_0.lock"),
+            [0; 1],
+        )
+        .unwrap();
+        std::fs::create_dir_all(cache.join("repodata")).unwrap();
+        std::fs::write(cache.join("repodata").join("conda-forge.json"), [0; 5]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(std::env::temp_dir(), cache.join("pkgs").join("linked"))
+            .unwrap();
+
+        let far = Instant::now() + Duration::from_secs(60);
+        assert_eq!(
+            cache_info_until(cache.clone(), far),
+            CacheInfo {
+                path: cache.clone(),
+                size_bytes: 16,
+                package_count: 1,
+                partial: false,
+            }
+        );
+
+        let info = cache_info_until(cache.clone(), Instant::now());
+        assert!(info.partial);
+        assert_eq!(info.size_bytes, 0);
+    }
+
+    #[test]
+    fn missing_cache_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = cache_info_until(
+            dir.path().join("cache"),
+            Instant::now() + Duration::from_secs(60),
+        );
+        assert_eq!((info.size_bytes, info.package_count), (0, 0));
+        assert!(!info.partial);
+    }
+}
@@ -1,4 +1,9 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use pixi_api::{
     manifest::{EnvironmentName, HasFeaturesIter},
@@ -8,7 +13,7 @@ use serde::{Deserialize, Serialize};
 use tauri::{Runtime, Window};
 use which::which;
 
-use crate::{error::Error, utils};
+use crate::{error::Error, state::AppState, utils};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -100,14 +105,75 @@ const INSTALLABLE_EDITORS: &[Editor] = &[
     },
 ];
 
+/// How long detected editors are reused before they are looked up again
+const EDITOR_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Short-lived cache of detected editors, keyed by workspace and environment
+#[derive(Default)]
+pub struct EditorCache {
+    entries: HashMap<(PathBuf, EnvironmentName), (Instant, Vec<Editor>)>,
+}
+
+impl EditorCache {
+    pub fn get(&self, workspace: &Path, environment: &EnvironmentName) -> Option<Vec<Editor>> {
+        self.entries
+            .get(&(cache_key(workspace), environment.clone()))
+            .filter(|(cached_at, _)| cached_at.elapsed() < EDITOR_CACHE_TTL)
+            .map(|(_, editors)| editors.clone())
+    }
+
+    pub fn insert(&mut self, workspace: &Path, environment: EnvironmentName, editors: Vec<Editor>) {
+        // Drop expired entries so the cache doesn't grow with every workspace ever opened
+        self.entries
+            .retain(|_, (cached_at, _)| cached_at.elapsed() < EDITOR_CACHE_TTL);
+        self.entries.insert(
+            (cache_key(workspace), environment),
+            (Instant::now(), editors),
+        );
+    }
+
+    /// Drops all cached entries of the workspace (e.g. because its manifest changed)
+    pub fn invalidate(&mut self, workspace: &Path) {
+        let workspace = cache_key(workspace);
+        self.entries.retain(|(path, _), _| *path != workspace);
+    }
+}
+
+/// Canonical form of a workspace path, so trailing slashes or symlinks map to the same entry
+fn cache_key(workspace: &Path) -> PathBuf {
+    dunce::canonicalize(workspace).unwrap_or_else(|_| workspace.to_path_buf())
+}
+
 /// List all available editors for an environment (system editors + installed tools)
 #[tauri::command]
 pub async fn list_available_editors<R: Runtime>(
     window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+    environment: EnvironmentName,
+    force_refresh: bool,
+) -> Result<Vec<Editor>, Error> {
+    available_editors(window, &state, workspace, environment, force_refresh).await
+}
+
+async fn available_editors<R: Runtime>(
+    window: Window<R>,
+    state: &AppState,
     workspace: PathBuf,
     environment: EnvironmentName,
+    force_refresh: bool,
 ) -> Result<Vec<Editor>, Error> {
-    let ctx = utils::workspace_context(window, workspace)?;
+    if !force_refresh
+        && let Some(editors) = state
+            .editor_cache()
+            .lock()
+            .await
+            .get(&workspace, &environment)
+    {
+        return Ok(editors);
+    }
+
+    let ctx = utils::workspace_context(window, workspace.clone())?;
 
     let feature_names: Vec<_> = ctx
         .workspace()
@@ -128,28 +194,28 @@ pub async fn list_available_editors<R: Runtime>(
         .copied()
         .collect();
 
+    // Collect the dependencies of all the environment's features once
+    let mut dependencies = HashSet::new();
+    for feature_name in feature_names {
+        if let Some(deps) = ctx.list_feature_dependencies(feature_name, None).await {
+            dependencies.extend(deps.into_keys());
+        }
+    }
+
     // Check which installable editors are installed in this environment
     for editor in INSTALLABLE_EDITORS {
         let pkg_name = PackageName::from_str(editor.package_name.unwrap()).unwrap();
-
-        // Check if package exists in any of the environment's features
-        let mut is_installed = false;
-        for feature_name in &feature_names {
-            if let Some(deps) = ctx
-                .list_feature_dependencies(feature_name.clone(), None)
-                .await
-                && deps.contains_key(&pkg_name)
-            {
-                is_installed = true;
-                break;
-            }
-        }
-
-        if is_installed {
+        if dependencies.contains(&pkg_name) {
             editors.push(*editor);
         }
     }
 
+    state
+        .editor_cache()
+        .lock()
+        .await
+        .insert(&workspace, environment, editors.clone());
+
     Ok(editors)
 }
 
@@ -157,11 +223,14 @@ pub async fn list_available_editors<R: Runtime>(
 #[tauri::command]
 pub async fn list_installable_editors<R: Runtime>(
     window: Window<R>,
+    state: tauri::State<'_, AppState>,
     workspace: PathBuf,
     environment: EnvironmentName,
+    force_refresh: bool,
 ) -> Result<Vec<Editor>, Error> {
     // Get all available editors (system + installed tools)
-    let available = list_available_editors(window, workspace, environment).await?;
+    let available =
+        available_editors(window, &state, workspace, environment, force_refresh).await?;
 
     // Return INSTALLABLE_EDITORS minus those already available
     let installable: Vec<Editor> = INSTALLABLE_EDITORS
@@ -13,6 +13,29 @@ impl fmt::Display for Error {
 
 impl StdError for Error {}
 
+impl Error {
+    /// Code of the error returned by operations that were cancelled
+    pub const CANCELLED: &str = "pixi_gui::cancelled";
+
+    pub fn cancelled() -> Self {
+        Self(miette::miette!(
+            code = Error::CANCELLED,
+            "The operation was cancelled"
+        ))
+    }
+
+    /// Code of the error returned when the lock file had to be updated but wasn't allowed to be
+    pub const LOCK_FILE_OUTDATED: &str = "pixi_gui::lock_file_outdated";
+
+    pub fn lock_file_outdated() -> Self {
+        Self(miette::miette!(
+            code = Error::LOCK_FILE_OUTDATED,
+            help = "Update the lock file, e.g. by installing the environment",
+            "The lock file is out of date with the manifest"
+        ))
+    }
+}
+
 impl From<miette::Error> for Error {
     fn from(value: miette::Error) -> Self {
         Self(value)
@@ -25,8 +48,9 @@ impl From<String> for Error {
     }
 }
 
-/// Format an error with its full chain as plain text.
-pub fn format_error_chain(err: &miette::Error) -> String {
+/// Format an error with its full chain as text. The ANSI colors of the messages are only kept
+/// with `keep_ansi`, e.g. for a terminal-styled panel.
+pub fn format_error_chain(err: &miette::Error, keep_ansi: bool) -> String {
     let mut message = err.to_string();
 
     // Append help text if available
@@ -44,7 +68,45 @@ pub fn format_error_chain(err: &miette::Error) -> String {
         source = cause.source();
     }
 
-    message
+    if keep_ansi {
+        message
+    } else {
+        utils::strip_ansi_escapes(&message)
+    }
+}
+
+/// What the frontend receives when a command fails
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct ErrorPayload {
+    pub message: String,
+    pub help: Option<String>,
+    /// Sources of the error, outermost first
+    pub causes: Vec<String>,
+    pub code: Option<String>,
+    /// The whole chain as one text that keeps the ANSI colors, see [`format_error_chain`]
+    pub ansi: String,
+}
+
+impl From<&miette::Error> for ErrorPayload {
+    fn from(err: &miette::Error) -> Self {
+        let mut causes = Vec::new();
+        let err_ref: &dyn StdError = err.as_ref();
+        let mut source = err_ref.source();
+        while let Some(cause) = source {
+            causes.push(utils::strip_ansi_escapes(&cause.to_string()));
+            source = cause.source();
+        }
+
+        Self {
+            message: utils::strip_ansi_escapes(&err.to_string()),
+            help: err
+                .help()
+                .map(|help| utils::strip_ansi_escapes(&help.to_string())),
+            causes,
+            code: err.code().map(|code| code.to_string()),
+            ansi: format_error_chain(err, true),
+        }
+    }
 }
 
 impl serde::Serialize for Error {
@@ -52,12 +114,54 @@ impl serde::Serialize for Error {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&utils::strip_ansi_escapes(&format_error_chain(&self.0)))
+        ErrorPayload::from(&self.0).serialize(serializer)
     }
 }
 
 impl From<Error> for String {
     fn from(value: Error) -> Self {
-        format_error_chain(&value.0)
+        format_error_chain(&value.0, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miette::Context;
+
+    use super::*;
+
+    #[test]
+    fn payload_keeps_help_code_and_causes_apart() {
+        let err = Err::<(), _>(miette::miette!(
+            code = "pixi::manifest",
+            help = "Check the manifest",
+            "Invalid channel"
+        ))
+        .wrap_err("Failed to add the channel")
+        .unwrap_err();
+
+        assert_eq!(
+            ErrorPayload::from(&err),
+            ErrorPayload {
+                message: "Failed to add the channel".into(),
+                help: Some("Check the manifest".into()),
+                causes: vec!["Invalid channel".into()],
+                code: Some("pixi::manifest".into()),
+                ansi: "Failed to add the channel\nHelp: Check the manifest\nCaused by:\n    Invalid channel"
+                    .into(),
+            }
+        );
+    }
+
+    #[test]
+    fn ansi_is_only_kept_on_request() {
+        let err = miette::miette!("\x1b[31mInvalid\x1b[0m channel");
+
+        assert_eq!(format_error_chain(&err, false), "Invalid channel");
+        assert_eq!(
+            format_error_chain(&err, true),
+            "\x1b[31mInvalid\x1b[0m channel"
+        );
+        assert_eq!(ErrorPayload::from(&err).message, "Invalid channel");
     }
 }
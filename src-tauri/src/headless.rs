@@ -0,0 +1,88 @@
+//! Running tasks in the terminal without opening a window, e.g. `pixi-gui run test`.
+//!
+//! With `--window` the task runs in the window of the workspace instead, which is opened if
+//! needed, so `pixi-gui run --window build` starts the build in an app that's already running.
+
+use std::path::PathBuf;
+
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use crate::deep_link::DeepLink;
+use crate::pty::{PtyInvocation, PtyInvocationKind, PtyTaskInvocation};
+use crate::utils;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Path to the Pixi workspace directory
+    #[arg(long, default_value = ".")]
+    pub workspace: PathBuf,
+
+    /// The environment to run the task in
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// Run the task in the window of the workspace instead of the terminal
+    #[arg(long, conflicts_with = "args")]
+    pub window: bool,
+
+    /// The task to run
+    pub task: String,
+
+    /// Arguments passed to the task
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+impl RunArgs {
+    /// The link that runs the task in the window, a relative workspace is resolved against `cwd`
+    pub fn deep_link(&self, cwd: Option<&str>) -> DeepLink {
+        let base = cwd
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        // Drops the `.` of the default workspace
+        let path: PathBuf = base.join(&self.workspace).components().collect();
+
+        DeepLink::Run {
+            path: dunce::canonicalize(&path).unwrap_or(path),
+            task: self.task.clone(),
+            environment: self.environment.clone(),
+        }
+    }
+}
+
+/// Runs the task with its output going to the terminal. Returns the exit code of the task.
+pub fn run_task(args: RunArgs) -> i32 {
+    match invocation(args).and_then(|invocation| Ok(invocation.run_attached()?)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{:?}", e.0);
+            1
+        }
+    }
+}
+
+fn invocation(args: RunArgs) -> Result<PtyInvocation, crate::error::Error> {
+    let path = dunce::canonicalize(&args.workspace).into_diagnostic()?;
+    let workspace = utils::workspace(path)?;
+
+    Ok(PtyInvocation {
+        cwd: workspace.root().to_string_lossy().into_owned(),
+        manifest: workspace
+            .workspace
+            .provenance
+            .absolute_path()
+            .to_string_lossy()
+            .into_owned(),
+        term: None,
+        colorterm: None,
+        kind: PtyInvocationKind::Task(PtyTaskInvocation {
+            task: args.task,
+            environment: args.environment,
+            args: args.args,
+            cwd_override: None,
+            notify_on_exit: false,
+        }),
+    })
+}
@@ -1,10 +1,21 @@
 #![allow(unused_variables)]
 
+pub mod deep_link;
+pub mod diagnostics;
 pub mod editor;
 pub mod error;
+pub mod headless;
+pub mod lifecycle;
+pub mod logging;
+pub mod manifest;
+pub mod pinned;
 pub mod pixi;
 pub mod platform;
+pub mod progress_bars;
 pub mod pty;
+pub mod recent;
+pub mod scrollback;
+pub mod session;
 pub mod state;
 pub mod tauri_interface;
 pub mod utils;
@@ -16,67 +27,189 @@ pub use tauri_interface::TauriInterface;
 use std::path::PathBuf;
 
 use crate::state::AppState;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use url::Url;
 
 #[derive(Parser)]
 #[command(version = option_env!("PIXI_GUI_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")))]
 #[command(about = env!("CARGO_PKG_DESCRIPTION"))]
 pub struct Cli {
-    /// Path to the Pixi workspace directory
+    /// Path to the Pixi workspace directory.
+    ///
+    /// A directory named `run` has to be passed as `./run` or after `--`, otherwise it's the `run`
+    /// command.
     #[arg()]
     pub workspace: Option<PathBuf>,
 
+    /// Print the versions of Pixi GUI and the bundled Pixi as JSON and exit
+    #[arg(long)]
+    pub version_json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[cfg(not(debug_assertions))]
     /// Disables automatic app relaunch (detaches from terminal)
     #[arg(long)]
     pub no_relaunch: bool,
 }
 
-impl Cli {
-    pub fn absolute_workspace_path(&self, cwd: Option<&str>) -> Option<PathBuf> {
-        self.workspace.as_ref().map(|path| {
-            if path.is_absolute() {
-                path.clone()
-            } else {
-                let base = cwd
-                    .map(PathBuf::from)
-                    .or_else(|| std::env::current_dir().ok())
-                    .unwrap_or_else(|| PathBuf::from("."));
-                dunce::canonicalize(base.join(path)).unwrap_or_else(|_| path.clone())
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a task in the terminal without opening a window, or in the window with `--window`
+    Run(headless::RunArgs),
+}
+
+/// What the app opens once it's launched
+#[derive(Debug, PartialEq)]
+pub enum Launch {
+    Workspace(PathBuf),
+    /// A `pixi://` link, see `deep_link`
+    DeepLink(Url),
+}
+
+impl Launch {
+    /// The command line argument that launches the same again, used when relaunching the app
+    pub fn to_arg(&self) -> String {
+        match self {
+            Launch::Workspace(path) => path.to_string_lossy().into_owned(),
+            Launch::DeepLink(url) => url.to_string(),
+        }
+    }
+
+    fn open<R: tauri::Runtime>(&self, app: &tauri::AppHandle<R>) {
+        match self {
+            Launch::Workspace(path) => {
+                log::info!("Opening {} from the command line", path.display());
+                window::ensure_workspace_window(app, path);
             }
-        })
+            Launch::DeepLink(url) => deep_link::open_url(app, url),
+        }
+    }
+}
+
+impl Cli {
+    /// Whether the app only runs in the terminal, without opening a window
+    pub fn is_headless(&self) -> bool {
+        matches!(&self.command, Some(Command::Run(args)) if !args.window)
+    }
+
+    /// What to open, relative workspaces are resolved against `cwd`. For `run --window` this is
+    /// the `pixi://run` link of the task.
+    pub fn launch(&self, cwd: Option<&str>) -> Option<Launch> {
+        if let Some(Command::Run(args)) = &self.command
+            && args.window
+        {
+            return Some(Launch::DeepLink(args.deep_link(cwd).to_url()));
+        }
+
+        let path = self.workspace.as_ref()?;
+        // Links are resolved once the app runs, see `deep_link`
+        if deep_link::is_deep_link(path) {
+            let link = path.to_string_lossy();
+            return match Url::parse(&link) {
+                Ok(url) => Some(Launch::DeepLink(url)),
+                Err(e) => {
+                    log::warn!("Ignoring malformed link {link}: {e}");
+                    None
+                }
+            };
+        }
+
+        let path = if path.is_absolute() {
+            path.clone()
+        } else if let Some(path) = file_url_path(path) {
+            path
+        } else {
+            let base = cwd
+                .map(PathBuf::from)
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_else(|| PathBuf::from("."));
+            let path = base.join(path);
+            dunce::canonicalize(&path).unwrap_or(path)
+        };
+        Some(Launch::Workspace(path))
     }
 }
 
+/// Workspace opened when the app is launched without one, e.g. for kiosk setups.
+///
+/// The workspace is chosen in this order:
+/// 1. the workspace passed on the command line
+/// 2. `PIXI_GUI_DEFAULT_WORKSPACE`
+/// 3. the workspaces of the last session, if session restore is enabled
+/// 4. the start screen
+pub const DEFAULT_WORKSPACE_ENV: &str = "PIXI_GUI_DEFAULT_WORKSPACE";
+
+/// The workspace of `PIXI_GUI_DEFAULT_WORKSPACE`, if it's set and contains a valid manifest
+fn default_workspace(value: Option<std::ffi::OsString>) -> Option<PathBuf> {
+    let path = PathBuf::from(value.filter(|value| !value.is_empty())?);
+    let path = dunce::canonicalize(&path).unwrap_or(path);
+    match utils::workspace(path.clone()) {
+        Ok(_) => Some(path),
+        Err(e) => {
+            log::warn!(
+                "Ignoring {DEFAULT_WORKSPACE_ENV}={}: {}",
+                path.display(),
+                e.0
+            );
+            None
+        }
+    }
+}
+
+/// Opens the workspace of `PIXI_GUI_DEFAULT_WORKSPACE`, the last session or the start screen
+fn open_without_workspace<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    if let Some(workspace) = default_workspace(std::env::var_os(DEFAULT_WORKSPACE_ENV)) {
+        log::info!(
+            "Opening {} from {DEFAULT_WORKSPACE_ENV}",
+            workspace.display()
+        );
+        window::ensure_workspace_window(app, &workspace);
+    } else if session::restore(app) {
+        log::info!("Restored the last session");
+    } else {
+        log::info!("No workspace given, opening the start screen");
+        window::create_default_window(app);
+    }
+}
+
+/// Desktop environments may pass files as `file://` URLs
+fn file_url_path(arg: &std::path::Path) -> Option<PathBuf> {
+    let url = Url::parse(arg.to_str()?).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+    url.to_file_path().ok()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run(workspace_path: Option<String>) {
+pub fn run(launch: Option<Launch>) {
     let app = tauri::Builder::default();
 
     app.manage(AppState::default())
         .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
-            // When a second instance is launched, this callback receives its CLI arguments
+            // When a second instance is launched, this callback receives its CLI arguments.
+            // `run --window` arrives as a `pixi://run` link, which starts the task's PTY.
             let Ok(cli) = Cli::try_parse_from(args) else {
                 return;
             };
 
-            if let Some(workspace) = cli.absolute_workspace_path(Some(&cwd)) {
+            if let Some(launch) = cli.launch(Some(&cwd)) {
+                launch.open(app);
+            } else if let Some(workspace) =
+                default_workspace(std::env::var_os(DEFAULT_WORKSPACE_ENV))
+            {
+                log::info!(
+                    "Opening {} from {DEFAULT_WORKSPACE_ENV}",
+                    workspace.display()
+                );
                 window::ensure_workspace_window(app, &workspace);
             } else {
                 window::create_default_window(app);
             }
         }))
-        .plugin(
-            tauri_plugin_log::Builder::new()
-                .level(if cfg!(debug_assertions) {
-                    tauri_plugin_log::log::LevelFilter::Debug
-                } else {
-                    tauri_plugin_log::log::LevelFilter::Info
-                })
-                .target(tauri_plugin_log::Target::new(
-                    tauri_plugin_log::TargetKind::Webview,
-                ))
-                .build(),
-        )
+        .plugin(logging::plugin())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
@@ -84,62 +217,159 @@ pub fn run(workspace_path: Option<String>) {
         .invoke_handler(tauri::generate_handler![
             pixi::workspace::add::add_conda_deps,
             pixi::workspace::add::add_pypi_deps,
+            pixi::workspace::add::add_pypi_source_dep,
+            pixi::workspace::add::add_conda_deps_batch,
+            pixi::workspace::add::add_from_search_result,
+            pixi::workspace::add::add_pypi_deps_batch,
+            pixi::workspace::clean::clean_environments,
+            pixi::workspace::clean::clean_cache,
             pixi::workspace::init::init,
             pixi::workspace::list::list_packages,
+            pixi::workspace::lockfile::lockfile_environments,
+            pixi::workspace::lockfile::lockfile_snapshot,
+            pixi::workspace::lockfile::lockfile_diff,
+            pixi::workspace::lockfile::lockfile_status,
+            pixi::workspace::install::install,
             pixi::workspace::reinstall::reinstall,
             pixi::workspace::remove::remove_conda_deps,
             pixi::workspace::remove::remove_pypi_deps,
+            pixi::workspace::remove::remove_conda_deps_batch,
+            pixi::workspace::remove::remove_pypi_deps_batch,
+            pixi::workspace::remove::remove_conda_dep_everywhere,
+            pixi::workspace::remove::remove_pypi_dep_everywhere,
+            pixi::workspace::move_dep::move_conda_dep,
+            pixi::workspace::move_dep::move_pypi_dep,
             pixi::workspace::workspace::name,
             pixi::workspace::workspace::list_features,
+            pixi::workspace::workspace::list_members,
             pixi::workspace::workspace::list_feature_channels,
             pixi::workspace::workspace::list_feature_dependencies,
             pixi::workspace::workspace::list_feature_pypi_dependencies,
+            pixi::workspace::workspace::list_all_dependencies,
+            pixi::workspace::workspace::validate_manifest,
+            pixi::workspace::workspace::get_manifest_text,
+            pixi::workspace::workspace::save_manifest_text,
             pixi::workspace::workspace::list_feature_tasks,
             pixi::workspace::workspace::feature_by_task,
+            pixi::workspace::workspace::task_environments,
             pixi::workspace::workspace::set_name,
             pixi::workspace::workspace::root,
+            pixi::workspace::workspace::reload_workspace,
             pixi::workspace::workspace::manifest,
+            pixi::workspace::workspace::manifest_kind,
+            pixi::workspace::workspace::open_manifest_external,
+            pixi::workspace::workspace::format_manifest,
+            pixi::workspace::workspace::reveal_in_file_manager,
+            pixi::workspace::archive::export_archive,
+            pixi::workspace::archive::import_archive,
             pixi::workspace::workspace::list_environments,
             pixi::workspace::workspace::add_environment,
             pixi::workspace::workspace::remove_environment,
+            pixi::workspace::workspace::duplicate_environment,
+            pixi::workspace::workspace::reorder_environments,
+            pixi::workspace::workspace::set_solve_group,
+            pixi::workspace::workspace::list_solve_groups,
+            pixi::workspace::workspace::get_system_requirements,
+            pixi::workspace::workspace::set_system_requirements,
+            pixi::workspace::workspace::list_activation,
+            pixi::workspace::workspace::set_activation_env,
+            pixi::workspace::workspace::set_activation_scripts,
+            pixi::workspace::workspace::activated_env,
+            pixi::workspace::workspace::get_pypi_options,
+            pixi::workspace::workspace::set_pypi_options,
             pixi::workspace::workspace::remove_feature,
+            pixi::workspace::workspace::rename_feature,
+            pixi::workspace::workspace::set_pypi_editable,
             pixi::workspace::workspace::description,
             pixi::workspace::workspace::set_description,
+            pixi::workspace::workspace::version,
+            pixi::workspace::workspace::set_version,
+            pixi::workspace::workspace::requires_pixi,
+            pixi::workspace::workspace::set_requires_pixi,
+            pixi::workspace::workspace::check_pixi_compatibility,
+            pixi::workspace::workspace::list_preview_features,
+            pixi::workspace::workspace::set_preview_feature,
+            pixi::workspace::workspace::project_info,
+            pixi::workspace::workspace::set_project_info,
             pixi::workspace::workspace::list_channels,
+            pixi::workspace::workspace::describe_channels,
             pixi::workspace::workspace::add_channel,
             pixi::workspace::workspace::remove_channel,
+            pixi::workspace::workspace::add_channels,
+            pixi::workspace::workspace::remove_channels,
+            pixi::workspace::workspace::reorder_channels,
             pixi::workspace::workspace::set_channels,
             pixi::workspace::workspace::list_platforms,
             pixi::workspace::workspace::add_platforms,
             pixi::workspace::workspace::remove_platforms,
             pixi::workspace::workspace::current_platform,
+            pixi::workspace::workspace::detected_virtual_packages,
+            pixi::workspace::workspace::which_in_environment,
             pixi::workspace::task::list_tasks,
             pixi::workspace::task::add_task,
             pixi::workspace::task::remove_task,
+            pixi::workspace::task::rename_task,
+            pixi::workspace::task::set_task_description,
             pixi::workspace::search::search_wildcard,
             pixi::workspace::search::search_exact,
+            pixi::workspace::search::search_versions,
+            pixi::workspace::search::search_pypi,
+            pixi::workspace::search::check_platform_support,
             pixi::pixi_version,
+            pixi::resolved_pixi_version,
+            pixi::cancel_operation,
+            pixi::global::list_global_tools,
+            recent::list_recent_workspaces,
+            recent::add_recent_workspace,
+            recent::remove_recent_workspace,
+            recent::clear_recent_workspaces,
+            pinned::list_pinned_workspaces,
+            pinned::pin_workspace,
+            pinned::unpin_workspace,
+            pinned::reorder_pinned,
+            session::session_restore_enabled,
+            session::set_session_restore,
             pixi::app_version,
+            diagnostics::system_info,
+            diagnostics::cache_info,
             pty::pty_write,
+            pty::pty_write_bytes,
             pty::pty_create,
             pty::pty_resize,
             pty::pty_get_buffer,
+            pty::pty_get_buffer_plain,
             pty::pty_kill,
+            pty::pty_kill_all,
             pty::pty_is_running,
             pty::pty_list,
-            watcher::watch_manifest,
-            watcher::unwatch_manifest,
+            scrollback::pty_load_saved,
+            scrollback::scrollback_persistence_enabled,
+            scrollback::set_scrollback_persistence,
+            logging::log_level,
+            logging::set_log_level,
+            logging::get_log_file_path,
+            logging::read_app_log,
+            lifecycle::workspace_opened,
+            lifecycle::workspace_closed,
             window::open_new_window,
+            window::open_new_window_sized,
+            window::duplicate_workspace_window,
+            window::get_default_window_size,
+            window::set_default_window_size,
+            window::list_windows,
+            window::close_window,
             editor::list_available_editors,
             editor::list_installable_editors,
         ])
         .setup(move |app| {
+            progress_bars::install();
+
             // On Linux and Windows, file associations launch a new process with the file path in CLI args
-            if let Some(workspace) = &workspace_path {
-                window::ensure_workspace_window(app.handle(), &std::path::PathBuf::from(workspace));
+            if let Some(launch) = &launch {
+                launch.open(app.handle());
             } else if cfg!(target_os = "linux") || cfg!(target_os = "windows") {
-                // No files were opened -> Open default window
-                window::create_default_window(app.handle());
+                // No files were opened
+                open_without_workspace(app.handle());
             }
 
             Ok(())
@@ -147,13 +377,40 @@ pub fn run(workspace_path: Option<String>) {
         .build(tauri::generate_context!())
         .expect("Error while running Tauri application")
         .run(|app, event| {
+            match &event {
+                tauri::RunEvent::ExitRequested { .. } => session::save_open_workspaces(app, None),
+                tauri::RunEvent::WindowEvent {
+                    label,
+                    event: tauri::WindowEvent::CloseRequested { .. },
+                    ..
+                } => session::save_open_workspaces(app, Some(label)),
+                tauri::RunEvent::WindowEvent {
+                    label,
+                    event: tauri::WindowEvent::Destroyed,
+                    ..
+                } => {
+                    use tauri::Manager;
+                    // Windows closed by the OS don't go through `close_window`
+                    let state = app.state::<AppState>().inner().clone();
+                    let label = label.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = lifecycle::release(&state, &label).await {
+                            log::error!("Failed to release the workspace of window {label}: {e}");
+                        }
+                    });
+                }
+                _ => {}
+            }
+
             // On macOS, file associations reuse the existing process and send `RunEvent::Opened` events
             #[cfg(target_os = "macos")]
             match event {
                 tauri::RunEvent::Opened { urls } => {
                     log::info!("Received RunEvent::Opened with {} URL(s)", urls.len());
                     for url in urls {
-                        if let Ok(path) = url.to_file_path() {
+                        if url.scheme() == deep_link::SCHEME {
+                            deep_link::open_url(app, &url);
+                        } else if let Ok(path) = url.to_file_path() {
                             log::info!("Opening workspace from URL: {}", path.display());
                             window::ensure_workspace_window(app, &path);
                         }
@@ -163,11 +420,109 @@ pub fn run(workspace_path: Option<String>) {
                     use tauri::Manager;
                     log::info!("Received RunEvent::Ready");
                     if app.webview_windows().is_empty() {
-                        log::info!("No windows open, creating default window");
-                        window::create_default_window(app);
+                        open_without_workspace(app);
                     }
                 }
                 _ => {}
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_workspace_needs_a_manifest() {
+        assert_eq!(default_workspace(None), None);
+        assert_eq!(default_workspace(Some("".into())), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(default_workspace(Some(dir.path().into())), None);
+    }
+
+    #[test]
+    fn relative_workspaces_are_resolved_against_cwd() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("project")).unwrap();
+        let dir = dunce::canonicalize(temp.path()).unwrap();
+        let cwd = dir.to_str().unwrap();
+        let resolve = |workspace: &str| {
+            Cli::try_parse_from(["pixi-gui", workspace])
+                .unwrap()
+                .launch(Some(cwd))
+                .unwrap()
+        };
+        let workspace = |path: PathBuf| Launch::Workspace(path);
+
+        assert_eq!(resolve("project"), workspace(dir.join("project")));
+        assert_eq!(
+            resolve("project/../project"),
+            workspace(dir.join("project"))
+        );
+        assert_eq!(resolve("."), workspace(dir.clone()));
+        // Paths that don't exist are still made absolute
+        assert_eq!(resolve("missing"), workspace(dir.join("missing")));
+        assert_eq!(
+            resolve(dir.join("project").to_str().unwrap()),
+            workspace(dir.join("project"))
+        );
+        assert_eq!(
+            Cli::try_parse_from(["pixi-gui"]).unwrap().launch(Some(cwd)),
+            None
+        );
+    }
+
+    #[test]
+    fn workspaces_named_run_need_an_escape() {
+        let cwd = "/missing";
+        for args in [&["pixi-gui", "./run"][..], &["pixi-gui", "--", "run"]] {
+            let cli = Cli::try_parse_from(args).unwrap();
+            assert!(cli.command.is_none());
+            assert_eq!(
+                cli.launch(Some(cwd)),
+                Some(Launch::Workspace(PathBuf::from(cwd).join("run")))
+            );
+        }
+        // Without an escape `run` is the command, which needs a task
+        assert!(Cli::try_parse_from(["pixi-gui", "run"]).is_err());
+    }
+
+    #[test]
+    fn links_are_launched_as_links() {
+        let link = "pixi://open?path=%2Fmissing%2Fproject";
+        let launch = Cli::try_parse_from(["pixi-gui", link])
+            .unwrap()
+            .launch(None)
+            .unwrap();
+        assert_eq!(launch, Launch::DeepLink(Url::parse(link).unwrap()));
+        assert_eq!(launch.to_arg(), link);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_in_window_becomes_a_link() {
+        let cli =
+            Cli::try_parse_from(["pixi-gui", "run", "--window", "-e", "py312", "build"]).unwrap();
+        assert!(!cli.is_headless());
+
+        let Some(Launch::DeepLink(link)) = cli.launch(Some("/missing/project")) else {
+            panic!("`run --window` should launch a link");
+        };
+        assert_eq!(
+            deep_link::DeepLink::parse(&link),
+            Some(deep_link::DeepLink::Run {
+                path: PathBuf::from("/missing/project"),
+                task: "build".into(),
+                environment: Some("py312".into()),
+            })
+        );
+
+        assert!(
+            Cli::try_parse_from(["pixi-gui", "run", "build", "--fast"])
+                .unwrap()
+                .is_headless()
+        );
+        assert!(Cli::try_parse_from(["pixi-gui", "run", "--window", "build", "--fast"]).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Opening and closing a workspace in a window.
+//!
+//! Everything a window holds for its workspace (the manifest watcher, the cached workspace and
+//! the PTYs) is set up and released here.
+
+use std::path::PathBuf;
+
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, Window};
+
+use crate::{error::Error, pty, state::AppState, utils, window::record_recent_workspace};
+
+#[derive(Clone, Serialize)]
+pub struct WorkspaceOpenedEvent {
+    pub root: PathBuf,
+    pub manifest: PathBuf,
+}
+
+/// Watches the manifest of the workspace for the window, caches the workspace and moves it to
+/// the top of the recent workspaces. Emits `workspace-opened` to the window afterwards.
+///
+/// Replaces whatever the window had open before.
+#[tauri::command]
+pub async fn workspace_opened<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+) -> Result<(), Error> {
+    let label = window.label().to_string();
+    let located = utils::workspace(workspace.clone())?;
+    let manifest = located.workspace.provenance.absolute_path();
+
+    state.workspace_cache().lock().unwrap().insert(
+        label.clone(),
+        workspace.clone(),
+        located.clone(),
+    );
+    state
+        .watcher()
+        .lock()
+        .await
+        .watch(app.clone(), label.clone(), manifest.clone())?;
+    record_recent_workspace(&app, &workspace);
+
+    window
+        .emit_to(
+            &label,
+            "workspace-opened",
+            WorkspaceOpenedEvent {
+                root: located.root().to_path_buf(),
+                manifest,
+            },
+        )
+        .into_diagnostic()?;
+
+    Ok(())
+}
+
+/// Kills the PTYs of the window, stops its manifest watcher and drops its cached workspace.
+/// Emits `workspace-closed` to the window afterwards.
+#[tauri::command]
+pub async fn workspace_closed<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), Error> {
+    let label = window.label();
+    release(&state, label).await?;
+
+    window
+        .emit_to(label, "workspace-closed", ())
+        .into_diagnostic()?;
+
+    Ok(())
+}
+
+/// Releases everything the window holds for its workspace
+pub async fn release(state: &AppState, window_label: &str) -> Result<(), Error> {
+    // Stop the watcher first, so killed tasks that touch the manifest don't trigger a reload
+    state.watcher().lock().await.unwatch(window_label);
+    state
+        .workspace_cache()
+        .lock()
+        .unwrap()
+        .invalidate_window(window_label);
+    pty::kill_window_ptys(state, window_label).await
+}
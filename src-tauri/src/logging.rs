@@ -0,0 +1,130 @@
+//! Logging to the webview, stdout and a file in the app log dir, with a level that can be changed
+//! at runtime so users can capture debug logs of a bug without a special build.
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::LevelFilter;
+use miette::IntoDiagnostic;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_log::{Target, TargetKind};
+
+use crate::error::Error;
+use crate::utils;
+
+const DEFAULT_LEVEL: LevelFilter = if cfg!(debug_assertions) {
+    LevelFilter::Debug
+} else {
+    LevelFilter::Info
+};
+/// Large enough to hold a debug session, the previous file is kept on rotation
+const MAX_FILE_SIZE: u128 = 5 * 1024 * 1024;
+
+static LEVEL: AtomicUsize = AtomicUsize::new(DEFAULT_LEVEL as usize);
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::new()
+        // Everything passes the logger, the filter applies the level set at runtime
+        .level(LevelFilter::Trace)
+        .filter(|metadata| metadata.level() <= level())
+        .max_file_size(MAX_FILE_SIZE)
+        .target(Target::new(TargetKind::Webview))
+        .build()
+}
+
+fn level() -> LevelFilter {
+    LevelFilter::iter()
+        .nth(LEVEL.load(Ordering::Relaxed))
+        .unwrap_or(DEFAULT_LEVEL)
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, Error> {
+    LevelFilter::from_str(level).map_err(|_| {
+        miette::miette!(
+            "Unknown log level '{level}', expected one of off, error, warn, info, debug or trace"
+        )
+        .into()
+    })
+}
+
+#[tauri::command]
+pub fn log_level() -> String {
+    level().as_str().to_lowercase()
+}
+
+/// Applies until the app exits, e.g. `debug` to capture more details of a bug
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<(), Error> {
+    let level = parse_level(&level)?;
+    LEVEL.store(level as usize, Ordering::Relaxed);
+    log::set_max_level(level);
+    log::info!("Log level set to {level}");
+    Ok(())
+}
+
+/// The file the logs are written to, e.g. to attach it to an issue report
+#[tauri::command]
+pub fn get_log_file_path<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, Error> {
+    let dir = app.path().app_log_dir().into_diagnostic()?;
+    Ok(dir.join(format!("{}.log", app.package_info().name)))
+}
+
+/// The last `lines` lines of the log file without ANSI escape codes, empty if there's no log yet
+#[tauri::command]
+pub async fn read_app_log<R: Runtime>(app: AppHandle<R>, lines: usize) -> Result<String, Error> {
+    let path = get_log_file_path(app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(e) => {
+                return Err(miette::miette!("Failed to read '{}': {e}", path.display()).into());
+            }
+        };
+        let contents = String::from_utf8_lossy(&contents);
+        Ok(utils::strip_ansi_escapes(last_lines(&contents, lines)))
+    })
+    .await
+    .into_diagnostic()?
+}
+
+fn last_lines(text: &str, lines: usize) -> &str {
+    let text = text.trim_end_matches('\n');
+    if lines == 0 {
+        return "";
+    }
+    match text.rmatch_indices('\n').nth(lines - 1) {
+        Some((index, _)) => &text[index + 1..],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_parsed() {
+        assert_eq!(parse_level("debug").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level("TRACE").unwrap(), LevelFilter::Trace);
+        assert_eq!(parse_level("off").unwrap(), LevelFilter::Off);
+        assert!(parse_level("verbose").is_err());
+
+        for level in LevelFilter::iter() {
+            assert_eq!(LevelFilter::iter().nth(level as usize), Some(level));
+        }
+    }
+
+    #[test]
+    fn last_lines_of_the_log() {
+        let log = "one\ntwo\nthree\n";
+        assert_eq!(last_lines(log, 2), "two\nthree");
+        assert_eq!(last_lines(log, 3), "one\ntwo\nthree");
+        assert_eq!(last_lines(log, 10), "one\ntwo\nthree");
+        assert_eq!(last_lines(log, 0), "");
+        assert_eq!(last_lines("", 5), "");
+    }
+}
@@ -8,7 +8,7 @@
 static STACK_SIZE: [u8; 23] = *b" /STACK:8388608,8388608";
 
 use clap::Parser;
-use pixi_gui_lib::Cli;
+use pixi_gui_lib::{Cli, Command};
 
 fn main() {
     // Disable DMA-BUF renderer for WebKit on Linux to avoid graphics glitches with AMDGPU drivers
@@ -19,28 +19,42 @@ fn main() {
     }
 
     let cli = Cli::parse();
-    let workspace = cli
-        .absolute_workspace_path(None)
-        .map(|p| p.to_string_lossy().to_string());
+
+    // These only use the terminal, so they must not relaunch or open a window
+    let headless = cli.is_headless();
+    if cli.version_json || headless {
+        #[cfg(target_os = "windows")]
+        pixi_gui_lib::platform::windows::attach_parent_console();
+    }
+    if cli.version_json {
+        println!("{}", pixi_gui_lib::pixi::versions());
+        return;
+    }
+    if headless && let Some(Command::Run(args)) = cli.command {
+        std::process::exit(pixi_gui_lib::headless::run_task(args));
+    }
+    let launch = cli.launch(None);
 
     // Relaunch as detached process when started from terminal (like VSCode does)
     #[cfg(not(debug_assertions))]
     if pixi_gui_lib::utils::launched_via_terminal() && !cli.no_relaunch {
+        let arg = launch.as_ref().map(pixi_gui_lib::Launch::to_arg);
+
         #[cfg(target_os = "macos")]
-        if pixi_gui_lib::platform::osx::relaunch_via_launchd(workspace.as_deref()) {
+        if pixi_gui_lib::platform::osx::relaunch_via_launchd(arg.as_deref()) {
             return;
         }
 
         #[cfg(target_os = "linux")]
-        if pixi_gui_lib::platform::linux::relaunch_detached(workspace.as_deref()) {
+        if pixi_gui_lib::platform::linux::relaunch_detached(arg.as_deref()) {
             return;
         }
 
         #[cfg(target_os = "windows")]
-        if pixi_gui_lib::platform::windows::relaunch_detached(workspace.as_deref()) {
+        if pixi_gui_lib::platform::windows::relaunch_detached(arg.as_deref()) {
             return;
         }
     }
 
-    pixi_gui_lib::run(workspace)
+    pixi_gui_lib::run(launch)
 }
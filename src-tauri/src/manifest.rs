@@ -0,0 +1,416 @@
+//! Direct edits of the workspace manifest.
+//!
+//! `pixi_api` covers the common operations, but some settings (e.g. task metadata)
+//! can't be changed through it. For those we edit the TOML document ourselves,
+//! which keeps the user's formatting and comments intact.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use miette::{Context, IntoDiagnostic, Result};
+use pixi_api::core::Workspace;
+use serde::Serialize;
+use toml_edit::{Decor, DocumentMut, Item, Key, Table, TableLike};
+
+/// Name of the implicit feature that lives at the root of the manifest
+pub const DEFAULT_FEATURE: &str = "default";
+
+/// Name of the environment that exists even when it isn't listed in `[environments]`
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// Name of the `[tool]` table with the settings of the GUI itself
+const GUI_TOOL_NAME: &str = "pixi-gui";
+
+/// Which file holds the pixi configuration of a workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestKind {
+    /// A `pixi.toml`, or another file with the same layout
+    Pixi,
+    /// A `pyproject.toml` with the configuration under `[tool.pixi]`
+    Pyproject,
+}
+
+impl ManifestKind {
+    pub fn of(manifest: &Path) -> Self {
+        if manifest
+            .file_name()
+            .is_some_and(|name| name == "pyproject.toml")
+        {
+            Self::Pyproject
+        } else {
+            Self::Pixi
+        }
+    }
+}
+
+pub struct ManifestDocument {
+    path: PathBuf,
+    document: DocumentMut,
+}
+
+impl ManifestDocument {
+    pub fn load(workspace: &Workspace) -> Result<Self> {
+        Self::from_path(workspace.workspace.provenance.absolute_path())
+    }
+
+    pub fn from_path(path: PathBuf) -> Result<Self> {
+        let contents = std::fs::read_to_string(&path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read manifest '{}'", path.display()))?;
+        Self::parse(path, &contents)
+    }
+
+    /// Parses the manifest contents, `path` is where they get saved to
+    pub fn parse(path: PathBuf, contents: &str) -> Result<Self> {
+        let document = contents
+            .parse::<DocumentMut>()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to parse manifest '{}'", path.display()))?;
+
+        Ok(Self { path, document })
+    }
+
+    /// Whether the pixi configuration lives in a `pyproject.toml` under `[tool.pixi]`
+    pub fn is_pyproject(&self) -> bool {
+        ManifestKind::of(&self.path) == ManifestKind::Pyproject
+    }
+
+    /// The table holding the pixi configuration
+    pub fn pixi_table(&self) -> Option<&Table> {
+        if self.is_pyproject() {
+            self.document.get("tool")?.get("pixi")?.as_table()
+        } else {
+            Some(self.document.as_table())
+        }
+    }
+
+    pub fn pixi_table_mut(&mut self) -> Result<&mut Table> {
+        if self.is_pyproject() {
+            let tool = get_or_insert_table(self.document.as_table_mut(), "tool")?;
+            get_or_insert_table(tool, "pixi")
+        } else {
+            Ok(self.document.as_table_mut())
+        }
+    }
+
+    /// The `[tool.pixi-gui]` table, which pixi ignores
+    pub fn gui_table(&self) -> Option<&Table> {
+        self.document.get("tool")?.get(GUI_TOOL_NAME)?.as_table()
+    }
+
+    pub fn gui_table_mut(&mut self) -> Result<&mut Table> {
+        let tool = get_or_insert_table(self.document.as_table_mut(), "tool")?;
+        get_or_insert_table(tool, GUI_TOOL_NAME)
+    }
+
+    /// The table with the workspace metadata, `[workspace]` or `[project]` in older manifests
+    pub fn workspace_table(&self) -> Option<&Table> {
+        let pixi = self.pixi_table()?;
+        ["workspace", "project"]
+            .into_iter()
+            .find_map(|key| pixi.get(key)?.as_table())
+    }
+
+    pub fn workspace_table_mut(&mut self) -> Result<&mut Table> {
+        let pixi = self.pixi_table_mut()?;
+        let key = if pixi.contains_key("workspace") || !pixi.contains_key("project") {
+            "workspace"
+        } else {
+            "project"
+        };
+        get_or_insert_table(pixi, key)
+    }
+
+    /// A workspace metadata field, a `pyproject.toml` can also define it in the `[project]` table
+    pub fn workspace_field(&self, key: &str) -> Option<&Item> {
+        if let Some(item) = self.workspace_table().and_then(|table| table.get(key)) {
+            return Some(item);
+        }
+        if self.is_pyproject() {
+            return self.document.get("project")?.get(key);
+        }
+        None
+    }
+
+    /// The table of a feature, which is the pixi root table for the default feature
+    pub fn feature_table(&self, feature: &str) -> Option<&Table> {
+        let pixi = self.pixi_table()?;
+        if feature == DEFAULT_FEATURE {
+            Some(pixi)
+        } else {
+            pixi.get("feature")?.get(feature)?.as_table()
+        }
+    }
+
+    pub fn feature_table_mut(&mut self, feature: &str) -> Result<&mut Table> {
+        let pixi = self.pixi_table_mut()?;
+        if feature == DEFAULT_FEATURE {
+            Ok(pixi)
+        } else {
+            let features = get_or_insert_table(pixi, "feature")?;
+            get_or_insert_table(features, feature)
+        }
+    }
+
+    /// Calls `f` with the name and table of every feature, starting with the default feature
+    pub fn for_each_feature_mut(&mut self, mut f: impl FnMut(&str, &mut Table)) -> Result<()> {
+        let pixi = self.pixi_table_mut()?;
+        f(DEFAULT_FEATURE, pixi);
+
+        if let Some(features) = pixi.get_mut("feature").and_then(Item::as_table_mut) {
+            for (name, feature) in features.iter_mut() {
+                if let Some(feature) = feature.as_table_mut() {
+                    f(name.get(), feature);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tidies the whitespace of the manifest without touching comments or values: trailing
+    /// whitespace is removed, runs of blank lines are collapsed to one and keys are separated
+    /// from their values by ` = `.
+    ///
+    /// Returns whether anything changed.
+    pub fn format(&mut self) -> bool {
+        let before = self.to_string();
+
+        format_table(self.document.as_table_mut());
+        if let Some(trailing) = self.document.trailing().as_str() {
+            let trailing = tidy_lines(trailing);
+            let trailing = trailing.trim_end();
+            self.document.set_trailing(if trailing.is_empty() {
+                String::new()
+            } else {
+                format!("{trailing}\n")
+            });
+        }
+
+        self.to_string() != before
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let result = std::fs::write(&self.path, self.to_string())
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to write manifest '{}'", self.path.display()));
+        // Also after a failed write, which might have changed the file partially
+        crate::utils::invalidate_cached_workspace(&self.path);
+        result
+    }
+}
+
+impl fmt::Display for ManifestDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.document, f)
+    }
+}
+
+fn format_table(table: &mut Table) {
+    tidy_decor(table.decor_mut());
+
+    for (mut key, item) in table.iter_mut() {
+        match item {
+            Item::Value(value) => {
+                let decor = key.leaf_decor_mut();
+                if let Some(prefix) = decor.prefix().and_then(|prefix| prefix.as_str()) {
+                    decor.set_prefix(tidy_lines(prefix));
+                }
+                if decor
+                    .suffix()
+                    .and_then(|suffix| suffix.as_str())
+                    .is_some_and(|suffix| suffix.trim().is_empty())
+                {
+                    decor.set_suffix(" ");
+                }
+
+                let decor = value.decor_mut();
+                if decor
+                    .prefix()
+                    .and_then(|prefix| prefix.as_str())
+                    .is_some_and(|prefix| prefix.trim().is_empty())
+                {
+                    decor.set_prefix(" ");
+                }
+                if let Some(suffix) = decor.suffix().and_then(|suffix| suffix.as_str()) {
+                    decor.set_suffix(suffix.trim_end().to_string());
+                }
+            }
+            Item::Table(table) => format_table(table),
+            Item::ArrayOfTables(tables) => tables.iter_mut().for_each(format_table),
+            Item::None => {}
+        }
+    }
+}
+
+fn tidy_decor(decor: &mut Decor) {
+    if let Some(prefix) = decor.prefix().and_then(|prefix| prefix.as_str()) {
+        decor.set_prefix(tidy_lines(prefix));
+    }
+    if let Some(suffix) = decor.suffix().and_then(|suffix| suffix.as_str()) {
+        decor.set_suffix(suffix.trim_end().to_string());
+    }
+}
+
+/// Removes trailing whitespace from the lines and collapses runs of blank lines. The last line
+/// is the indentation of what follows, so it's kept as is.
+fn tidy_lines(raw: &str) -> String {
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    let indentation = lines.pop().unwrap_or_default();
+
+    let mut tidied: Vec<&str> = Vec::with_capacity(lines.len() + 1);
+    for line in lines {
+        let line = line.trim_end();
+        if !(line.is_empty() && tidied.last().is_some_and(|last| last.is_empty())) {
+            tidied.push(line);
+        }
+    }
+    tidied.push(indentation);
+    tidied.join("\n")
+}
+
+/// Returns the sub-table `key` of `table`, inserting an implicit table if it doesn't exist yet
+pub fn get_or_insert_table<'a>(table: &'a mut Table, key: &str) -> Result<&'a mut Table> {
+    table
+        .entry(key)
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| miette::miette!("Expected `{key}` to be a table in the manifest"))
+}
+
+/// Renames the entry `old` to `new` while keeping its position and formatting
+/// (e.g. the comments above it).
+///
+/// Returns false if there is no entry named `old`.
+pub fn rename_key(table: &mut dyn TableLike, old: &str, new: &str) -> bool {
+    let Some(key) = table.key(old) else {
+        return false;
+    };
+    let renamed = Key::new(new)
+        .with_leaf_decor(key.leaf_decor().clone())
+        .with_dotted_decor(key.dotted_decor().clone());
+
+    // Re-insert all entries in their original order to keep the position of the renamed one
+    let keys: Vec<Key> = table
+        .iter()
+        .filter_map(|(key, _)| table.key(key).cloned())
+        .collect();
+    for key in keys {
+        let Some(item) = table.remove(key.get()) else {
+            continue;
+        };
+        let key = if key.get() == old { &renamed } else { &key };
+        table.insert(key.get(), item);
+        if let Some(mut formatted) = table.key_mut(key.get()) {
+            *formatted.leaf_decor_mut() = key.leaf_decor().clone();
+            *formatted.dotted_decor_mut() = key.dotted_decor().clone();
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_tidies_whitespace_and_keeps_comments() {
+        let mut manifest = ManifestDocument::parse(
+            PathBuf::from("pixi.toml"),
+            "[workspace]   \nname=\"demo\"  # the name  \n\n\n\n# Tasks   \n[tasks]\nbuild =   \"cargo build\"\n\n\n",
+        )
+        .unwrap();
+
+        assert!(manifest.format());
+        assert_eq!(
+            manifest.to_string(),
+            "[workspace]\nname = \"demo\"  # the name\n\n# Tasks\n[tasks]\nbuild = \"cargo build\"\n"
+        );
+        assert!(!manifest.format());
+    }
+
+    #[test]
+    fn workspace_table_of_legacy_manifest() {
+        let mut manifest = ManifestDocument::parse(
+            PathBuf::from("pixi.toml"),
+            "[project]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.workspace_field("version").and_then(Item::as_str),
+            Some("0.1.0")
+        );
+
+        manifest
+            .workspace_table_mut()
+            .unwrap()
+            .insert("version", toml_edit::value("0.2.0"));
+        assert_eq!(
+            manifest.to_string(),
+            "[project]\nname = \"demo\"\nversion = \"0.2.0\"\n"
+        );
+    }
+
+    #[test]
+    fn workspace_field_falls_back_to_pyproject_project() {
+        let manifest = ManifestDocument::parse(
+            PathBuf::from("pyproject.toml"),
+            "[project]\nname = \"demo\"\nversion = \"1.0\"\n\n[tool.pixi.workspace]\nchannels = []\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.workspace_field("version").and_then(Item::as_str),
+            Some("1.0")
+        );
+        assert!(manifest.workspace_field("license").is_none());
+    }
+
+    #[test]
+    fn name_and_description_of_pyproject_workspace() {
+        let path = PathBuf::from("tests/fixtures/pyproject/pyproject.toml");
+        assert_eq!(ManifestKind::of(&path), ManifestKind::Pyproject);
+        assert_eq!(ManifestKind::of(Path::new("pixi.toml")), ManifestKind::Pixi);
+
+        let mut manifest = ManifestDocument::parse(
+            path,
+            include_str!("../tests/fixtures/pyproject/pyproject.toml"),
+        )
+        .unwrap();
+        assert!(manifest.is_pyproject());
+        let field = |manifest: &ManifestDocument, key| {
+            manifest
+                .workspace_field(key)
+                .and_then(Item::as_str)
+                .map(str::to_string)
+        };
+        assert_eq!(field(&manifest, "name").as_deref(), Some("demo"));
+        assert_eq!(
+            field(&manifest, "description").as_deref(),
+            Some("A pyproject based workspace")
+        );
+
+        // Written to `[tool.pixi.workspace]`, which takes precedence over `[project]`
+        manifest
+            .workspace_table_mut()
+            .unwrap()
+            .insert("description", toml_edit::value("Renamed"));
+        assert_eq!(field(&manifest, "description").as_deref(), Some("Renamed"));
+        assert_eq!(field(&manifest, "name").as_deref(), Some("demo"));
+
+        let written = manifest.to_string();
+        assert!(written.contains("description = \"A pyproject based workspace\""));
+        assert!(written.contains(
+            "[tool.pixi.workspace]\nchannels = [\"conda-forge\"]\nplatforms = [\"linux-64\", \"osx-arm64\", \"win-64\"]\ndescription = \"Renamed\"\n"
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Workspaces pinned to the top of the start screen, in the order the user put them.
+
+use std::path::{Path, PathBuf};
+
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::{error::Error, recent};
+
+const STORE_PATH: &str = "pinned-workspaces.json";
+const STORE_KEY: &str = "pinnedWorkspaces";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedWorkspace {
+    manifest: PathBuf,
+    name: String,
+}
+
+#[tauri::command]
+pub async fn list_pinned_workspaces<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<PinnedWorkspace>, Error> {
+    load(&app)
+}
+
+/// Pins the workspace at `path` after the pinned ones, it keeps its place if it's already pinned
+#[tauri::command]
+pub async fn pin_workspace<R: Runtime>(app: AppHandle<R>, path: PathBuf) -> Result<(), Error> {
+    let (manifest, name) = recent::manifest_and_name(&path)?;
+    let pinned = pin(load(&app)?, PinnedWorkspace { manifest, name });
+    save(&app, &pinned)
+}
+
+/// Unpins the workspace, `path` can be its manifest or its root directory
+#[tauri::command]
+pub async fn unpin_workspace<R: Runtime>(app: AppHandle<R>, path: PathBuf) -> Result<(), Error> {
+    let mut pinned = load(&app)?;
+    pinned.retain(|entry| !is_workspace(entry, &path));
+    save(&app, &pinned)
+}
+
+/// Puts the pinned workspaces in the order of their manifests in `order`, which has to contain
+/// exactly the pinned ones
+#[tauri::command]
+pub async fn reorder_pinned<R: Runtime>(
+    app: AppHandle<R>,
+    order: Vec<PathBuf>,
+) -> Result<(), Error> {
+    let pinned = reorder(load(&app)?, &order)?;
+    save(&app, &pinned)
+}
+
+fn is_workspace(entry: &PinnedWorkspace, path: &Path) -> bool {
+    entry.manifest == path || entry.manifest.parent() == Some(path)
+}
+
+fn pin(mut pinned: Vec<PinnedWorkspace>, workspace: PinnedWorkspace) -> Vec<PinnedWorkspace> {
+    match pinned
+        .iter_mut()
+        .find(|entry| entry.manifest == workspace.manifest)
+    {
+        // The name might have changed since it was pinned
+        Some(entry) => entry.name = workspace.name,
+        None => pinned.push(workspace),
+    }
+    pinned
+}
+
+fn reorder(
+    mut pinned: Vec<PinnedWorkspace>,
+    order: &[PathBuf],
+) -> Result<Vec<PinnedWorkspace>, Error> {
+    if order.len() != pinned.len() {
+        return Err(miette::miette!(
+            "The new order has {} workspaces, but {} are pinned",
+            order.len(),
+            pinned.len()
+        )
+        .into());
+    }
+
+    order
+        .iter()
+        .map(|manifest| {
+            let index = pinned
+                .iter()
+                .position(|entry| &entry.manifest == manifest)
+                .ok_or_else(|| miette::miette!("'{}' isn't pinned", manifest.display()))?;
+            Ok(pinned.remove(index))
+        })
+        .collect()
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<PinnedWorkspace>, Error> {
+    let store = app.store(STORE_PATH).into_diagnostic()?;
+    Ok(store
+        .get(STORE_KEY)
+        .and_then(|pinned| serde_json::from_value(pinned).ok())
+        .unwrap_or_default())
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, pinned: &[PinnedWorkspace]) -> Result<(), Error> {
+    let store = app.store(STORE_PATH).into_diagnostic()?;
+    store.set(STORE_KEY, serde_json::to_value(pinned).into_diagnostic()?);
+    store.save().into_diagnostic()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinned(manifest: &str, name: &str) -> PinnedWorkspace {
+        PinnedWorkspace {
+            manifest: PathBuf::from(manifest),
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn pinned_workspaces_keep_their_place() {
+        let workspaces = vec![pinned("/a/pixi.toml", "a"), pinned("/b/pixi.toml", "b")];
+
+        let workspaces = pin(workspaces, pinned("/c/pixi.toml", "c"));
+        let workspaces = pin(workspaces, pinned("/a/pixi.toml", "renamed"));
+        assert_eq!(
+            workspaces,
+            [
+                pinned("/a/pixi.toml", "renamed"),
+                pinned("/b/pixi.toml", "b"),
+                pinned("/c/pixi.toml", "c"),
+            ]
+        );
+
+        assert!(is_workspace(&workspaces[1], Path::new("/b")));
+        assert!(is_workspace(&workspaces[1], Path::new("/b/pixi.toml")));
+        assert!(!is_workspace(&workspaces[1], Path::new("/a")));
+    }
+
+    #[test]
+    fn reorder_needs_all_pinned_workspaces() {
+        let workspaces = vec![pinned("/a/pixi.toml", "a"), pinned("/b/pixi.toml", "b")];
+
+        assert_eq!(
+            reorder(
+                workspaces.clone(),
+                &["/b/pixi.toml".into(), "/a/pixi.toml".into()]
+            )
+            .unwrap(),
+            [pinned("/b/pixi.toml", "b"), pinned("/a/pixi.toml", "a")]
+        );
+        assert!(reorder(workspaces.clone(), &["/b/pixi.toml".into()]).is_err());
+        assert!(reorder(workspaces, &["/b/pixi.toml".into(), "/b/pixi.toml".into()]).is_err());
+    }
+}
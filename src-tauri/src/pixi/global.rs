@@ -0,0 +1,158 @@
+//! Tools installed with `pixi global install`, independent of any workspace.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use miette::{Context, IntoDiagnostic};
+use serde::Serialize;
+use toml_edit::DocumentMut;
+
+use crate::error::Error;
+use crate::pty;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GlobalTool {
+    /// Name of the global environment, usually the name of the installed package
+    pub name: String,
+    /// Installed version, `None` if the environment isn't installed (yet)
+    pub version: Option<String>,
+    pub exposed_binaries: Vec<String>,
+}
+
+/// Lists the global environments of `pixi global`.
+///
+/// Returns an empty list if nothing has been installed globally yet.
+#[tauri::command]
+pub async fn list_global_tools() -> Result<Vec<GlobalTool>, Error> {
+    let Some(home) = pixi_home() else {
+        return Ok(Vec::new());
+    };
+    let manifest = home.join("manifests").join("pixi-global.toml");
+
+    let content = match fs::read_to_string(&manifest) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(miette::miette!("Failed to read '{}': {e}", manifest.display()).into());
+        }
+    };
+
+    global_tools(&content, &home.join("envs"))
+        .wrap_err_with(|| format!("Failed to parse '{}'", manifest.display()))
+        .map_err(Error::from)
+}
+
+/// `$PIXI_HOME`, or `~/.pixi` like pixi itself
+fn pixi_home() -> Option<PathBuf> {
+    std::env::var_os("PIXI_HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| pty::home_dir().map(|home| home.join(".pixi")))
+}
+
+fn global_tools(manifest: &str, envs_dir: &Path) -> miette::Result<Vec<GlobalTool>> {
+    let document = manifest.parse::<DocumentMut>().into_diagnostic()?;
+    let Some(envs) = document.get("envs").and_then(|envs| envs.as_table_like()) else {
+        return Ok(Vec::new());
+    };
+
+    let tools = envs
+        .iter()
+        .map(|(name, env)| {
+            let dependencies: Vec<&str> = env
+                .get("dependencies")
+                .and_then(|dependencies| dependencies.as_table_like())
+                .map(|dependencies| dependencies.iter().map(|(name, _)| name).collect())
+                .unwrap_or_default();
+            // `pixi global install ripgrep` creates a `ripgrep` environment with a `ripgrep`
+            // dependency, other environments are described by their first dependency
+            let package = dependencies
+                .iter()
+                .find(|dependency| **dependency == name)
+                .or(dependencies.first());
+
+            let exposed_binaries = env
+                .get("exposed")
+                .and_then(|exposed| exposed.as_table_like())
+                .map(|exposed| exposed.iter().map(|(name, _)| name.to_string()).collect())
+                .unwrap_or_default();
+
+            GlobalTool {
+                name: name.to_string(),
+                version: package
+                    .and_then(|package| installed_version(&envs_dir.join(name), package)),
+                exposed_binaries,
+            }
+        })
+        .collect();
+
+    Ok(tools)
+}
+
+/// Reads the version of `package` from the `conda-meta/<name>-<version>-<build>.json` records
+fn installed_version(prefix: &Path, package: &str) -> Option<String> {
+    fs::read_dir(prefix.join("conda-meta"))
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let file_name = entry.file_name();
+            let record = file_name.to_str()?.strip_suffix(".json")?;
+            let mut parts = record.rsplitn(3, '-');
+            let (_build, version, name) = (parts.next()?, parts.next()?, parts.next()?);
+            (name == package).then(|| version.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+version = 1
+
+[envs.ripgrep]
+channels = ["conda-forge"]
+dependencies = { ripgrep = "*" }
+exposed = { rg = "rg" }
+
+[envs.python-tools]
+channels = ["conda-forge"]
+dependencies = { python = "3.12.*", ruff = "*" }
+exposed = { python3 = "python3", ruff = "ruff" }
+"#;
+
+    #[test]
+    fn lists_environments_with_exposed_binaries() {
+        let envs = tempfile::tempdir().unwrap();
+        let envs = envs.path();
+        let conda_meta = envs.join("ripgrep").join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(conda_meta.join("ripgrep-14.1.1-h0d2f4fd_0.json"), "{}").unwrap();
+        fs::write(conda_meta.join("libgcc-15.1.0-h767d61c_2.json"), "{}").unwrap();
+
+        assert_eq!(
+            global_tools(MANIFEST, envs).unwrap(),
+            vec![
+                GlobalTool {
+                    name: "ripgrep".into(),
+                    version: Some("14.1.1".into()),
+                    exposed_binaries: vec!["rg".into()],
+                },
+                GlobalTool {
+                    name: "python-tools".into(),
+                    version: None,
+                    exposed_binaries: vec!["python3".into(), "ruff".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn manifest_without_environments() {
+        assert_eq!(
+            global_tools("version = 1", Path::new("/nonexistent")).unwrap(),
+            vec![]
+        );
+    }
+}
@@ -1,13 +1,103 @@
+use miette::IntoDiagnostic;
 use tauri::Runtime;
 use tauri::Window;
 
+use crate::error::Error;
+use crate::pty;
+use crate::state::AppState;
+
+pub mod global;
 pub mod workspace;
 
 #[tauri::command]
 pub async fn pixi_version<R: Runtime>(window: Window<R>) -> String {
     pixi_api::PIXI_VERSION.to_string()
 }
+
+/// Version of the pixi binary that runs tasks and terminals, which can differ from the bundled
+/// [`pixi_version`]. `None` without a pixi binary, the whole output of `pixi --version` if it
+/// doesn't contain a version.
+#[tauri::command]
+pub async fn resolved_pixi_version() -> Result<Option<String>, Error> {
+    tauri::async_runtime::spawn_blocking(|| {
+        Ok(binary_pixi_version()?.map(|output| displayed_pixi_version(&output).to_string()))
+    })
+    .await
+    .into_diagnostic()?
+}
+
+/// Output of `pixi --version` of the pixi binary that runs tasks and terminals, `None` without a
+/// pixi binary. Blocks until pixi exits.
+pub(crate) fn binary_pixi_version() -> Result<Option<String>, Error> {
+    let output = match pty::pixi_command().arg("--version").output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(miette::miette!("Failed to run `pixi --version`: {e}").into());
+        }
+    };
+    if !output.status.success() {
+        return Err(miette::miette!(
+            "`pixi --version` failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// The version in the output of `pixi --version`, e.g. `pixi 0.50.2`
+pub(crate) fn parse_pixi_version(output: &str) -> Option<&str> {
+    output
+        .trim()
+        .strip_prefix("pixi ")
+        .map(str::trim)
+        .filter(|version| !version.is_empty())
+}
+
+fn displayed_pixi_version(output: &str) -> &str {
+    parse_pixi_version(output).unwrap_or_else(|| output.trim())
+}
+
 #[tauri::command]
 pub fn app_version() -> &'static str {
     option_env!("PIXI_GUI_VERSION").unwrap_or(env!("CARGO_PKG_VERSION"))
 }
+
+/// Versions of the app and the bundled pixi, as printed by `--version-json`
+pub fn versions() -> serde_json::Value {
+    serde_json::json!({
+        "pixi_gui": app_version(),
+        "pixi": pixi_api::PIXI_VERSION,
+    })
+}
+
+/// Cancels an operation started with this id.
+///
+/// - `reinstall` stops wherever it is, the environment is completed by the next install.
+/// - `add_conda_deps` restores the manifest as it was before, pixi writes the manifest before
+///   solving. A lock file that was already updated gets solved again on the next install.
+///
+/// Returns false if the operation isn't running (anymore).
+#[tauri::command]
+pub async fn cancel_operation(
+    state: tauri::State<'_, AppState>,
+    id: String,
+) -> Result<bool, Error> {
+    Ok(state.cancel_operation(&id).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unparsable_version_is_shown_as_is() {
+        assert_eq!(displayed_pixi_version("pixi 0.50.2\n"), "0.50.2");
+        assert_eq!(
+            displayed_pixi_version("pixi-nightly 2025\n"),
+            "pixi-nightly 2025"
+        );
+    }
+}
@@ -1,40 +1,161 @@
-use std::path::PathBuf;
+use std::hash::Hash;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 
 use indexmap::IndexMap;
+use miette::{Context, IntoDiagnostic};
+use pixi_api::WorkspaceContext;
+use pixi_api::core::environment::LockFileUsage;
 use pixi_api::manifest::SpecType;
 use pixi_api::pep508::Requirement;
-use pixi_api::pypi_spec::PypiPackageName;
-use pixi_api::rattler_conda_types::{MatchSpec, PackageName};
-use pixi_api::spec::GitReference;
+use pixi_api::pypi_spec::{PixiPypiSource, PixiPypiSpec, PypiPackageName};
+use pixi_api::rattler_conda_types::{
+    Channel, MatchSpec, NamedChannelOrUrl, PackageName, ParseStrictness, Platform, StringMatcher,
+};
+use pixi_api::spec::{GitReference, GitSpec};
 use pixi_api::workspace::{DependencyOptions, GitOptions};
+use serde::Deserialize;
 use tauri::{Runtime, Window};
+use url::Url;
 
+use crate::TauriInterface;
 use crate::error::Error;
+use crate::manifest;
+use crate::tauri_interface::run_with_progress;
 use crate::utils::{self, spawn_local};
 
+/// Settings that apply to all conda dependencies of a single add call
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CondaDepOptions {
+    pub build: Option<String>,
+    pub channel: Option<String>,
+    pub feature: Option<String>,
+    pub platform: Option<String>,
+}
+
+/// Adds conda dependencies.
+///
+/// The `options` get applied to all `specs`, so the frontend doesn't have to compose match
+/// spec strings itself.
+///
+/// With `dep_options.no_install` the environment isn't installed, only the manifest and lock file
+/// are updated like `pixi add --no-install`. This applies to all add commands.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_conda_deps<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
-    specs: IndexMap<PackageName, MatchSpec>,
-    dep_options: DependencyOptions,
+    mut specs: IndexMap<PackageName, MatchSpec>,
+    mut dep_options: DependencyOptions,
+    options: Option<CondaDepOptions>,
+    operation_id: Option<String>,
 ) -> Result<(), Error> {
     spawn_local(move || async move {
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+        let options = options.unwrap_or_default();
+
+        if let Some(build) = options.build {
+            let build = StringMatcher::from_str(&build)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Invalid build string '{build}'"))?;
+            for spec in specs.values_mut() {
+                spec.build = Some(build.clone());
+            }
+        }
+
+        if let Some(channel) = options.channel {
+            let channel = Arc::new(workspace_channel(&ctx, &channel).await?);
+            for spec in specs.values_mut() {
+                spec.channel = Some(channel.clone());
+            }
+        }
+
+        scope_dependency_options(&mut dep_options, options.feature, options.platform)?;
+
         let git_options = GitOptions {
             git: None,
             reference: GitReference::DefaultBranch,
             subdir: None,
         };
 
-        utils::workspace_context(window, workspace)?
-            .add_conda_deps(specs, SpecType::Run, dep_options, git_options)
-            .await?;
+        // pixi writes the manifest before solving, it's restored if the add gets cancelled
+        let manifest = ctx.workspace().workspace.provenance.absolute_path();
+        let original = std::fs::read_to_string(&manifest).into_diagnostic()?;
+
+        let message = added_message(specs.keys().map(PackageName::as_source));
+        let result = run_with_progress(
+            &window,
+            operation_id,
+            "solve",
+            message,
+            ctx.add_conda_deps(specs, SpecType::Run, dep_options, git_options),
+        )
+        .await;
+
+        let Some(result) = result else {
+            std::fs::write(&manifest, original)
+                .into_diagnostic()
+                .wrap_err("Failed to restore the manifest")?;
+            return Err(Error::cancelled());
+        };
+        result?;
 
         Ok(())
     })
     .await
 }
 
+/// Targets the dependency options at a single feature and/or platform
+/// (e.g. `[feature.cuda.target.linux-64.dependencies]`)
+pub fn scope_dependency_options(
+    dep_options: &mut DependencyOptions,
+    feature: Option<String>,
+    platform: Option<String>,
+) -> Result<(), Error> {
+    if let Some(feature) = feature {
+        dep_options.feature = feature.into();
+    }
+
+    if let Some(platform) = platform {
+        let platform = Platform::from_str(&platform)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Unknown platform '{platform}'"))?;
+        dep_options.platforms = vec![platform];
+    }
+
+    Ok(())
+}
+
+/// Resolves `channel` to one of the channels the workspace already uses
+async fn workspace_channel<R: Runtime>(
+    ctx: &WorkspaceContext<TauriInterface<R>>,
+    channel: &str,
+) -> Result<Channel, Error> {
+    let channel_config = ctx.workspace().channel_config();
+    let requested = NamedChannelOrUrl::from_str(channel)
+        .into_diagnostic()
+        .and_then(|named| named.into_channel(&channel_config).into_diagnostic())
+        .wrap_err_with(|| format!("Invalid channel '{channel}'"))?;
+
+    let known = ctx
+        .list_channel()
+        .await
+        .into_values()
+        .flatten()
+        .filter_map(|named| named.into_channel(&channel_config).ok())
+        .any(|known| known.base_url == requested.base_url);
+    if !known {
+        return Err(miette::miette!(
+            "Channel '{channel}' is not used by the workspace, add it to the channels first"
+        )
+        .into());
+    }
+
+    Ok(requested)
+}
+
 #[tauri::command]
 pub async fn add_pypi_deps<R: Runtime>(
     window: Window<R>,
@@ -44,16 +165,592 @@ pub async fn add_pypi_deps<R: Runtime>(
     dep_options: DependencyOptions,
 ) -> Result<(), Error> {
     spawn_local(move || async move {
+        let message = added_message(pypi_deps.keys().map(PypiPackageName::as_source));
         let pypi_deps = pypi_deps
             .into_iter()
             .map(|(name, req)| (name, (req, None, None)))
             .collect();
 
-        utils::workspace_context(window, workspace)?
-            .add_pypi_deps(pypi_deps, editable, dep_options)
-            .await?;
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        run_with_progress(
+            &window,
+            None,
+            "solve",
+            message,
+            ctx.add_pypi_deps(pypi_deps, editable, dep_options),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Adds several conda dependencies with a single manifest write.
+///
+/// All specs get validated first. If any of them is invalid nothing is added and the error lists
+/// every spec that failed to parse.
+#[tauri::command]
+pub async fn add_conda_deps_batch<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    specs: Vec<String>,
+    dep_options: DependencyOptions,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let specs = parse_conda_specs(&specs)?;
+        let git_options = GitOptions {
+            git: None,
+            reference: GitReference::DefaultBranch,
+            subdir: None,
+        };
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        let message = added_message(specs.keys().map(PackageName::as_source));
+        run_with_progress(
+            &window,
+            None,
+            "solve",
+            message,
+            ctx.add_conda_deps(specs, SpecType::Run, dep_options, git_options),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
 
         Ok(())
     })
     .await
 }
+
+/// The package and version picked from the search results, e.g. a `SearchPackage` or a
+/// `PackageVersion` as `search_wildcard` and `search_versions` return them
+#[derive(Debug, Deserialize)]
+pub struct SearchResult {
+    pub name: String,
+    pub version: String,
+}
+
+/// How a dependency added from a search result is pinned to its version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PinMode {
+    /// Exactly this version, e.g. `==1.2.3`
+    Exact,
+    /// This version or a later one of the same minor version, e.g. `>=1.2.3,<1.3`
+    MinorCompatible,
+    /// This version or any later one, e.g. `>=1.2.3`
+    Latest,
+    /// No version, pixi pins the version it solves like `pixi add` does
+    None,
+}
+
+/// Adds the package of a search result to the dependencies, pinned to its version by `pin`.
+#[tauri::command]
+pub async fn add_from_search_result<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    result: SearchResult,
+    pin: PinMode,
+    feature: Option<String>,
+    platform: Option<String>,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let specs = parse_conda_specs(&[pinned_spec(&result, pin)?])?;
+        let mut dep_options = DependencyOptions {
+            feature: manifest::DEFAULT_FEATURE.into(),
+            platforms: Vec::new(),
+            no_install: false,
+            lock_file_usage: LockFileUsage::Update,
+        };
+        scope_dependency_options(&mut dep_options, feature, platform)?;
+        let git_options = GitOptions {
+            git: None,
+            reference: GitReference::DefaultBranch,
+            subdir: None,
+        };
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        run_with_progress(
+            &window,
+            None,
+            "solve",
+            added_message([result.name.as_str()]),
+            ctx.add_conda_deps(specs, SpecType::Run, dep_options, git_options),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
+
+        Ok(())
+    })
+    .await
+}
+
+/// The spec of the search result with the version constraint of `pin`
+fn pinned_spec(result: &SearchResult, pin: PinMode) -> Result<String, Error> {
+    let SearchResult { name, version } = result;
+    Ok(match pin {
+        PinMode::Exact => format!("{name} =={version}"),
+        PinMode::MinorCompatible => format!("{name} >={version},<{}", next_minor(version)?),
+        PinMode::Latest => format!("{name} >={version}"),
+        PinMode::None => name.clone(),
+    })
+}
+
+/// The first version after the minor version of `version`, e.g. `1.3` for `1.2.3` and `2` for `1`
+fn next_minor(version: &str) -> Result<String, Error> {
+    let mut segments: Vec<&str> = version.split('.').take(2).collect();
+    let last = segments.pop().unwrap_or_default();
+    let bumped = last.parse::<u64>().map_err(|_| {
+        miette::miette!("Can't derive a minor-compatible range from version '{version}'")
+    })? + 1;
+
+    Ok(segments
+        .into_iter()
+        .map(str::to_string)
+        .chain([bumped.to_string()])
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+/// Adds several pypi dependencies with a single manifest write.
+///
+/// Like [`add_conda_deps_batch`], nothing is added if any of the requirements is invalid.
+#[tauri::command]
+pub async fn add_pypi_deps_batch<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    specs: Vec<String>,
+    editable: bool,
+    dep_options: DependencyOptions,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let pypi_deps = parse_pypi_specs(&specs)?;
+        let message = added_message(pypi_deps.keys().map(PypiPackageName::as_source));
+        let pypi_deps = pypi_deps
+            .into_iter()
+            .map(|(name, req)| (name, (req, None, None)))
+            .collect();
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        run_with_progress(
+            &window,
+            None,
+            "solve",
+            message,
+            ctx.add_pypi_deps(pypi_deps, editable, dep_options),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Progress message while the dependencies are added, e.g. `Adding numpy, scipy`
+fn added_message<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    format!(
+        "Adding {}",
+        names.into_iter().collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Parses conda match specs (e.g. `conda-forge::numpy >=2`), failing with all invalid specs
+pub fn parse_conda_specs(specs: &[String]) -> Result<IndexMap<PackageName, MatchSpec>, Error> {
+    parse_specs(specs, |spec| {
+        // The name comes after an optional `channel::` prefix
+        let name = spec_name(spec.rsplit("::").next().unwrap_or(spec));
+        let name = PackageName::from_str(name).map_err(|err| err.to_string())?;
+        let spec =
+            MatchSpec::from_str(spec, ParseStrictness::Lenient).map_err(|err| err.to_string())?;
+        Ok((name, spec))
+    })
+}
+
+/// Parses PEP 508 requirements (e.g. `requests[socks]>=2`), failing with all invalid ones
+pub fn parse_pypi_specs(specs: &[String]) -> Result<IndexMap<PypiPackageName, Requirement>, Error> {
+    parse_specs(specs, |spec| {
+        let name = PypiPackageName::from_str(spec_name(spec)).map_err(|err| err.to_string())?;
+        let requirement = Requirement::from_str(spec).map_err(|err| err.to_string())?;
+        Ok((name, requirement))
+    })
+}
+
+fn parse_specs<K: Hash + Eq, V>(
+    specs: &[String],
+    parse: impl Fn(&str) -> Result<(K, V), String>,
+) -> Result<IndexMap<K, V>, Error> {
+    let mut parsed = IndexMap::new();
+    let mut failed = Vec::new();
+    for spec in specs {
+        let spec = spec.trim();
+        if spec_name(spec).is_empty() {
+            failed.push(format!("'{spec}': missing package name"));
+            continue;
+        }
+        match parse(spec) {
+            Ok((name, value)) => {
+                parsed.insert(name, value);
+            }
+            Err(err) => failed.push(format!("'{spec}': {err}")),
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(miette::miette!("Invalid dependencies:\n{}", failed.join("\n")).into());
+    }
+
+    Ok(parsed)
+}
+
+/// The package name at the start of a spec
+fn spec_name(spec: &str) -> &str {
+    let spec = spec.trim_start();
+    let end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    &spec[..end]
+}
+
+/// Where a pypi dependency comes from
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PypiSource {
+    Git {
+        git: String,
+        rev: Option<String>,
+        branch: Option<String>,
+        tag: Option<String>,
+    },
+    Path {
+        path: String,
+        #[serde(default)]
+        editable: bool,
+    },
+    Version {
+        version: Option<String>,
+    },
+}
+
+/// Adds a single pypi dependency from a git repository, a local path or a package index
+#[tauri::command]
+pub async fn add_pypi_source_dep<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    name: PypiPackageName,
+    source: PypiSource,
+    dep_options: DependencyOptions,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        let root = ctx.workspace().root();
+        let requirement = pypi_requirement(name.as_source(), &source, root)?;
+        let requirement = Requirement::from_str(&requirement)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Invalid requirement '{requirement}'"))?;
+        let spec = pypi_spec(&source, root)?;
+        let editable = matches!(source, PypiSource::Path { editable: true, .. });
+
+        let message = added_message([name.as_source()]);
+        let pypi_deps = IndexMap::from([(name, (requirement, spec, None))]);
+        run_with_progress(
+            &window,
+            None,
+            "solve",
+            message,
+            ctx.add_pypi_deps(pypi_deps, editable, dep_options),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
+
+        Ok(())
+    })
+    .await
+}
+
+/// PEP 508 requirement for a pypi dependency, which pixi resolves. The manifest entry of git and
+/// path dependencies is written from [`pypi_spec`] instead.
+fn pypi_requirement(name: &str, source: &PypiSource, root: &Path) -> Result<String, Error> {
+    match source {
+        PypiSource::Git { git, .. } => {
+            let url = git.strip_prefix("git+").unwrap_or(git);
+            Ok(match git_reference(source)? {
+                GitReference::Branch(reference)
+                | GitReference::Tag(reference)
+                | GitReference::Rev(reference) => format!("{name} @ git+{url}@{reference}"),
+                GitReference::DefaultBranch => format!("{name} @ git+{url}"),
+            })
+        }
+        PypiSource::Path { path, .. } => {
+            let path = root.join(path);
+            let path = dunce::canonicalize(&path).unwrap_or(path);
+            let path = path.to_string_lossy().replace('\\', "/");
+            // Windows paths (`C:/...`) need an additional slash to form a valid file URL
+            let separator = if path.starts_with('/') { "" } else { "/" };
+            Ok(format!("{name} @ file://{separator}{path}"))
+        }
+        PypiSource::Version { version } => {
+            let version = version.as_deref().map(str::trim).unwrap_or_default();
+            Ok(match version {
+                "" | "*" => name.to_string(),
+                version if version.starts_with(|c: char| c.is_ascii_digit()) => {
+                    format!("{name}=={version}")
+                }
+                version => format!("{name}{version}"),
+            })
+        }
+    }
+}
+
+/// The manifest entry of git and path dependencies, like `pixi add --pypi` writes it. Paths are
+/// relative to the workspace root so the manifest works on other machines too.
+fn pypi_spec(source: &PypiSource, root: &Path) -> Result<Option<PixiPypiSpec>, Error> {
+    let source = match source {
+        PypiSource::Git { git, .. } => {
+            let url = git.strip_prefix("git+").unwrap_or(git);
+            let url = Url::parse(url)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Invalid git url '{git}'"))?;
+            PixiPypiSource::Git {
+                git: GitSpec {
+                    git: url,
+                    rev: Some(git_reference(source)?),
+                    subdirectory: None,
+                },
+            }
+        }
+        PypiSource::Path { path, editable } => PixiPypiSource::Path {
+            path: relative_path(root, &root.join(path)),
+            editable: editable.then_some(true),
+        },
+        PypiSource::Version { .. } => return Ok(None),
+    };
+
+    Ok(Some(PixiPypiSpec {
+        extras: Vec::new(),
+        source,
+    }))
+}
+
+fn git_reference(source: &PypiSource) -> Result<GitReference, Error> {
+    let PypiSource::Git {
+        git,
+        rev,
+        branch,
+        tag,
+    } = source
+    else {
+        return Ok(GitReference::DefaultBranch);
+    };
+
+    match (rev, branch, tag) {
+        (None, None, None) => Ok(GitReference::DefaultBranch),
+        (Some(rev), None, None) => Ok(GitReference::Rev(rev.clone())),
+        (None, Some(branch), None) => Ok(GitReference::Branch(branch.clone())),
+        (None, None, Some(tag)) => Ok(GitReference::Tag(tag.clone())),
+        _ => Err(miette::miette!("Only one of rev, branch or tag can be set for '{git}'").into()),
+    }
+}
+
+/// `path` relative to `root`, e.g. `./packages/core` or `../shared`. Paths on another drive stay
+/// absolute.
+fn relative_path(root: &Path, path: &Path) -> PathBuf {
+    // Paths that don't exist (yet) are normalized without the file system
+    let normalize = |path: &Path| {
+        dunce::canonicalize(path).unwrap_or_else(|_| {
+            let mut normalized = PathBuf::new();
+            for component in path.components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        normalized.pop();
+                    }
+                    component => normalized.push(component),
+                }
+            }
+            normalized
+        })
+    };
+    let root = normalize(root);
+    let path = normalize(path);
+
+    let root_components: Vec<_> = root.components().collect();
+    let path_components: Vec<_> = path.components().collect();
+    let common = root_components
+        .iter()
+        .zip(&path_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path;
+    }
+
+    let mut relative = PathBuf::new();
+    if common == root_components.len() {
+        relative.push(".");
+    }
+    for _ in common..root_components.len() {
+        relative.push("..");
+    }
+    relative.extend(&path_components[common..]);
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    const ROOT: &str = "/home/user/monorepo";
+    #[cfg(windows)]
+    const ROOT: &str = r"C:\Users\user\monorepo";
+
+    fn search_result(version: &str) -> SearchResult {
+        SearchResult {
+            name: "numpy".into(),
+            version: version.into(),
+        }
+    }
+
+    #[test]
+    fn exact_pin() {
+        assert_eq!(
+            pinned_spec(&search_result("1.2.3"), PinMode::Exact).unwrap(),
+            "numpy ==1.2.3"
+        );
+    }
+
+    #[test]
+    fn minor_compatible_pin() {
+        assert_eq!(
+            pinned_spec(&search_result("1.2.3"), PinMode::MinorCompatible).unwrap(),
+            "numpy >=1.2.3,<1.3"
+        );
+        assert_eq!(
+            pinned_spec(&search_result("2024"), PinMode::MinorCompatible).unwrap(),
+            "numpy >=2024,<2025"
+        );
+        assert!(pinned_spec(&search_result("1.2a"), PinMode::MinorCompatible).is_err());
+    }
+
+    #[test]
+    fn latest_pin() {
+        assert_eq!(
+            pinned_spec(&search_result("1.2.3"), PinMode::Latest).unwrap(),
+            "numpy >=1.2.3"
+        );
+    }
+
+    #[test]
+    fn no_pin() {
+        assert_eq!(
+            pinned_spec(&search_result("1.2.3"), PinMode::None).unwrap(),
+            "numpy"
+        );
+    }
+
+    #[test]
+    fn editable_path_dependency() {
+        let source: PypiSource =
+            serde_json::from_str(r#"{ "path": "packages/core", "editable": true }"#).unwrap();
+        assert!(matches!(source, PypiSource::Path { editable: true, .. }));
+
+        let requirement = pypi_requirement("core", &source, Path::new(ROOT)).unwrap();
+        #[cfg(unix)]
+        assert_eq!(
+            requirement,
+            "core @ file:///home/user/monorepo/packages/core"
+        );
+        #[cfg(windows)]
+        assert_eq!(
+            requirement,
+            "core @ file:///C:/Users/user/monorepo/packages/core"
+        );
+
+        // The manifest gets the path relative to the workspace
+        let spec = pypi_spec(&source, Path::new(ROOT)).unwrap().unwrap();
+        let PixiPypiSource::Path { path, editable } = spec.source else {
+            panic!("expected a path source, got {:?}", spec.source);
+        };
+        assert_eq!(path, Path::new(".").join("packages").join("core"));
+        assert_eq!(editable, Some(true));
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_workspace() {
+        let root = Path::new(ROOT);
+        assert_eq!(
+            relative_path(root, &root.join("packages").join("core")),
+            Path::new(".").join("packages").join("core")
+        );
+        assert_eq!(
+            relative_path(root, &root.join("..").join("shared")),
+            Path::new("..").join("shared")
+        );
+        assert_eq!(
+            relative_path(root, &Path::new(ROOT).parent().unwrap().join("shared")),
+            Path::new("..").join("shared")
+        );
+    }
+
+    #[test]
+    fn git_dependency() {
+        let source: PypiSource = serde_json::from_str(
+            r#"{ "git": "https://github.com/prefix-dev/pixi-gui.git", "tag": "v1" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            pypi_requirement("pixi-gui", &source, Path::new(ROOT)).unwrap(),
+            "pixi-gui @ git+https://github.com/prefix-dev/pixi-gui.git@v1"
+        );
+        let spec = pypi_spec(&source, Path::new(ROOT)).unwrap().unwrap();
+        let PixiPypiSource::Git { git } = spec.source else {
+            panic!("expected a git source, got {:?}", spec.source);
+        };
+        assert_eq!(
+            git.git.as_str(),
+            "https://github.com/prefix-dev/pixi-gui.git"
+        );
+        assert!(matches!(git.rev, Some(GitReference::Tag(tag)) if tag == "v1"));
+
+        let source: PypiSource = serde_json::from_str(
+            r#"{ "git": "https://github.com/prefix-dev/pixi-gui.git", "rev": "abc", "tag": "v1" }"#,
+        )
+        .unwrap();
+        assert!(pypi_requirement("pixi-gui", &source, Path::new(ROOT)).is_err());
+        assert!(pypi_spec(&source, Path::new(ROOT)).is_err());
+    }
+
+    #[test]
+    fn version_dependency() {
+        let requirement = |version: &str| {
+            let source = PypiSource::Version {
+                version: Some(version.to_string()),
+            };
+            pypi_requirement("numpy", &source, Path::new(ROOT)).unwrap()
+        };
+
+        assert_eq!(requirement(""), "numpy");
+        assert_eq!(requirement("2.1"), "numpy==2.1");
+        assert_eq!(requirement(">=2,<3"), "numpy>=2,<3");
+    }
+
+    #[test]
+    fn spec_names() {
+        assert_eq!(spec_name("numpy>=2"), "numpy");
+        assert_eq!(spec_name("  scikit-learn 1.5.*"), "scikit-learn");
+        assert_eq!(spec_name("requests[socks]>=2"), "requests");
+        assert_eq!(spec_name(">=2"), "");
+    }
+
+    #[test]
+    fn batch_reports_all_invalid_specs() {
+        let specs = vec!["numpy".to_string(), ">=2".to_string(), "==1".to_string()];
+        let err = parse_conda_specs(&specs).unwrap_err().to_string();
+        assert!(err.contains("'>=2'"));
+        assert!(err.contains("'==1'"));
+        assert!(!err.contains("'numpy'"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Sharing a workspace as a `.tar.gz` with its manifest and optionally the lock file and the
+//! installed environments.
+//!
+//! Symlinks are never followed while packing. Links that point into the workspace are kept as
+//! links, others are left out so nothing from outside the workspace ends up in the archive.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use log::warn;
+use miette::{Context, IntoDiagnostic};
+use tauri::{Runtime, Window};
+
+use crate::error::Error;
+use crate::utils;
+
+const LOCK_FILE: &str = "pixi.lock";
+const ENVIRONMENTS_DIR: &str = ".pixi/envs";
+
+/// Packs the workspace into a `.tar.gz` in a new temporary directory and returns its path, e.g. to
+/// copy it to where the user wants to save it
+#[tauri::command]
+pub async fn export_archive<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    include_lock: bool,
+    include_env: bool,
+) -> Result<PathBuf, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let root = dunce::canonicalize(workspace.root()).into_diagnostic()?;
+    let manifest = workspace.workspace.provenance.absolute_path();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = entries(&root, &manifest, include_lock, include_env)?;
+
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "workspace".into());
+        let dir = std::env::temp_dir().join(format!("pixi-gui-export-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).into_diagnostic()?;
+        let archive = dir.join(format!("{name}.tar.gz"));
+
+        write_archive(&root, &entries, &archive)
+            .wrap_err_with(|| format!("Failed to write '{}'", archive.display()))?;
+        Ok(archive)
+    })
+    .await
+    .into_diagnostic()?
+}
+
+/// Extracts an archive of [`export_archive`] into `destination`, which must not exist or be
+/// empty. Returns the root of the extracted workspace.
+#[tauri::command]
+pub async fn import_archive(path: PathBuf, destination: PathBuf) -> Result<PathBuf, Error> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if fs::read_dir(&destination).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(
+                miette::miette!("'{}' is not an empty directory", destination.display()).into(),
+            );
+        }
+        fs::create_dir_all(&destination).into_diagnostic()?;
+
+        let file = File::open(&path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to open '{}'", path.display()))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        archive.set_overwrite(false);
+        // Skips entries that would end up outside of the destination
+        archive
+            .unpack(&destination)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to extract '{}'", path.display()))?;
+
+        let root = dunce::canonicalize(&destination).into_diagnostic()?;
+        utils::workspace(root.clone())
+            .map_err(|e| e.0.wrap_err("The archive doesn't contain a Pixi workspace"))?;
+        Ok(root)
+    })
+    .await
+    .into_diagnostic()?
+}
+
+fn write_archive(root: &Path, entries: &[PathBuf], archive: &Path) -> miette::Result<()> {
+    let file = File::create(archive).into_diagnostic()?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder.follow_symlinks(false);
+
+    for entry in entries {
+        let name = entry.strip_prefix(root).into_diagnostic()?;
+        builder
+            .append_path_with_name(entry, name)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to add '{}'", entry.display()))?;
+    }
+
+    builder
+        .into_inner()
+        .into_diagnostic()?
+        .finish()
+        .into_diagnostic()?;
+    Ok(())
+}
+
+/// Paths to pack, directories come before their contents. `root` must be canonical.
+fn entries(
+    root: &Path,
+    manifest: &Path,
+    include_lock: bool,
+    include_env: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut entries = vec![root.join(manifest.file_name().unwrap_or_default())];
+
+    let lock_file = root.join(LOCK_FILE);
+    if include_lock && lock_file.is_file() {
+        entries.push(lock_file);
+    }
+
+    let environments = root.join(ENVIRONMENTS_DIR);
+    if include_env && environments.is_dir() {
+        entries.push(root.join(".pixi"));
+        collect(root, &environments, &mut entries)?;
+    }
+
+    Ok(entries)
+}
+
+fn collect(root: &Path, path: &Path, entries: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let metadata = fs::symlink_metadata(path).into_diagnostic()?;
+    if metadata.is_symlink() {
+        if link_stays_inside(root, path) {
+            entries.push(path.to_path_buf());
+        } else {
+            warn!(
+                "Not packing '{}', it links outside of the workspace",
+                path.display()
+            );
+        }
+    } else if metadata.is_dir() {
+        entries.push(path.to_path_buf());
+        let mut children = fs::read_dir(path)
+            .into_diagnostic()?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()?;
+        children.sort();
+        for child in children {
+            collect(root, &child, entries)?;
+        }
+    } else if metadata.is_file() {
+        entries.push(path.to_path_buf());
+    }
+
+    Ok(())
+}
+
+/// Whether the symlink at `link` resolves to something inside `root`, which must be canonical
+fn link_stays_inside(root: &Path, link: &Path) -> bool {
+    let Ok(target) = fs::read_link(link) else {
+        return false;
+    };
+    let target = link.parent().unwrap_or(root).join(target);
+    dunce::canonicalize(target).is_ok_and(|target| target.starts_with(root))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+
+    #[test]
+    fn symlinks_out_of_the_workspace_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let root = dir.join("workspace");
+        let env = root.join(ENVIRONMENTS_DIR).join("default");
+        fs::create_dir_all(env.join("lib")).unwrap();
+        fs::write(root.join("pixi.toml"), "").unwrap();
+        fs::write(root.join(LOCK_FILE), "").unwrap();
+        fs::write(env.join("lib/libz.so.1"), "").unwrap();
+        fs::write(dir.join("secret"), "").unwrap();
+        symlink("libz.so.1", env.join("lib/libz.so")).unwrap();
+        symlink("../../../../secret", env.join("secret")).unwrap();
+        symlink(dir, env.join("outside")).unwrap();
+
+        let root = dunce::canonicalize(&root).unwrap();
+        let relative = |include_lock, include_env| -> Vec<PathBuf> {
+            entries(&root, &root.join("pixi.toml"), include_lock, include_env)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.strip_prefix(&root).unwrap().to_path_buf())
+                .collect()
+        };
+
+        assert_eq!(relative(false, false), vec![PathBuf::from("pixi.toml")]);
+        assert_eq!(
+            relative(true, true),
+            [
+                "pixi.toml",
+                "pixi.lock",
+                ".pixi",
+                ".pixi/envs",
+                ".pixi/envs/default",
+                ".pixi/envs/default/lib",
+                ".pixi/envs/default/lib/libz.so",
+                ".pixi/envs/default/lib/libz.so.1",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use miette::{Context, IntoDiagnostic};
+use tauri::{Runtime, Window};
+
+use crate::error::Error;
+use crate::utils;
+
+/// Removes the installed environments of the workspace, or only `environment`, like `pixi clean`.
+///
+/// They get installed again the next time they're used. Returns the number of bytes freed.
+#[tauri::command]
+pub async fn clean_environments<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: Option<String>,
+) -> Result<u64, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let target = match environment {
+        Some(environment) => {
+            let name = utils::parse_environment_name(&environment)?;
+            workspace
+                .environment(&name)
+                .ok_or_else(|| miette::miette!("Unknown environment '{environment}'"))?
+                .dir()
+        }
+        None => workspace.environments_dir(),
+    };
+    let pixi_dir = workspace.pixi_dir();
+
+    tauri::async_runtime::spawn_blocking(move || remove_within(&target, &pixi_dir))
+        .await
+        .into_diagnostic()?
+}
+
+/// Removes the global package cache, like `pixi clean cache`. Returns the number of bytes freed.
+#[tauri::command]
+pub async fn clean_cache() -> Result<u64, Error> {
+    let cache = cache_dir().ok_or_else(|| miette::miette!("Could not find the cache directory"))?;
+    let Some(parent) = cache.parent().map(Path::to_path_buf) else {
+        return Err(miette::miette!("Refusing to remove '{}'", cache.display()).into());
+    };
+
+    tauri::async_runtime::spawn_blocking(move || remove_within(&cache, &parent))
+        .await
+        .into_diagnostic()?
+}
+
+/// The package cache pixi uses, see `pixi info`
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    for variable in ["PIXI_CACHE_DIR", "RATTLER_CACHE_DIR"] {
+        if let Some(dir) = std::env::var_os(variable).filter(|dir| !dir.is_empty()) {
+            return Some(PathBuf::from(dir));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    let cache = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+    #[cfg(target_os = "macos")]
+    let cache = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Caches"));
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")));
+
+    cache.map(|cache| cache.join("rattler").join("cache"))
+}
+
+/// Removes `path`, which has to be inside `root`. Returns the number of bytes freed.
+///
+/// Symlinks are removed, but never followed, so nothing outside of `root` gets deleted.
+fn remove_within(path: &Path, root: &Path) -> Result<u64, Error> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        // Nothing to clean
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(miette::miette!("Failed to read '{}': {e}", path.display()).into()),
+    };
+
+    // Only the parent gets resolved, `path` itself might be a symlink
+    let parent = path
+        .parent()
+        .and_then(|parent| dunce::canonicalize(parent).ok());
+    let root = dunce::canonicalize(root).into_diagnostic()?;
+    if !parent.is_some_and(|parent| parent.starts_with(&root)) {
+        return Err(miette::miette!(
+            "Refusing to remove '{}', it's outside of '{}'",
+            path.display(),
+            root.display()
+        )
+        .into());
+    }
+
+    if metadata.is_symlink() {
+        // Directory symlinks on Windows can only be removed with `remove_dir`
+        fs::remove_file(path)
+            .or_else(|_| fs::remove_dir(path))
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to remove '{}'", path.display()))?;
+        return Ok(0);
+    }
+    if metadata.is_file() {
+        fs::remove_file(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to remove '{}'", path.display()))?;
+        return Ok(metadata.len());
+    }
+
+    let size = dir_size(path);
+    // Removes symlinks inside the directory without following them
+    fs::remove_dir_all(path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to remove '{}'", path.display()))?;
+    Ok(size)
+}
+
+/// Size of the files in `dir`, without following symlinks
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn removes_environments_and_counts_bytes() {
+        let root = tempdir().unwrap();
+        let root = root.path();
+        let envs = root.join(".pixi").join("envs");
+        fs::create_dir_all(envs.join("default").join("bin")).unwrap();
+        fs::write(envs.join("default").join("bin").join("python"), [0; 10]).unwrap();
+        fs::write(envs.join("default").join("conda-meta"), [0; 5]).unwrap();
+
+        assert_eq!(remove_within(&envs, &root.join(".pixi")).unwrap(), 15);
+        assert!(!envs.exists());
+        assert_eq!(remove_within(&envs, &root.join(".pixi")).unwrap(), 0);
+    }
+
+    #[test]
+    fn refuses_paths_outside_of_root() {
+        let root = tempdir().unwrap();
+        fs::create_dir_all(root.path().join(".pixi")).unwrap();
+        let other = tempdir().unwrap();
+
+        assert!(remove_within(other.path(), &root.path().join(".pixi")).is_err());
+        assert!(other.path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_not_followed() {
+        let root = tempdir().unwrap();
+        let root = root.path();
+        let outside = tempdir().unwrap();
+        let outside = outside.path();
+        fs::write(outside.join("keep"), [0; 10]).unwrap();
+
+        let envs = root.join(".pixi").join("envs");
+        fs::create_dir_all(&envs).unwrap();
+        std::os::unix::fs::symlink(outside, envs.join("linked")).unwrap();
+
+        assert_eq!(remove_within(&envs, &root.join(".pixi")).unwrap(), 0);
+        assert!(outside.join("keep").exists());
+    }
+}
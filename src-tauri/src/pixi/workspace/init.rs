@@ -1,13 +1,209 @@
-use crate::TauriInterface;
-use crate::error::format_error_chain;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use miette::{Context, IntoDiagnostic};
+use pixi_api::core::Workspace;
+use pixi_api::core::environment::LockFileUsage;
+use pixi_api::manifest::SpecType;
+use pixi_api::spec::GitReference;
+use pixi_api::workspace::{DependencyOptions, GitOptions};
 use pixi_api::{WorkspaceContext, workspace::InitOptions};
+use serde::Deserialize;
 use tauri::{Runtime, Window};
 
+use crate::TauriInterface;
+use crate::error::Error;
+use crate::manifest::{self, ManifestDocument};
+use crate::pixi::workspace::add::{parse_conda_specs, parse_pypi_specs};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// A conda `environment.yml`, like `pixi init --import`
+    CondaYaml,
+    /// A pip `requirements.txt`, which becomes the pypi dependencies
+    RequirementsTxt,
+}
+
+/// A file the dependencies of the new workspace are taken from
+#[derive(Debug, Deserialize)]
+pub struct InitImport {
+    pub kind: ImportKind,
+    pub path: PathBuf,
+}
+
+/// Creates a workspace, with the dependencies of `import` if it's given. Nothing is created if the
+/// file to import is missing or invalid.
 #[tauri::command]
-pub async fn init<R: Runtime>(window: Window<R>, options: InitOptions) -> Result<(), String> {
-    let interface = TauriInterface::new(window);
-    let _ = WorkspaceContext::init(interface, options)
-        .await
-        .map_err(|e| format_error_chain(&e))?;
+pub async fn init<R: Runtime>(
+    window: Window<R>,
+    mut options: InitOptions,
+    import: Option<InitImport>,
+) -> Result<(), Error> {
+    let requirements = match &import {
+        Some(import) => import_file(import, &mut options)?,
+        None => Vec::new(),
+    };
+
+    let interface = TauriInterface::new(window.clone());
+    let workspace = WorkspaceContext::init(interface, options).await?;
+
+    if !requirements.is_empty() {
+        add_requirements(window, workspace, &requirements).await?;
+    }
     Ok(())
 }
+
+/// Checks the file to import. An `environment.yml` is imported by pixi itself, the requirements
+/// of a `requirements.txt` are returned to be added after the workspace is created.
+fn import_file(import: &InitImport, options: &mut InitOptions) -> Result<Vec<String>, Error> {
+    if !import.path.is_file() {
+        return Err(miette::miette!(
+            "The file to import '{}' doesn't exist",
+            import.path.display()
+        )
+        .into());
+    }
+
+    match import.kind {
+        ImportKind::CondaYaml => {
+            options.env_file = Some(import.path.clone());
+            Ok(Vec::new())
+        }
+        ImportKind::RequirementsTxt => {
+            let contents = std::fs::read_to_string(&import.path)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Failed to read '{}'", import.path.display()))?;
+            let requirements = parse_requirements_txt(&contents)
+                .wrap_err_with(|| format!("Failed to import '{}'", import.path.display()))?;
+            // Checked before the workspace is created, so an invalid file doesn't leave one behind
+            parse_pypi_specs(&requirements).map_err(|e| {
+                e.0.wrap_err(format!("Failed to import '{}'", import.path.display()))
+            })?;
+            Ok(requirements)
+        }
+    }
+}
+
+/// The requirements of a `requirements.txt`. Options like `-r other.txt` or `--index-url` can't
+/// be imported and fail with their line numbers.
+fn parse_requirements_txt(contents: &str) -> miette::Result<Vec<String>> {
+    let mut requirements = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut line = String::new();
+    let mut first_line = 0;
+    for (index, physical) in contents.lines().enumerate() {
+        if line.is_empty() {
+            first_line = index + 1;
+        }
+        // A trailing backslash continues the requirement on the next line
+        if let Some(continued) = physical.strip_suffix('\\') {
+            line.push_str(continued);
+            continue;
+        }
+        line.push_str(physical);
+
+        let requirement = match line.find(" #").or_else(|| line.find("\t#")) {
+            Some(comment) => &line[..comment],
+            None if line.trim_start().starts_with('#') => "",
+            None => &line,
+        }
+        .trim();
+        if requirement.starts_with('-') {
+            unsupported.push(format!("line {first_line}: '{requirement}'"));
+        } else if !requirement.is_empty() {
+            requirements.push(requirement.to_string());
+        }
+        line.clear();
+    }
+
+    if !unsupported.is_empty() {
+        return Err(miette::miette!(
+            help = "Only requirements can be imported, add the others to the manifest yourself",
+            "Unsupported options:\n{}",
+            unsupported.join("\n")
+        ));
+    }
+
+    Ok(requirements)
+}
+
+/// Adds the requirements to the manifest of the new workspace, with python for them to install
+/// into. The environment is solved on its first install.
+async fn add_requirements<R: Runtime>(
+    window: Window<R>,
+    workspace: Workspace,
+    requirements: &[String],
+) -> Result<(), Error> {
+    let dep_options = || DependencyOptions {
+        feature: manifest::DEFAULT_FEATURE.into(),
+        platforms: Vec::new(),
+        no_install: true,
+        lock_file_usage: LockFileUsage::Frozen,
+    };
+    // A `pyproject.toml` gets python from its `requires-python`
+    let is_pyproject = ManifestDocument::load(&workspace)?.is_pyproject();
+    let ctx = WorkspaceContext::new(TauriInterface::new(window), workspace);
+
+    if !is_pyproject {
+        let git_options = GitOptions {
+            git: None,
+            reference: GitReference::DefaultBranch,
+            subdir: None,
+        };
+        ctx.add_conda_deps(
+            parse_conda_specs(&["python".to_string()])?,
+            SpecType::Run,
+            dep_options(),
+            git_options,
+        )
+        .await?;
+    }
+
+    let pypi_deps: IndexMap<_, _> = parse_pypi_specs(requirements)?
+        .into_iter()
+        .map(|(name, requirement)| (name, (requirement, None, None)))
+        .collect();
+    ctx.add_pypi_deps(pypi_deps, false, dep_options()).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_are_read_without_comments() {
+        let requirements = parse_requirements_txt(
+            "# Web
+requests[socks]>=2  # for the API
+flask==3.0.* \\
+    ; python_version >= '3.9'
+
+numpy
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            requirements,
+            [
+                "requests[socks]>=2",
+                "flask==3.0.*     ; python_version >= '3.9'",
+                "numpy",
+            ]
+        );
+    }
+
+    #[test]
+    fn options_are_reported_with_their_lines() {
+        let err = parse_requirements_txt("numpy\n-r dev.txt\n--index-url https://example.com\n")
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Unsupported options:\nline 2: '-r dev.txt'\nline 3: '--index-url https://example.com'"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use pixi_api::core::environment::LockFileUsage;
+use tauri::{Runtime, Window};
+
+use crate::{
+    error::Error,
+    pixi::workspace::list::is_lock_file_outdated,
+    tauri_interface::run_with_progress,
+    utils::{self, spawn_local},
+};
+
+/// Installs an environment so it matches the lock file, like `pixi install`. Packages that are
+/// already installed are kept, unlike with `reinstall`.
+///
+/// With `locked` the lock file isn't updated, it fails with [`Error::LOCK_FILE_OUTDATED`] if it's
+/// out of date with the manifest. pixi_api has no install of its own, listing the packages
+/// installs the environment first like `pixi list` does.
+#[tauri::command]
+pub async fn install<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: Option<String>,
+    locked: bool,
+    operation_id: Option<String>,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+        let lock_file_usage = if locked {
+            LockFileUsage::Locked
+        } else {
+            LockFileUsage::Update
+        };
+
+        let message = format!(
+            "Installing the {} environment",
+            environment.as_deref().unwrap_or("default")
+        );
+        run_with_progress(
+            &window,
+            operation_id,
+            "install",
+            message,
+            ctx.list_packages(None, None, environment, false, false, lock_file_usage),
+        )
+        .await
+        .ok_or_else(Error::cancelled)?
+        .map_err(|e| {
+            if locked && is_lock_file_outdated(&e) {
+                Error::lock_file_outdated()
+            } else {
+                Error::from(e)
+            }
+        })?;
+
+        Ok(())
+    })
+    .await
+}
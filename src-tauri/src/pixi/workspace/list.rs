@@ -4,12 +4,15 @@ use crate::{
     error::Error,
     utils::{self, spawn_local},
 };
-use pixi_api::{
-    core::environment::LockFileUsage, manifest::PixiPlatformName, rattler_conda_types::Platform,
-    workspace::Package,
-};
+use pixi_api::{core::environment::LockFileUsage, manifest::PixiPlatformName, workspace::Package};
 use tauri::{Runtime, Window};
 
+/// How `lock_file_usage` affects listing the packages:
+///
+/// - `Update` re-solves the environment if the lock file is out of date with the manifest
+/// - `Locked` only reads the lock file, it neither solves nor installs anything and fails with
+///   [`Error::LOCK_FILE_OUTDATED`] if the lock file is out of date
+/// - `Frozen` reads the lock file as is, without checking it against the manifest
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn list_packages<R: Runtime>(
@@ -24,23 +27,59 @@ pub async fn list_packages<R: Runtime>(
 ) -> Result<Vec<Package>, Error> {
     spawn_local(move || async move {
         let platform: Option<PixiPlatformName> = platform
-            .map(|p| p.parse::<Platform>())
-            .transpose()
-            .unwrap()
+            .as_deref()
+            .map(utils::parse_platform)
+            .transpose()?
             .map(PixiPlatformName::from);
 
+        let locked = matches!(lock_file_usage, LockFileUsage::Locked);
         let packages = utils::workspace_context(window, workspace)?
             .list_packages(
                 regex,
                 platform,
                 environment,
                 explicit,
-                no_install,
+                no_install || locked,
                 lock_file_usage,
             )
-            .await?;
+            .await
+            .map_err(|e| {
+                if locked && is_lock_file_outdated(&e) {
+                    Error::lock_file_outdated()
+                } else {
+                    Error::from(e)
+                }
+            })?;
 
         Ok(packages)
     })
     .await
 }
+
+/// Whether pixi refused to continue because the lock file doesn't satisfy the manifest
+pub(crate) fn is_lock_file_outdated(err: &miette::Error) -> bool {
+    let err: &dyn std::error::Error = err.as_ref();
+    std::iter::successors(Some(err), |err| err.source())
+        .any(|err| err.to_string().contains("lock-file not up-to-date"))
+}
+
+#[cfg(test)]
+mod tests {
+    use miette::Context;
+
+    use super::*;
+
+    #[test]
+    fn outdated_lock_file_is_detected_in_the_chain() {
+        let err = Err::<(), _>(miette::miette!(
+            "lock-file not up-to-date with the workspace"
+        ))
+        .wrap_err("Failed to list the packages")
+        .unwrap_err();
+        assert!(is_lock_file_outdated(&err));
+
+        assert!(!is_lock_file_outdated(&miette::miette!(
+            "No lock file found"
+        )));
+    }
+}
@@ -0,0 +1,437 @@
+//! Reads `pixi.lock` directly, without solving or installing anything.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use miette::{Context, IntoDiagnostic};
+use pixi_api::{
+    core::environment::LockFileUsage, manifest::EnvironmentName, rattler_conda_types::Platform,
+};
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::Serialize;
+use tauri::{Runtime, Window};
+
+use crate::error::Error;
+use crate::pixi::workspace::list::is_lock_file_outdated;
+use crate::utils::{self, spawn_local};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageKind {
+    Conda,
+    Pypi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    VersionChanged,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PackageChange {
+    pub environment: String,
+    pub platform: String,
+    pub name: String,
+    pub kind: PackageKind,
+    pub change: ChangeKind,
+    pub previous_version: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Name and version per package of each environment and platform
+type LockedVersions = BTreeMap<(String, String), BTreeMap<(PackageKind, String), String>>;
+
+/// The environments that are solved in the lock file, with the platforms they're solved for.
+///
+/// Environments of the manifest that are missing need to be installed first. Returns an empty map
+/// if there's no lock file yet.
+#[tauri::command]
+pub async fn lockfile_environments<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<HashMap<EnvironmentName, Vec<Platform>>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let Some(lock_file) = read(&workspace.lock_file_path())? else {
+        return Ok(HashMap::new());
+    };
+    solved_environments(&lock_file)
+}
+
+/// The current content of the lock file, to compare with `lockfile_diff` after an operation
+#[tauri::command]
+pub async fn lockfile_snapshot<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Option<String>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    read_to_string(&workspace.lock_file_path())
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LockFileStatus {
+    pub up_to_date: bool,
+    /// Why the lock file is out of date
+    pub reason: Option<String>,
+}
+
+/// Whether the lock file still satisfies the manifest, e.g. to offer updating it.
+///
+/// Nothing is solved or installed. The check is pixi's own, which also runs before every install
+/// with a `Locked` lock file usage.
+#[tauri::command]
+pub async fn lockfile_status<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<LockFileStatus, Error> {
+    spawn_local(move || async move {
+        let located = utils::workspace(workspace.clone())?;
+        let Some(lock_file) = read(&located.lock_file_path())? else {
+            return Ok(outdated("There is no lock file yet".into()));
+        };
+        let environments = located
+            .environments()
+            .iter()
+            .map(|environment| environment.name().to_string())
+            .collect::<Vec<_>>();
+        if let Some(missing) = missing_environments(&environments, &lock_file).first() {
+            return Ok(outdated(format!(
+                "The environment '{missing}' isn't in the lock file"
+            )));
+        }
+
+        let result = utils::workspace_context(window, workspace)?
+            .list_packages(None, None, None, false, true, LockFileUsage::Locked)
+            .await;
+        match result {
+            Ok(_) => Ok(LockFileStatus {
+                up_to_date: true,
+                reason: None,
+            }),
+            Err(e) if is_lock_file_outdated(&e) => Ok(outdated(reason(&e))),
+            Err(e) => Err(e.into()),
+        }
+    })
+    .await
+}
+
+fn outdated(reason: String) -> LockFileStatus {
+    LockFileStatus {
+        up_to_date: false,
+        reason: Some(reason),
+    }
+}
+
+/// The messages of the error and its causes, e.g. which environment changed
+fn reason(err: &miette::Error) -> String {
+    let err: &dyn std::error::Error = err.as_ref();
+    std::iter::successors(Some(err), |err| err.source())
+        .map(|err| utils::strip_ansi_escapes(&err.to_string()))
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+/// Environments of the manifest that the lock file doesn't have
+fn missing_environments<'a>(environments: &'a [String], lock_file: &LockFile) -> Vec<&'a str> {
+    environments
+        .iter()
+        .filter(|environment| lock_file.environment(environment).is_none())
+        .map(String::as_str)
+        .collect()
+}
+
+/// The packages that changed since the `previous` lock file content, see `lockfile_snapshot`.
+///
+/// Without a previous lock file all packages count as added.
+#[tauri::command]
+pub async fn lockfile_diff<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    previous: Option<String>,
+) -> Result<Vec<PackageChange>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let previous = match previous {
+        Some(previous) => {
+            locked_versions(&parse(&previous).wrap_err("Failed to parse the previous lock file")?)
+        }
+        None => LockedVersions::new(),
+    };
+    let current = read(&workspace.lock_file_path())?
+        .map(|lock_file| locked_versions(&lock_file))
+        .unwrap_or_default();
+
+    Ok(diff(&previous, &current))
+}
+
+fn solved_environments(
+    lock_file: &LockFile,
+) -> Result<HashMap<EnvironmentName, Vec<Platform>>, Error> {
+    lock_file
+        .environments()
+        .map(|(name, environment)| {
+            let mut platforms: Vec<&str> = environment
+                .platforms()
+                .map(|platform| platform.as_str())
+                .collect();
+            platforms.sort_unstable();
+            let platforms = platforms
+                .into_iter()
+                .map(utils::parse_platform)
+                .collect::<Result<_, _>>()?;
+            Ok((utils::parse_environment_name(name)?, platforms))
+        })
+        .collect()
+}
+
+/// Returns `None` if the lock file doesn't exist
+fn read(path: &Path) -> Result<Option<LockFile>, Error> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(
+        LockFile::from_path(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to parse '{}'", path.display()))?,
+    ))
+}
+
+fn read_to_string(path: &Path) -> Result<Option<String>, Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(miette::miette!("Failed to read '{}': {e}", path.display()).into()),
+    }
+}
+
+fn parse(content: &str) -> miette::Result<LockFile> {
+    LockFile::from_str(content).into_diagnostic()
+}
+
+fn locked_versions(lock_file: &LockFile) -> LockedVersions {
+    let mut versions = LockedVersions::new();
+    for (environment, locked) in lock_file.environments() {
+        for platform in locked.platforms() {
+            let entry = versions
+                .entry((environment.to_string(), platform.to_string()))
+                .or_default();
+            for package in locked.packages(platform).into_iter().flatten() {
+                let (kind, version) = match package {
+                    LockedPackageRef::Conda(data) => {
+                        (PackageKind::Conda, data.record().version.to_string())
+                    }
+                    LockedPackageRef::Pypi(data, ..) => {
+                        (PackageKind::Pypi, data.version.to_string())
+                    }
+                };
+                entry.insert((kind, package.name().to_string()), version);
+            }
+        }
+    }
+    versions
+}
+
+fn diff(previous: &LockedVersions, current: &LockedVersions) -> Vec<PackageChange> {
+    let empty = BTreeMap::new();
+    let targets: BTreeSet<_> = previous.keys().chain(current.keys()).collect();
+
+    let mut changes = Vec::new();
+    for target in targets {
+        let (environment, platform) = target;
+        let before = previous.get(target).unwrap_or(&empty);
+        let after = current.get(target).unwrap_or(&empty);
+
+        let packages: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+        for package in packages {
+            let (kind, name) = package;
+            let previous_version = before.get(package);
+            let version = after.get(package);
+            let change = match (previous_version, version) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => ChangeKind::VersionChanged,
+                _ => continue,
+            };
+            changes.push(PackageChange {
+                environment: environment.clone(),
+                platform: platform.clone(),
+                name: name.clone(),
+                kind: *kind,
+                change,
+                previous_version: previous_version.cloned(),
+                version: version.cloned(),
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK_FILE: &str = r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.0-hab00c5b_0_cpython.conda
+      osx-arm64:
+      - conda: https://conda.anaconda.org/conda-forge/osx-arm64/python-3.12.0-h47c9636_0_cpython.conda
+  lint:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/noarch/ruff-0.5.0-py_0.conda
+packages:
+- conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.0-hab00c5b_0_cpython.conda
+  sha256: 5398ebae6a1ccbfd3f76361eac75f3ac071527a8072627c4bf9008c689034f48
+- conda: https://conda.anaconda.org/conda-forge/osx-arm64/python-3.12.0-h47c9636_0_cpython.conda
+  sha256: 4b7d76f5a1e2d3e4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d8
+- conda: https://conda.anaconda.org/conda-forge/noarch/ruff-0.5.0-py_0.conda
+  sha256: 0d2f4e6a8c1b3d5f7e9a2c4b6d8f1e3a5c7b9d2f4e6a8c1b3d5f7e9a2c4b6d8f
+"#;
+
+    #[test]
+    fn environments_missing_from_the_lock_file() {
+        let lock_file = parse(LOCK_FILE).unwrap();
+        let environments = ["default", "test", "lint"].map(String::from);
+
+        assert_eq!(missing_environments(&environments, &lock_file), ["test"]);
+        assert!(missing_environments(&environments[..1], &lock_file).is_empty());
+    }
+
+    #[test]
+    fn reason_includes_the_causes() {
+        let err = Err::<(), _>(miette::miette!("the dependencies of 'default' changed"))
+            .wrap_err("lock-file not up-to-date with the workspace")
+            .unwrap_err();
+
+        assert_eq!(
+            reason(&err),
+            "lock-file not up-to-date with the workspace: the dependencies of 'default' changed"
+        );
+    }
+
+    #[test]
+    fn environments_with_their_platforms() {
+        let environments = solved_environments(&parse(LOCK_FILE).unwrap()).unwrap();
+
+        assert_eq!(environments.len(), 2);
+        assert_eq!(
+            environments[&utils::parse_environment_name("default").unwrap()],
+            vec![
+                utils::parse_platform("linux-64").unwrap(),
+                utils::parse_platform("osx-arm64").unwrap()
+            ]
+        );
+        assert_eq!(
+            environments[&utils::parse_environment_name("lint").unwrap()],
+            vec![utils::parse_platform("linux-64").unwrap()]
+        );
+    }
+
+    const UPDATED_LOCK_FILE: &str = r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.1-hab00c5b_0_cpython.conda
+      - pypi: https://files.pythonhosted.org/packages/f9/9b/requests-2.32.3-py3-none-any.whl
+      osx-arm64:
+      - conda: https://conda.anaconda.org/conda-forge/osx-arm64/python-3.12.0-h47c9636_0_cpython.conda
+packages:
+- conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.1-hab00c5b_0_cpython.conda
+  sha256: 5398ebae6a1ccbfd3f76361eac75f3ac071527a8072627c4bf9008c689034f48
+- conda: https://conda.anaconda.org/conda-forge/osx-arm64/python-3.12.0-h47c9636_0_cpython.conda
+  sha256: 4b7d76f5a1e2d3e4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d8
+- pypi: https://files.pythonhosted.org/packages/f9/9b/requests-2.32.3-py3-none-any.whl
+  name: requests
+  version: 2.32.3
+"#;
+
+    fn change(
+        environment: &str,
+        platform: &str,
+        name: &str,
+        kind: PackageKind,
+        change: ChangeKind,
+        versions: (Option<&str>, Option<&str>),
+    ) -> PackageChange {
+        PackageChange {
+            environment: environment.into(),
+            platform: platform.into(),
+            name: name.into(),
+            kind,
+            change,
+            previous_version: versions.0.map(str::to_string),
+            version: versions.1.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn diff_between_lock_files() {
+        let previous = locked_versions(&parse(LOCK_FILE).unwrap());
+        let current = locked_versions(&parse(UPDATED_LOCK_FILE).unwrap());
+
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                change(
+                    "default",
+                    "linux-64",
+                    "python",
+                    PackageKind::Conda,
+                    ChangeKind::VersionChanged,
+                    (Some("3.12.0"), Some("3.12.1"))
+                ),
+                change(
+                    "default",
+                    "linux-64",
+                    "requests",
+                    PackageKind::Pypi,
+                    ChangeKind::Added,
+                    (None, Some("2.32.3"))
+                ),
+                change(
+                    "lint",
+                    "linux-64",
+                    "ruff",
+                    PackageKind::Conda,
+                    ChangeKind::Removed,
+                    (Some("0.5.0"), None)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_lock_file_adds_everything() {
+        let current = locked_versions(&parse(UPDATED_LOCK_FILE).unwrap());
+        let changes = diff(&LockedVersions::new(), &current);
+
+        assert_eq!(changes.len(), 3);
+        assert!(
+            changes
+                .iter()
+                .all(|change| change.change == ChangeKind::Added)
+        );
+    }
+
+    #[test]
+    fn missing_lock_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read(&dir.path().join("pixi.lock")).unwrap().is_none());
+    }
+}
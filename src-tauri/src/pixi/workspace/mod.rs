@@ -1,6 +1,11 @@
 pub mod add;
+pub mod archive;
+pub mod clean;
 pub mod init;
+pub mod install;
 pub mod list;
+pub mod lockfile;
+pub mod move_dep;
 pub mod reinstall;
 pub mod remove;
 pub mod search;
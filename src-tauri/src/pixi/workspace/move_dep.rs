@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+
+use tauri::{Runtime, Window};
+use toml_edit::{Item, TableLike};
+
+use crate::error::Error;
+use crate::manifest::{self, ManifestDocument};
+use crate::utils;
+
+#[tauri::command]
+pub async fn move_conda_dep<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    package: String,
+    from_feature: String,
+    to_feature: String,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    move_dependency(
+        &mut manifest,
+        "dependencies",
+        &package,
+        &from_feature,
+        &to_feature,
+        normalize_conda_name,
+    )?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn move_pypi_dep<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    package: String,
+    from_feature: String,
+    to_feature: String,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    move_dependency(
+        &mut manifest,
+        "pypi-dependencies",
+        &package,
+        &from_feature,
+        &to_feature,
+        normalize_pypi_name,
+    )?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// Moves the entry of `package` from the `table` of one feature to the other, keeping the spec
+/// exactly as it was written
+fn move_dependency(
+    manifest: &mut ManifestDocument,
+    table: &str,
+    package: &str,
+    from: &str,
+    to: &str,
+    normalize: fn(&str) -> String,
+) -> Result<(), Error> {
+    if from == to {
+        return Ok(());
+    }
+
+    let target_has_package = manifest
+        .feature_table(to)
+        .and_then(|feature| feature.get(table))
+        .and_then(Item::as_table_like)
+        .and_then(|deps| find_key(deps, package, normalize))
+        .is_some();
+    if target_has_package {
+        return Err(miette::miette!("Feature '{to}' already depends on '{package}'").into());
+    }
+
+    let not_found = || miette::miette!("Feature '{from}' doesn't depend on '{package}'");
+    let deps = manifest
+        .feature_table_mut(from)?
+        .get_mut(table)
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(not_found)?;
+    let name = find_key(deps, package, normalize).ok_or_else(not_found)?;
+    let key = deps.key(&name).cloned().ok_or_else(not_found)?;
+    let spec = deps.remove(&name).ok_or_else(not_found)?;
+
+    let feature = manifest.feature_table_mut(to)?;
+    manifest::get_or_insert_table(feature, table)?.insert_formatted(&key, spec);
+
+    Ok(())
+}
+
+/// Name of the entry in `deps` that refers to `package`
+pub(crate) fn find_key(
+    deps: &dyn TableLike,
+    package: &str,
+    normalize: fn(&str) -> String,
+) -> Option<String> {
+    let package = normalize(package);
+    deps.iter()
+        .map(|(name, _)| name)
+        .find(|name| normalize(name) == package)
+        .map(str::to_string)
+}
+
+pub(crate) fn normalize_conda_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Normalized pypi name, which treats `-`, `_` and `.` the same (PEP 503)
+pub(crate) fn normalize_pypi_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for part in name
+        .trim()
+        .split(['-', '_', '.'])
+        .filter(|part| !part.is_empty())
+    {
+        if !normalized.is_empty() {
+            normalized.push('-');
+        }
+        normalized.push_str(&part.to_lowercase());
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::DEFAULT_FEATURE;
+
+    fn parse(contents: &str) -> ManifestDocument {
+        ManifestDocument::parse(PathBuf::from("pixi.toml"), contents).unwrap()
+    }
+
+    #[test]
+    fn move_conda_dependency_to_feature() {
+        let mut manifest = parse(
+            r#"[dependencies]
+python = "3.12.*"
+pytest = { version = ">=8", build = "pyhd8ed1ab_0" }
+
+[feature.test.dependencies]
+coverage = "*"
+"#,
+        );
+
+        move_dependency(
+            &mut manifest,
+            "dependencies",
+            "pytest",
+            DEFAULT_FEATURE,
+            "test",
+            normalize_conda_name,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[dependencies]
+python = "3.12.*"
+
+[feature.test.dependencies]
+coverage = "*"
+pytest = { version = ">=8", build = "pyhd8ed1ab_0" }
+"#
+        );
+    }
+
+    #[test]
+    fn move_pypi_dependency_with_normalized_name() {
+        let mut manifest = parse(
+            r#"[feature.dev.pypi-dependencies]
+Typing_Extensions = ">=4"
+"#,
+        );
+
+        move_dependency(
+            &mut manifest,
+            "pypi-dependencies",
+            "typing-extensions",
+            "dev",
+            DEFAULT_FEATURE,
+            normalize_pypi_name,
+        )
+        .unwrap();
+
+        let deps = manifest.feature_table(DEFAULT_FEATURE).unwrap()["pypi-dependencies"]
+            .as_table_like()
+            .unwrap();
+        assert_eq!(deps.get("Typing_Extensions").unwrap().as_str(), Some(">=4"));
+    }
+
+    #[test]
+    fn missing_dependency_is_an_error() {
+        let mut manifest = parse(
+            r#"[dependencies]
+python = "3.12.*"
+"#,
+        );
+
+        let result = move_dependency(
+            &mut manifest,
+            "dependencies",
+            "numpy",
+            DEFAULT_FEATURE,
+            "test",
+            normalize_conda_name,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn normalized_names() {
+        assert_eq!(
+            normalize_pypi_name("Typing_Extensions"),
+            "typing-extensions"
+        );
+        assert_eq!(normalize_pypi_name("zope..interface"), "zope-interface");
+    }
+}
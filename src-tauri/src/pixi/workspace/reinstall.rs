@@ -1,25 +1,301 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::{
     error::Error,
+    pixi::workspace::clean,
+    tauri_interface::run_with_progress,
     utils::{self, spawn_local},
 };
-use pixi_api::{core::environment::LockFileUsage, workspace::ReinstallOptions};
+use pixi_api::{
+    core::environment::LockFileUsage,
+    workspace::{Package, PackageKind, ReinstallEnvironment, ReinstallOptions, ReinstallPackages},
+};
+use serde::Serialize;
 use tauri::{Runtime, Window};
 
+/// What a reinstall would do, see `reinstall` with `dry_run`
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ReinstallPlan {
+    pub environments: Vec<EnvironmentPlan>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct EnvironmentPlan {
+    pub environment: String,
+    /// Installed conda packages that get removed
+    pub remove: Vec<PlannedPackage>,
+    /// Conda packages that aren't in the package cache yet
+    pub download: Vec<PlannedPackage>,
+    /// Locked packages that get installed
+    pub link: Vec<PlannedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedPackage {
+    pub name: String,
+    pub version: String,
+    pub build: Option<String>,
+}
+
+/// Reinstalls the environments, or with `dry_run` only returns what would change.
+///
+/// pixi_api doesn't expose the install transaction, so the plan compares the lock file with the
+/// installed conda packages. Getting the locked packages may update the lock file like the
+/// reinstall would, but the environments stay untouched.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn reinstall<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     options: ReinstallOptions,
     lock_file_usage: LockFileUsage,
-) -> Result<(), Error> {
+    operation_id: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Option<ReinstallPlan>, Error> {
     spawn_local(move || async move {
-        utils::workspace_context(window, workspace)?
-            .reinstall(options, lock_file_usage)
-            .await?;
+        let ctx = utils::workspace_context(window.clone(), workspace.clone())?;
+
+        if dry_run.unwrap_or(false) {
+            let plan = run_with_progress(
+                &window,
+                operation_id,
+                "solve",
+                "Planning the reinstall",
+                plan(&ctx, &workspace, &options, lock_file_usage),
+            )
+            .await;
+            return plan.ok_or_else(Error::cancelled)?.map(Some);
+        }
 
-        Ok(())
+        run_with_progress(
+            &window,
+            operation_id,
+            "install",
+            "Reinstalling the environments",
+            ctx.reinstall(options, lock_file_usage),
+        )
+        .await
+        .ok_or_else(Error::cancelled)??;
+
+        Ok(None)
     })
     .await
 }
+
+/// The parts of pixi's `Package` the plan needs
+struct LockedPackage {
+    name: String,
+    version: String,
+    build: Option<String>,
+    is_conda: bool,
+    file_name: Option<String>,
+}
+
+impl From<Package> for LockedPackage {
+    fn from(package: Package) -> Self {
+        Self {
+            is_conda: package.kind == PackageKind::Conda,
+            name: package.name,
+            version: package.version,
+            build: package.build,
+            file_name: package.file_name,
+        }
+    }
+}
+
+async fn plan<I: pixi_api::Interface>(
+    ctx: &pixi_api::WorkspaceContext<I>,
+    workspace: &Path,
+    options: &ReinstallOptions,
+    lock_file_usage: LockFileUsage,
+) -> Result<ReinstallPlan, Error> {
+    let workspace = utils::workspace(workspace.to_path_buf())?;
+
+    let environments = match &options.reinstall_environments {
+        ReinstallEnvironment::All => workspace
+            .environments()
+            .iter()
+            .map(|environment| environment.name().to_string())
+            .collect(),
+        ReinstallEnvironment::Some(environments) => environments.clone(),
+        ReinstallEnvironment::Default => {
+            vec![workspace.default_environment().name().to_string()]
+        }
+    };
+    // Reinstalling without naming packages reinstalls all of them
+    let packages: Option<Vec<String>> = match &options.reinstall_packages {
+        ReinstallPackages::Some(packages) => Some(packages.iter().cloned().collect()),
+        ReinstallPackages::None | ReinstallPackages::All => None,
+    };
+    let package_cache = clean::cache_dir().map(|cache| cache.join("pkgs"));
+
+    let mut plan = ReinstallPlan::default();
+    for environment in environments {
+        let name = utils::parse_environment_name(&environment)?;
+        let prefix = workspace
+            .environment(&name)
+            .ok_or_else(|| miette::miette!("Unknown environment '{environment}'"))?
+            .dir();
+
+        let locked = ctx
+            .list_packages(
+                None,
+                None,
+                Some(environment.clone()),
+                false,
+                true,
+                lock_file_usage,
+            )
+            .await?
+            .into_iter()
+            .map(LockedPackage::from)
+            .collect();
+
+        plan.environments.push(plan_environment(
+            environment,
+            installed_packages(&prefix),
+            locked,
+            packages.as_deref(),
+            |file_name| {
+                package_cache
+                    .as_ref()
+                    .is_some_and(|cache| is_cached(cache, file_name))
+            },
+        ));
+    }
+
+    Ok(plan)
+}
+
+fn plan_environment(
+    environment: String,
+    installed: Vec<PlannedPackage>,
+    locked: Vec<LockedPackage>,
+    packages: Option<&[String]>,
+    is_cached: impl Fn(&str) -> bool,
+) -> EnvironmentPlan {
+    let selected =
+        |name: &str| packages.is_none_or(|packages| packages.iter().any(|p| p.as_str() == name));
+
+    let mut plan = EnvironmentPlan {
+        environment,
+        remove: installed
+            .into_iter()
+            .filter(|package| selected(&package.name))
+            .collect(),
+        ..Default::default()
+    };
+    for package in locked.into_iter().filter(|package| selected(&package.name)) {
+        let planned = PlannedPackage {
+            name: package.name,
+            version: package.version,
+            build: package.build,
+        };
+        if package.is_conda && !package.file_name.as_deref().is_some_and(&is_cached) {
+            plan.download.push(planned.clone());
+        }
+        plan.link.push(planned);
+    }
+
+    plan
+}
+
+/// Conda packages of the prefix, from the `conda-meta/<name>-<version>-<build>.json` records
+fn installed_packages(prefix: &Path) -> Vec<PlannedPackage> {
+    let Ok(entries) = fs::read_dir(prefix.join("conda-meta")) else {
+        return Vec::new();
+    };
+
+    let mut packages: Vec<PlannedPackage> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let record = file_name.to_str()?.strip_suffix(".json")?;
+            let mut parts = record.rsplitn(3, '-');
+            let (build, version, name) = (parts.next()?, parts.next()?, parts.next()?);
+            Some(PlannedPackage {
+                name: name.to_string(),
+                version: version.to_string(),
+                build: Some(build.to_string()),
+            })
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+/// Whether the package cache has the extracted package, e.g. `pkgs/python-3.12.0-h1234_0`
+fn is_cached(cache: &Path, file_name: &str) -> bool {
+    let name = file_name
+        .strip_suffix(".conda")
+        .or_else(|| file_name.strip_suffix(".tar.bz2"))
+        .unwrap_or(file_name);
+    cache.join(name).is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(name: &str, version: &str) -> PlannedPackage {
+        PlannedPackage {
+            name: name.into(),
+            version: version.into(),
+            build: Some("h0_0".into()),
+        }
+    }
+
+    fn locked(name: &str, version: &str, kind: PackageKind) -> LockedPackage {
+        let is_conda = kind == PackageKind::Conda;
+        LockedPackage {
+            name: name.into(),
+            version: version.into(),
+            build: is_conda.then(|| "h0_0".into()),
+            is_conda,
+            file_name: Some(format!("{name}-{version}-h0_0.conda")),
+        }
+    }
+
+    #[test]
+    fn whole_environment() {
+        let plan = plan_environment(
+            "default".into(),
+            vec![installed("python", "3.11.0"), installed("zlib", "1.3")],
+            vec![
+                locked("python", "3.12.0", PackageKind::Conda),
+                locked("zlib", "1.3", PackageKind::Conda),
+                locked("requests", "2.32.0", PackageKind::Pypi),
+            ],
+            None,
+            |file_name| file_name.starts_with("zlib-"),
+        );
+
+        let names = |packages: &[PlannedPackage]| -> Vec<String> {
+            packages.iter().map(|p| p.name.clone()).collect()
+        };
+        assert_eq!(names(&plan.remove), ["python", "zlib"]);
+        assert_eq!(names(&plan.download), ["python"]);
+        assert_eq!(names(&plan.link), ["python", "zlib", "requests"]);
+    }
+
+    #[test]
+    fn selected_packages_only() {
+        let packages = vec!["zlib".to_string()];
+        let plan = plan_environment(
+            "default".into(),
+            vec![installed("python", "3.12.0"), installed("zlib", "1.3")],
+            vec![
+                locked("python", "3.12.0", PackageKind::Conda),
+                locked("zlib", "1.3", PackageKind::Conda),
+            ],
+            Some(&packages),
+            |_| true,
+        );
+
+        assert_eq!(plan.remove, vec![installed("zlib", "1.3")]);
+        assert!(plan.download.is_empty());
+        assert_eq!(plan.link.len(), 1);
+        assert_eq!(plan.link[0].name, "zlib");
+    }
+}
@@ -3,15 +3,21 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use indexmap::IndexMap;
+use miette::{Context, IntoDiagnostic};
 use pixi_api::core::workspace::PypiDeps;
 use pixi_api::manifest::SpecType;
 use pixi_api::pep508::Requirement;
 use pixi_api::pypi_spec::PypiPackageName;
 use pixi_api::rattler_conda_types::{MatchSpec, PackageName};
 use pixi_api::workspace::DependencyOptions;
+use serde::Serialize;
 use tauri::{Runtime, Window};
+use toml_edit::{Item, TableLike};
 
 use crate::error::Error;
+use crate::manifest::ManifestDocument;
+use crate::pixi::workspace::add::{parse_conda_specs, parse_pypi_specs, scope_dependency_options};
+use crate::pixi::workspace::move_dep::{find_key, normalize_conda_name, normalize_pypi_name};
 use crate::utils::{self, spawn_local};
 
 #[tauri::command]
@@ -19,16 +25,22 @@ pub async fn remove_conda_deps<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     specs: HashMap<String, MatchSpec>,
-    dep_options: DependencyOptions,
+    mut dep_options: DependencyOptions,
+    feature: Option<String>,
+    platform: Option<String>,
 ) -> Result<(), Error> {
     spawn_local(move || async move {
+        scope_dependency_options(&mut dep_options, feature, platform)?;
+
         let specs: IndexMap<PackageName, MatchSpec> = specs
             .into_iter()
             .map(|(name, spec)| {
-                let package_name = PackageName::from_str(&name).unwrap();
-                (package_name, spec)
+                let package_name = PackageName::from_str(&name)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("Invalid package name '{name}'"))?;
+                Ok((package_name, spec))
             })
-            .collect();
+            .collect::<Result<_, Error>>()?;
 
         Ok(utils::workspace_context(window, workspace)?
             .remove_conda_deps(specs, SpecType::Run, dep_options)
@@ -60,3 +72,238 @@ pub async fn remove_pypi_deps<R: Runtime>(
     })
     .await
 }
+
+/// Removes several conda dependencies with a single manifest write, nothing is removed if any of
+/// the specs is invalid
+#[tauri::command]
+pub async fn remove_conda_deps_batch<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    specs: Vec<String>,
+    dep_options: DependencyOptions,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let specs = parse_conda_specs(&specs)?;
+
+        Ok(utils::workspace_context(window, workspace)?
+            .remove_conda_deps(specs, SpecType::Run, dep_options)
+            .await
+            .map_err(miette::Report::new)?)
+    })
+    .await
+}
+
+/// Removes several pypi dependencies with a single manifest write, nothing is removed if any of
+/// the requirements is invalid
+#[tauri::command]
+pub async fn remove_pypi_deps_batch<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    specs: Vec<String>,
+    dep_options: DependencyOptions,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        let pypi_deps: PypiDeps = parse_pypi_specs(&specs)?
+            .into_iter()
+            .map(|(name, req)| (name, (req, None, None)))
+            .collect();
+
+        utils::workspace_context(window, workspace)?
+            .remove_pypi_deps(pypi_deps, dep_options)
+            .await
+            .map_err(miette::Report::new)?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// A place in the manifest a dependency was removed from, `platform` is set for the dependencies
+/// of a `[target.<platform>]` table
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DependencyLocation {
+    pub feature: String,
+    pub platform: Option<String>,
+}
+
+/// Removes the conda dependency from every feature and target that declares it, with a single
+/// manifest write
+#[tauri::command]
+pub async fn remove_conda_dep_everywhere<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    package: String,
+) -> Result<Vec<DependencyLocation>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let removed = remove_dependency_everywhere(
+        &mut manifest,
+        "dependencies",
+        &package,
+        normalize_conda_name,
+    )?;
+    manifest.save()?;
+
+    Ok(removed)
+}
+
+/// Removes the pypi dependency from every feature and target that declares it, with a single
+/// manifest write. The `[project.dependencies]` of a `pyproject.toml` are left as they are.
+#[tauri::command]
+pub async fn remove_pypi_dep_everywhere<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    package: String,
+) -> Result<Vec<DependencyLocation>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let removed = remove_dependency_everywhere(
+        &mut manifest,
+        "pypi-dependencies",
+        &package,
+        normalize_pypi_name,
+    )?;
+    manifest.save()?;
+
+    Ok(removed)
+}
+
+fn remove_dependency_everywhere(
+    manifest: &mut ManifestDocument,
+    table: &str,
+    package: &str,
+    normalize: fn(&str) -> String,
+) -> Result<Vec<DependencyLocation>, Error> {
+    let mut removed = Vec::new();
+    manifest.for_each_feature_mut(|feature, feature_table| {
+        if remove_from(feature_table, table, package, normalize) {
+            removed.push(DependencyLocation {
+                feature: feature.to_string(),
+                platform: None,
+            });
+        }
+
+        let Some(targets) = feature_table
+            .get_mut("target")
+            .and_then(Item::as_table_like_mut)
+        else {
+            return;
+        };
+        for (platform, target) in targets.iter_mut() {
+            if let Some(target) = target.as_table_like_mut()
+                && remove_from(target, table, package, normalize)
+            {
+                removed.push(DependencyLocation {
+                    feature: feature.to_string(),
+                    platform: Some(platform.get().to_string()),
+                });
+            }
+        }
+    })?;
+
+    if removed.is_empty() {
+        return Err(miette::miette!("No feature depends on '{package}'").into());
+    }
+
+    Ok(removed)
+}
+
+/// Removes `package` from the `table` of the dependencies in `parent`, returns whether it was there
+fn remove_from(
+    parent: &mut dyn TableLike,
+    table: &str,
+    package: &str,
+    normalize: fn(&str) -> String,
+) -> bool {
+    let Some(deps) = parent.get_mut(table).and_then(Item::as_table_like_mut) else {
+        return false;
+    };
+    match find_key(deps, package, normalize) {
+        Some(name) => deps.remove(&name).is_some(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::DEFAULT_FEATURE;
+
+    fn parse(contents: &str) -> ManifestDocument {
+        ManifestDocument::parse(PathBuf::from("pixi.toml"), contents).unwrap()
+    }
+
+    #[test]
+    fn dependency_is_removed_from_all_features() {
+        let mut manifest = parse(
+            r#"[dependencies]
+python = "3.12.*"
+pytest = ">=8"
+
+[feature.test.dependencies]
+coverage = "*"
+
+[feature.test.target.linux-64.dependencies]
+PyTest = "*"
+
+[feature.lint.dependencies]
+ruff = "*"
+"#,
+        );
+
+        let removed = remove_dependency_everywhere(
+            &mut manifest,
+            "dependencies",
+            "pytest",
+            normalize_conda_name,
+        )
+        .unwrap();
+
+        assert_eq!(
+            removed,
+            [
+                DependencyLocation {
+                    feature: DEFAULT_FEATURE.to_string(),
+                    platform: None,
+                },
+                DependencyLocation {
+                    feature: "test".to_string(),
+                    platform: Some("linux-64".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            manifest.to_string(),
+            r#"[dependencies]
+python = "3.12.*"
+
+[feature.test.dependencies]
+coverage = "*"
+
+[feature.test.target.linux-64.dependencies]
+
+[feature.lint.dependencies]
+ruff = "*"
+"#
+        );
+    }
+
+    #[test]
+    fn missing_dependency_is_an_error() {
+        let mut manifest = parse(
+            r#"[dependencies]
+python = "3.12.*"
+"#,
+        );
+
+        let result = remove_dependency_everywhere(
+            &mut manifest,
+            "dependencies",
+            "numpy",
+            normalize_conda_name,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,36 +1,130 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    TauriInterface,
     error::Error,
+    manifest::{self, ManifestDocument},
+    pixi::workspace::move_dep::normalize_pypi_name,
+    state::AppState,
     utils::{self},
 };
+use futures::{StreamExt, stream};
+use indexmap::IndexMap;
 use miette::{Context, IntoDiagnostic};
 use pixi_api::{
-    manifest::FeaturesExt,
+    WorkspaceContext,
+    manifest::{EnvironmentName, FeaturesExt, HasFeaturesIter},
     rattler_conda_types::{
-        MatchSpec, ParseStrictness, ParseStrictnessWithNameMatcher, Platform, RepoDataRecord,
+        Channel, MatchSpec, NamedChannelOrUrl, PackageName, ParseStrictness,
+        ParseStrictnessWithNameMatcher, Platform, RepoDataRecord,
     },
+    spec::PixiSpec,
 };
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
 use tauri::{Runtime, Window};
+use toml_edit::Item;
+use url::Url;
+
+const PYPI_INDEX_URL: &str = "https://pypi.org/simple/";
+const PYPI_SEARCH_LIMIT: usize = 20;
+const PYPI_SEARCH_MAX_LIMIT: usize = 100;
+/// Details of this many search results are fetched at the same time
+const PYPI_DETAILS_CONCURRENCY: usize = 8;
+const PYPI_INDEX_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Prefer the JSON API of the simple repository (PEP 691) over the HTML page
+const SIMPLE_INDEX_CONTENT_TYPES: &str = "application/vnd.pypi.simple.v1+json, text/html;q=0.1";
+
+/// A page of search results, `total_count` is the number of packages across all pages
+#[derive(Serialize)]
+pub struct SearchResults {
+    packages: Vec<SearchPackage>,
+    total_count: usize,
+}
+
+/// The latest version of a package together with all versions that are available
+#[derive(Serialize)]
+pub struct SearchPackage {
+    #[serde(flatten)]
+    metadata: PackageMetadata,
+    /// Newest first
+    versions: Vec<String>,
+    record: RepoDataRecord,
+}
+
+#[derive(Serialize)]
+pub struct PackageMetadata {
+    name: String,
+    version: String,
+    build: String,
+    subdir: String,
+    license: Option<String>,
+    size_bytes: Option<u64>,
+    /// Milliseconds since the Unix epoch
+    timestamp: Option<i64>,
+}
+
+impl From<&RepoDataRecord> for PackageMetadata {
+    fn from(record: &RepoDataRecord) -> Self {
+        let package = &record.package_record;
+        Self {
+            name: package.name.as_source().to_string(),
+            version: package.version.to_string(),
+            build: package.build.clone(),
+            subdir: package.subdir.clone(),
+            license: package.license.clone(),
+            size_bytes: package.size,
+            timestamp: timestamp(record),
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch, serialized the same way as in the repodata
+fn timestamp(record: &RepoDataRecord) -> Option<i64> {
+    serde_json::to_value(record.package_record.timestamp.as_ref())
+        .ok()
+        .and_then(|timestamp| timestamp.as_i64())
+}
+
+/// A build of a package to pick a pin from, see [`search_versions`]
+#[derive(Serialize)]
+pub struct PackageVersion {
+    #[serde(flatten)]
+    metadata: PackageMetadata,
+    channel: Option<String>,
+    depends: Vec<String>,
+}
+
+impl From<&RepoDataRecord> for PackageVersion {
+    fn from(record: &RepoDataRecord) -> Self {
+        Self {
+            metadata: PackageMetadata::from(record),
+            channel: record.channel.clone(),
+            depends: record.package_record.depends.clone(),
+        }
+    }
+}
 
 #[tauri::command]
 pub async fn search_wildcard<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     package_name_filter: &str,
-) -> Result<Option<Vec<RepoDataRecord>>, Error> {
+    channels: Option<Vec<NamedChannelOrUrl>>,
+    platform: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<SearchResults, Error> {
     let ctx = utils::workspace_context(window, workspace)?;
 
-    let channels = ctx
-        .workspace()
-        .default_environment()
-        .channels()
-        .into_iter()
-        .cloned()
-        .map(|channel| channel.into_channel(&ctx.workspace().channel_config()))
-        .collect::<Result<_, _>>()
-        .into_diagnostic()
-        .wrap_err("Failed to parse channels")?;
+    let channels = search_channels(&ctx, channels)?;
+    let platforms = search_platforms(platform)?;
 
     let match_spec = MatchSpec::from_str(
         package_name_filter,
@@ -41,22 +135,97 @@ pub async fn search_wildcard<R: Runtime>(
     )
     .into_diagnostic()?;
 
-    let packages = ctx
-        .search(
-            match_spec,
-            channels,
-            vec![Platform::current(), Platform::NoArch],
-        )
-        .await?;
+    // The search API returns all records at once, so the page is only cut out afterwards
+    let records = ctx.search(match_spec, channels, platforms).await?;
+
+    Ok(paginate(group_by_package(records), limit, offset))
+}
+
+/// All builds of a single package, newest version first and the newest build of a version
+/// first. Only the first `limit` builds are returned if it's given.
+#[tauri::command]
+pub async fn search_versions<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    name: PackageName,
+    channels: Option<Vec<NamedChannelOrUrl>>,
+    platform: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PackageVersion>, Error> {
+    let ctx = utils::workspace_context(window, workspace)?;
+
+    let channels = search_channels(&ctx, channels)?;
+    let platforms = search_platforms(platform)?;
+    let match_spec = MatchSpec::from_str(
+        name.as_source(),
+        ParseStrictnessWithNameMatcher::from(ParseStrictness::Strict),
+    )
+    .into_diagnostic()?;
+
+    let mut records = ctx.search(match_spec, channels, platforms).await?;
+    records.sort_by(|a, b| {
+        b.package_record
+            .version
+            .cmp(&a.package_record.version)
+            .then_with(|| timestamp(b).cmp(&timestamp(a)))
+    });
+
+    Ok(records
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(PackageVersion::from)
+        .collect())
+}
+
+/// Groups the records by package, keeping the order in which the packages were found
+fn group_by_package(records: Vec<RepoDataRecord>) -> Vec<SearchPackage> {
+    let mut packages: IndexMap<PackageName, Vec<RepoDataRecord>> = IndexMap::new();
+    for record in records {
+        packages
+            .entry(record.package_record.name.clone())
+            .or_default()
+            .push(record);
+    }
+
+    packages
+        .into_values()
+        .filter_map(|mut records| {
+            records.sort_by(|a, b| b.package_record.version.cmp(&a.package_record.version));
 
-    let mut seen_packages = HashSet::new();
+            let mut versions: Vec<String> = Vec::new();
+            for record in &records {
+                let version = record.package_record.version.to_string();
+                if !versions.contains(&version) {
+                    versions.push(version);
+                }
+            }
 
-    let deduplicated_packages: Vec<RepoDataRecord> = packages
+            let latest = records.into_iter().next()?;
+            Some(SearchPackage {
+                metadata: PackageMetadata::from(&latest),
+                versions,
+                record: latest,
+            })
+        })
+        .collect()
+}
+
+fn paginate(
+    packages: Vec<SearchPackage>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> SearchResults {
+    let total_count = packages.len();
+    let packages = packages
         .into_iter()
-        .filter(|record| seen_packages.insert(record.package_record.name.clone()))
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
         .collect();
 
-    Ok(Some(deduplicated_packages))
+    SearchResults {
+        packages,
+        total_count,
+    }
 }
 
 #[tauri::command]
@@ -64,26 +233,419 @@ pub async fn search_exact<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     match_spec: MatchSpec,
+    channels: Option<Vec<NamedChannelOrUrl>>,
+    platform: Option<String>,
 ) -> Result<Option<Vec<RepoDataRecord>>, Error> {
     let ctx = utils::workspace_context(window, workspace)?;
 
-    let channels = ctx
-        .workspace()
-        .default_environment()
-        .channels()
+    let channels = search_channels(&ctx, channels)?;
+    let platforms = search_platforms(platform)?;
+
+    Ok(Some(ctx.search(match_spec, channels, platforms).await?))
+}
+
+/// Whether a conda dependency has a build for the platform, see [`check_platform_support`]
+#[derive(Debug, Serialize)]
+pub struct PackageSupport {
+    package: String,
+    available: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlatformSupport {
+    packages: Vec<PackageSupport>,
+    /// Pypi dependencies aren't checked, they can be built from source on any platform
+    skipped_pypi: Vec<String>,
+}
+
+/// Checks for each conda dependency of the environment whether its channels have a build for
+/// `platform` (or `noarch`) that satisfies the version specs of all features. Meant to be asked
+/// before the platform is added to the workspace.
+#[tauri::command]
+pub async fn check_platform_support<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: EnvironmentName,
+    platform: String,
+) -> Result<PlatformSupport, Error> {
+    let ctx = utils::workspace_context(window, workspace)?;
+
+    let platforms = search_platforms(Some(platform))?;
+    let (features, channels) = {
+        let environment = ctx
+            .workspace()
+            .environment(&environment)
+            .ok_or_else(|| miette::miette!("Environment '{}' not found", environment))?;
+        let features: Vec<_> = environment.features().map(|f| f.name.clone()).collect();
+        let channels = environment.channels().into_iter().cloned().collect();
+        (features, channels)
+    };
+    let channels: Vec<Channel> = search_channels(&ctx, Some(channels))?;
+
+    let mut specs: IndexMap<PackageName, Vec<PixiSpec>> = IndexMap::new();
+    let mut skipped_pypi = Vec::new();
+    for feature in features {
+        let conda = ctx
+            .list_feature_dependencies(feature.clone(), None)
+            .await
+            .unwrap_or_default();
+        for (name, feature_specs) in conda {
+            specs.entry(name).or_default().extend(feature_specs);
+        }
+
+        let pypi = ctx
+            .list_feature_pypi_dependencies(feature, None)
+            .await
+            .unwrap_or_default();
+        skipped_pypi.extend(pypi.into_keys().map(|name| name.as_source().to_string()));
+    }
+    specs.sort_unstable_keys();
+    skipped_pypi.sort_unstable();
+    skipped_pypi.dedup();
+
+    let mut packages = Vec::with_capacity(specs.len());
+    for (name, specs) in specs {
+        let match_spec = MatchSpec::from_str(
+            name.as_source(),
+            ParseStrictnessWithNameMatcher::from(ParseStrictness::Strict),
+        )
+        .into_diagnostic()?;
+        let records = ctx
+            .search(match_spec, channels.clone(), platforms.clone())
+            .await
+            .wrap_err_with(|| format!("Failed to search for '{}'", name.as_source()))?;
+
+        packages.push(PackageSupport {
+            available: records.iter().any(|record| {
+                specs.iter().all(|spec| {
+                    spec.as_version_spec()
+                        .is_none_or(|version| version.matches(&record.package_record.version))
+                })
+            }),
+            package: name.as_source().to_string(),
+        });
+    }
+
+    Ok(PlatformSupport {
+        packages,
+        skipped_pypi,
+    })
+}
+
+/// A package on the pypi index, `version` and `summary` are only known if the index also
+/// supports the JSON API of pypi.org
+#[derive(Serialize)]
+pub struct PypiSearchResult {
+    name: String,
+    version: Option<String>,
+    summary: Option<String>,
+}
+
+#[tauri::command]
+pub async fn search_pypi<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<PypiSearchResult>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+    let index = pypi_index_url(&manifest)?;
+
+    let client = reqwest::Client::new();
+    let cached = state.pypi_index_cache().lock().await.get(&index);
+    let projects = match cached {
+        Some(projects) => projects,
+        None => {
+            let projects = Arc::new(fetch_pypi_projects(&client, &index).await?);
+            state
+                .pypi_index_cache()
+                .lock()
+                .await
+                .insert(index.clone(), projects.clone());
+            projects
+        }
+    };
+
+    let limit = limit
+        .unwrap_or(PYPI_SEARCH_LIMIT)
+        .min(PYPI_SEARCH_MAX_LIMIT);
+    let names = match_pypi_projects(&projects, &query, limit);
+
+    // Packages without details are still results
+    let mut results: Vec<(usize, PypiSearchResult)> = stream::iter(names.into_iter().enumerate())
+        .map(|(rank, name)| {
+            let client = &client;
+            let index = &index;
+            async move {
+                let details = fetch_pypi_details(client, index, &name).await;
+                let (version, summary) =
+                    details.map_or((None, None), |info| (Some(info.version), info.summary));
+                let result = PypiSearchResult {
+                    name,
+                    version,
+                    summary,
+                };
+                (rank, result)
+            }
+        })
+        .buffer_unordered(PYPI_DETAILS_CONCURRENCY)
+        .collect()
+        .await;
+    results.sort_by_key(|(rank, _)| *rank);
+    let results = results.into_iter().map(|(_, result)| result).collect();
+
+    Ok(results)
+}
+
+/// The index from `[pypi-options]`, or pypi.org
+fn pypi_index_url(manifest: &ManifestDocument) -> Result<Url, Error> {
+    let index = manifest
+        .feature_table(manifest::DEFAULT_FEATURE)
+        .and_then(|feature| feature.get("pypi-options"))
+        .and_then(|options| options.get("index-url"))
+        .and_then(Item::as_str)
+        .unwrap_or(PYPI_INDEX_URL);
+
+    // Relative URLs of the index are resolved against the directory
+    let index = format!("{}/", index.trim_end_matches('/'));
+    Ok(Url::parse(&index)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Invalid index URL '{index}'"))?)
+}
+
+#[derive(Deserialize)]
+struct SimpleIndex {
+    projects: Vec<SimpleProject>,
+}
+
+#[derive(Deserialize)]
+struct SimpleProject {
+    name: String,
+}
+
+/// Names of all projects on the index, using the JSON API of the simple repository (PEP 691)
+/// if the index supports it and the HTML page otherwise
+async fn fetch_pypi_projects(client: &reqwest::Client, index: &Url) -> Result<PypiIndex, Error> {
+    let response = client
+        .get(index.clone())
+        .header(ACCEPT, SIMPLE_INDEX_CONTENT_TYPES)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to fetch the package index '{index}'"))?;
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"));
+    let body = response
+        .text()
+        .await
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to read the package index '{index}'"))?;
+
+    if is_json {
+        let index: SimpleIndex = serde_json::from_str(&body)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to parse the package index '{index}'"))?;
+        Ok(PypiIndex {
+            names: index
+                .projects
+                .into_iter()
+                .map(|project| project.name)
+                .collect(),
+            supports_json: true,
+        })
+    } else {
+        Ok(PypiIndex {
+            names: parse_simple_html(&body),
+            supports_json: false,
+        })
+    }
+}
+
+/// Project names from the links of a simple repository page (PEP 503)
+fn parse_simple_html(html: &str) -> Vec<String> {
+    html.split("</a>")
+        .filter_map(|link| link.rsplit_once('>'))
+        .map(|(_, name)| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Projects that start with the query, followed by projects that contain it. Indexes without
+/// the JSON API only get the prefix matches, as they are usually mirrors with fewer guarantees.
+fn match_pypi_projects(index: &PypiIndex, query: &str, limit: usize) -> Vec<String> {
+    let query = normalize_pypi_name(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut prefix_matches = Vec::new();
+    let mut other_matches = Vec::new();
+    for name in &index.names {
+        let normalized = normalize_pypi_name(name);
+        if normalized.starts_with(&query) {
+            prefix_matches.push((normalized.len(), name));
+        } else if index.supports_json && normalized.contains(&query) {
+            other_matches.push((normalized.len(), name));
+        }
+    }
+    // Shorter names are closer to the query, e.g. `numpy` before `numpy-financial`
+    prefix_matches.sort();
+    other_matches.sort();
+
+    prefix_matches
         .into_iter()
-        .cloned()
-        .map(|channel| channel.into_channel(&ctx.workspace().channel_config()))
+        .chain(other_matches)
+        .take(limit)
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct PypiProject {
+    info: PypiProjectInfo,
+}
+
+#[derive(Deserialize)]
+struct PypiProjectInfo {
+    version: String,
+    summary: Option<String>,
+}
+
+/// Latest version and summary from the JSON API at `<host>/pypi/<name>/json`, which pypi.org and
+/// most mirrors next to their simple index provide
+async fn fetch_pypi_details(
+    client: &reqwest::Client,
+    index: &Url,
+    name: &str,
+) -> Option<PypiProjectInfo> {
+    let api = index.join(&format!("../pypi/{name}/json")).ok()?;
+    let response = client
+        .get(api)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .ok()?;
+    let body = response.text().await.ok()?;
+    let project: PypiProject = serde_json::from_str(&body).ok()?;
+
+    Some(PypiProjectInfo {
+        summary: project
+            .info
+            .summary
+            .filter(|summary| !summary.trim().is_empty()),
+        ..project.info
+    })
+}
+
+/// The project names of a pypi index
+pub struct PypiIndex {
+    names: Vec<String>,
+    supports_json: bool,
+}
+
+/// Project lists of pypi indexes, which are too large to fetch for every search
+#[derive(Default)]
+pub struct PypiIndexCache {
+    entries: HashMap<Url, (Instant, Arc<PypiIndex>)>,
+}
+
+impl PypiIndexCache {
+    pub fn get(&self, index: &Url) -> Option<Arc<PypiIndex>> {
+        self.entries
+            .get(index)
+            .filter(|(cached_at, _)| cached_at.elapsed() < PYPI_INDEX_CACHE_TTL)
+            .map(|(_, projects)| projects.clone())
+    }
+
+    pub fn insert(&mut self, index: Url, projects: Arc<PypiIndex>) {
+        self.entries
+            .retain(|_, (cached_at, _)| cached_at.elapsed() < PYPI_INDEX_CACHE_TTL);
+        self.entries.insert(index, (Instant::now(), projects));
+    }
+}
+
+/// The given channels, or the channels of the default environment if there are none
+fn search_channels<R: Runtime, C: FromIterator<Channel>>(
+    ctx: &WorkspaceContext<TauriInterface<R>>,
+    channels: Option<Vec<NamedChannelOrUrl>>,
+) -> Result<C, Error> {
+    let channels = match channels {
+        Some(channels) => channels,
+        None => ctx
+            .workspace()
+            .default_environment()
+            .channels()
+            .into_iter()
+            .cloned()
+            .collect(),
+    };
+
+    let channel_config = ctx.workspace().channel_config();
+    Ok(channels
+        .into_iter()
+        .map(|channel| channel.into_channel(&channel_config))
         .collect::<Result<_, _>>()
         .into_diagnostic()
-        .wrap_err("Failed to parse channels")?;
+        .wrap_err("Failed to parse channels")?)
+}
 
-    Ok(Some(
-        ctx.search(
-            match_spec,
-            channels,
-            vec![Platform::current(), Platform::NoArch],
-        )
-        .await?,
-    ))
+/// The given platform, or the current one, together with `noarch`
+fn search_platforms(platform: Option<String>) -> Result<Vec<Platform>, Error> {
+    let platform = match platform {
+        Some(platform) => Platform::from_str(&platform)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Unknown platform '{platform}'"))?,
+        None => Platform::current(),
+    };
+
+    if platform == Platform::NoArch {
+        Ok(vec![Platform::NoArch])
+    } else {
+        Ok(vec![platform, Platform::NoArch])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(names: &[&str], supports_json: bool) -> PypiIndex {
+        PypiIndex {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            supports_json,
+        }
+    }
+
+    #[test]
+    fn prefix_matches_come_first() {
+        let index = index(&["pandas-stubs", "geopandas", "Pandas", "numpy"], true);
+
+        assert_eq!(
+            match_pypi_projects(&index, "pandas", 10),
+            vec!["Pandas", "pandas-stubs", "geopandas"]
+        );
+        assert_eq!(match_pypi_projects(&index, "pandas", 1), vec!["Pandas"]);
+    }
+
+    #[test]
+    fn html_index_only_matches_prefixes() {
+        let html = r#"<!DOCTYPE html>
+<html><body>
+<a href="/simple/pandas/">pandas</a>
+<a href="/simple/geopandas/">geopandas</a>
+</body></html>"#;
+        let index = PypiIndex {
+            names: parse_simple_html(html),
+            supports_json: false,
+        };
+
+        assert_eq!(index.names, vec!["pandas", "geopandas"]);
+        assert_eq!(match_pypi_projects(&index, "pandas", 10), vec!["pandas"]);
+    }
 }
@@ -1,23 +1,92 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
+use indexmap::IndexMap;
 use pixi_api::manifest::{EnvironmentName, Task, TaskName};
+use serde::{Deserialize, Serialize};
 use tauri::{Runtime, Window};
+use toml_edit::{Array, Formatted, InlineTable, Item, Table, TableLike, Value, value};
 
-use crate::{error::Error, utils};
+use crate::{
+    error::Error,
+    manifest::{self, DEFAULT_FEATURE, ManifestDocument},
+    utils,
+};
+
+/// Name of the table under `[tool.pixi-gui]` that holds the argument types of tasks
+const TASK_ARG_TYPES: &str = "task-arg-types";
+
+/// How the value of a task argument is entered in the GUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskArgType {
+    #[default]
+    String,
+    Path,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskArg {
+    pub name: String,
+    pub default: Option<String>,
+    #[serde(default)]
+    pub arg_type: TaskArgType,
+}
+
+/// A task together with the types of its arguments, which pixi itself doesn't know about
+#[derive(Serialize)]
+pub struct ListedTask {
+    #[serde(flatten)]
+    task: Task,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    arg_types: HashMap<String, TaskArgType>,
+}
 
 #[tauri::command]
 pub async fn list_tasks<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
-) -> Result<HashMap<EnvironmentName, HashMap<TaskName, Task>>, Error> {
-    Ok(utils::workspace_context(window, workspace)?
+) -> Result<HashMap<EnvironmentName, HashMap<TaskName, ListedTask>>, Error> {
+    let ctx = utils::workspace_context(window, workspace)?;
+    let manifest = ManifestDocument::load(ctx.workspace())?;
+
+    Ok(ctx
         .list_tasks(None)
         .await?
         .into_iter()
-        .map(|(environment, (_runnability, tasks))| (environment, tasks))
+        .map(|(environment, (_runnability, tasks))| {
+            let tasks = tasks
+                .into_iter()
+                .map(|(name, task)| {
+                    let arg_types = arg_types(&manifest, name.as_str());
+                    (name, ListedTask { task, arg_types })
+                })
+                .collect();
+            (environment, tasks)
+        })
         .collect())
 }
 
+/// Task settings that `pixi_api` doesn't take when adding a task
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AddTaskOptions {
+    /// Tasks to run first, added to the ones the task already depends on
+    pub depends_on: Option<Vec<String>>,
+    /// Environment variables, merged into the ones of the task
+    pub env: Option<IndexMap<String, String>>,
+    /// Working directory relative to the workspace root, an empty string removes it
+    pub cwd: Option<String>,
+    /// Arguments of the task, replacing existing ones with the same name
+    pub args: Option<Vec<TaskArg>>,
+    /// Task of the feature that gets replaced, e.g. when it's edited. Tasks depending on it are
+    /// left as they are.
+    pub replace: Option<String>,
+}
+
 #[tauri::command]
 pub async fn add_task<R: Runtime>(
     window: Window<R>,
@@ -25,20 +94,1125 @@ pub async fn add_task<R: Runtime>(
     name: String,
     task: Task,
     feature: String,
+    options: Option<AddTaskOptions>,
 ) -> Result<(), Error> {
-    Ok(utils::workspace_context(window, workspace)?
-        .add_task(name.into(), task, feature.into(), None)
-        .await?)
+    let options = options.unwrap_or_default();
+    let located = utils::workspace(workspace.clone())?;
+
+    // Keep the current manifest, so a failing update doesn't leave a half-configured task behind
+    let original = ManifestDocument::load(&located)?;
+
+    // pixi refuses to add a task that already exists, so the replaced one is removed first
+    if let Some(replaced) = &options.replace {
+        let mut manifest = ManifestDocument::load(&located)?;
+        remove_task_entry(&mut manifest, &feature, replaced)?;
+        manifest.save()?;
+    }
+
+    let result: Result<(), Error> = async {
+        let ctx = utils::workspace_context(window, workspace)?;
+        ctx.add_task(name.clone().into(), task, feature.clone().into(), None)
+            .await?;
+
+        let mut manifest = ManifestDocument::load(ctx.workspace())?;
+        configure_task(
+            &mut manifest,
+            ctx.workspace().root(),
+            &feature,
+            &name,
+            options,
+        )?;
+        Ok(manifest.save()?)
+    }
+    .await;
+    if result.is_err() {
+        original.save()?;
+    }
+
+    result
+}
+
+/// Applies the options of a newly added task and validates the resulting entry
+fn configure_task(
+    manifest: &mut ManifestDocument,
+    root: &Path,
+    feature: &str,
+    name: &str,
+    options: AddTaskOptions,
+) -> Result<(), Error> {
+    if let Some(args) = &options.args {
+        if let Some(arg) = args.iter().find(|arg| !is_identifier(&arg.name)) {
+            return Err(
+                miette::miette!("Task argument '{}' must be a valid identifier", arg.name).into(),
+            );
+        }
+        set_arg_types(manifest, name, args)?;
+    }
+
+    let task = task_item_mut(manifest, feature, name)?;
+
+    // The short forms `task = "cmd"` and `task = [dependencies]` have nothing to check
+    let has_options = options.depends_on.is_some()
+        || options.env.is_some()
+        || options.cwd.is_some()
+        || options.args.is_some();
+    if !has_options && !task.is_table_like() {
+        return Ok(());
+    }
+    let task = expand_task(task, name)?;
+
+    if let Some(depends_on) = options.depends_on {
+        add_depends_on(task, depends_on);
+    }
+
+    if let Some(env) = options.env {
+        add_env(task, env);
+    }
+
+    if let Some(args) = options.args {
+        add_args(task, &args);
+    }
+
+    match options.cwd.as_deref() {
+        Some("") => {
+            task.remove("cwd");
+        }
+        Some(cwd) => {
+            task.insert("cwd", value(cwd));
+        }
+        None => {}
+    }
+
+    // The working directory might also come from the task itself, so check whatever ended up in
+    // the manifest
+    if let Some(cwd) = task.get("cwd").and_then(Item::as_str) {
+        let normalized = utils::path_within_workspace(root, Path::new(cwd))?;
+        // Always use forward slashes so the manifest stays portable
+        let normalized = match normalized.to_string_lossy().replace('\\', "/") {
+            cwd if cwd.is_empty() => ".".to_string(),
+            cwd => cwd,
+        };
+        if normalized != cwd {
+            task.insert("cwd", value(normalized));
+        }
+    }
+
+    // A task without a command is an alias that only runs its dependencies
+    if task.get("cmd").is_none_or(is_empty_command) {
+        let has_dependencies = ["depends-on", "depends_on"]
+            .iter()
+            .filter_map(|key| task.get(key))
+            .any(|depends_on| !is_empty_command(depends_on));
+        if !has_dependencies {
+            return Err(miette::miette!(
+                "Task '{name}' needs a command or at least one dependency"
+            )
+            .into());
+        }
+        task.remove("cmd");
+    }
+
+    Ok(())
+}
+
+/// Whether a `cmd` (or `depends-on`) entry is missing its contents
+fn is_empty_command(item: &Item) -> bool {
+    match item.as_value() {
+        Some(Value::String(cmd)) => cmd.value().trim().is_empty(),
+        Some(Value::Array(array)) => array.is_empty(),
+        _ => false,
+    }
 }
 
+/// Removes a task of `feature` (the default feature if `None`).
+///
+/// With `cascade` the tasks that depend on it lose that dependency, otherwise the task isn't
+/// removed if anything depends on it. Returns the tasks whose dependencies were changed.
 #[tauri::command]
 pub async fn remove_task<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     name: String,
-    feature: String,
+    feature: Option<String>,
+    cascade: bool,
+) -> Result<Vec<String>, Error> {
+    let feature = feature.unwrap_or_else(|| DEFAULT_FEATURE.to_string());
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let changed = remove_from_manifest(&mut manifest, &feature, &name, cascade)?;
+    manifest.save()?;
+
+    Ok(changed)
+}
+
+/// Removes the task and, with `cascade`, the dependencies on it, see `remove_task`
+fn remove_from_manifest(
+    manifest: &mut ManifestDocument,
+    feature: &str,
+    name: &str,
+    cascade: bool,
+) -> Result<Vec<String>, Error> {
+    remove_task_entry(manifest, feature, name)?;
+    if cascade {
+        return Ok(remove_dependency(manifest, name)?);
+    }
+
+    // The dependents keep working if another feature still defines the task
+    if !is_defined(manifest, name)? {
+        let dependents = dependents(manifest, name)?;
+        if !dependents.is_empty() {
+            return Err(miette::miette!(
+                "Task '{name}' can't be removed, {} depend on it",
+                dependents.join(", ")
+            )
+            .into());
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Removes the task from the `tasks` table of the feature, leaving everything else as it is
+fn remove_task_entry(
+    manifest: &mut ManifestDocument,
+    feature: &str,
+    name: &str,
+) -> miette::Result<()> {
+    manifest
+        .feature_table_mut(feature)?
+        .get_mut("tasks")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|tasks| tasks.remove(name))
+        .map(|_| ())
+        .ok_or_else(|| miette::miette!("Task '{name}' not found in feature '{feature}'"))
+}
+
+#[tauri::command]
+pub async fn rename_task<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    old: String,
+    new: String,
+    feature: Option<String>,
+) -> Result<(), Error> {
+    validate_task_name(&new)?;
+    if old == new {
+        return Ok(());
+    }
+
+    let feature = feature.unwrap_or_else(|| DEFAULT_FEATURE.to_string());
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    rename_in_manifest(&mut manifest, &old, &new, &feature)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_task_description<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    task: String,
+    description: String,
+    feature: Option<String>,
 ) -> Result<(), Error> {
-    Ok(utils::workspace_context(window, workspace)?
-        .remove_task(vec![name.into()], None, feature.into())
-        .await?)
+    let feature = feature.unwrap_or_else(|| DEFAULT_FEATURE.to_string());
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    set_description(&mut manifest, &feature, &task, &description)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// Sets the description of a task, an empty description removes it
+fn set_description(
+    manifest: &mut ManifestDocument,
+    feature: &str,
+    name: &str,
+    description: &str,
+) -> miette::Result<()> {
+    let task = task_item_mut(manifest, feature, name)?;
+
+    let description = description.trim();
+    if description.is_empty() {
+        if let Some(task) = task.as_table_like_mut() {
+            task.remove("description");
+        }
+    } else {
+        expand_task(task, name)?.insert("description", value(description));
+    }
+
+    Ok(())
+}
+
+fn validate_task_name(name: &str) -> Result<(), Error> {
+    if name.trim().is_empty() {
+        return Err(miette::miette!("Task name must not be empty").into());
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(miette::miette!("Task name '{name}' must not contain whitespace").into());
+    }
+    // `pixi run -x` would treat the name as an option
+    if name.starts_with('-') {
+        return Err(miette::miette!("Task name '{name}' must not start with '-'").into());
+    }
+
+    Ok(())
+}
+
+fn rename_in_manifest(
+    manifest: &mut ManifestDocument,
+    old: &str,
+    new: &str,
+    feature: &str,
+) -> Result<(), Error> {
+    let mut exists = false;
+    let mut defined_in = 0;
+    manifest.for_each_feature_mut(|_, table| {
+        let mut defines_old = false;
+        for_each_tasks_table(table, &mut |tasks| {
+            exists |= tasks.contains_key(new);
+            defines_old |= tasks.contains_key(old);
+        });
+        defined_in += usize::from(defines_old);
+    })?;
+    if exists {
+        return Err(miette::miette!("A task named '{new}' already exists").into());
+    }
+    // Dependencies are referenced by name only, so they can't tell which of the tasks they mean
+    if defined_in > 1 {
+        return Err(miette::miette!(
+            "Task '{old}' is defined in multiple features, rename it in the manifest instead"
+        )
+        .into());
+    }
+
+    let mut renamed = false;
+    for_each_tasks_table(manifest.feature_table_mut(feature)?, &mut |tasks| {
+        renamed |= manifest::rename_key(tasks, old, new);
+    });
+    if !renamed {
+        return Err(miette::miette!("Task '{old}' not found in feature '{feature}'").into());
+    }
+
+    // Keep tasks that depend on the renamed task working
+    manifest.for_each_feature_mut(|_, table| {
+        for_each_tasks_table(table, &mut |tasks| {
+            for (_, task) in tasks.iter_mut() {
+                rename_dependency(task, old, new);
+            }
+        });
+    })?;
+
+    Ok(())
+}
+
+/// Names of the tasks that depend on `name`, in any feature
+fn dependents(manifest: &mut ManifestDocument, name: &str) -> miette::Result<Vec<String>> {
+    let mut dependents = Vec::new();
+    manifest.for_each_feature_mut(|_, table| {
+        for_each_tasks_table(table, &mut |tasks| {
+            for (task_name, task) in tasks.iter_mut() {
+                if dependency_names_of(task).any(|dependency| dependency == name)
+                    && !dependents.iter().any(|d| d == task_name.get())
+                {
+                    dependents.push(task_name.get().to_string());
+                }
+            }
+        });
+    })?;
+    Ok(dependents)
+}
+
+/// Whether any feature defines a task named `name`
+fn is_defined(manifest: &mut ManifestDocument, name: &str) -> miette::Result<bool> {
+    let mut defined = false;
+    manifest.for_each_feature_mut(|_, table| {
+        for_each_tasks_table(table, &mut |tasks| defined |= tasks.contains_key(name));
+    })?;
+    Ok(defined)
+}
+
+/// Removes `name` from the `depends-on` lists of all tasks, unless another feature still defines
+/// a task with that name. Returns the names of the changed tasks.
+fn remove_dependency(manifest: &mut ManifestDocument, name: &str) -> miette::Result<Vec<String>> {
+    if is_defined(manifest, name)? {
+        return Ok(Vec::new());
+    }
+
+    let mut changed = Vec::new();
+    manifest.for_each_feature_mut(|_, table| {
+        for_each_tasks_table(table, &mut |tasks| {
+            for (task_name, task) in tasks.iter_mut() {
+                if strip_dependency(task, name) && !changed.iter().any(|c| c == task_name.get()) {
+                    changed.push(task_name.get().to_string());
+                }
+            }
+        });
+    })?;
+    Ok(changed)
+}
+
+/// Removes the `depends-on` entries referring to `name`, returns whether there were any
+fn strip_dependency(task: &mut Item, name: &str) -> bool {
+    // `depends-on = "task"` short form
+    if let Some(table) = task.as_table_like_mut() {
+        for key in ["depends-on", "depends_on"] {
+            if table.get(key).and_then(Item::as_str) == Some(name) {
+                remove_keeping_format(table, key);
+                return true;
+            }
+        }
+    }
+
+    let Some(depends_on) = depends_on_mut(task) else {
+        return false;
+    };
+    let mut stripped = false;
+    let mut i = 0;
+    while i < depends_on.len() {
+        if depends_on.get(i).and_then(dependency_name) != Some(name) {
+            i += 1;
+            continue;
+        }
+        // The next entry takes over the whitespace, so `["a", "b"]` doesn't become `[ "b"]`
+        let removed = depends_on.remove(i);
+        if let Some(prefix) = removed.decor().prefix().cloned()
+            && let Some(next) = depends_on.get_mut(i)
+        {
+            next.decor_mut().set_prefix(prefix);
+        }
+        stripped = true;
+    }
+
+    if stripped
+        && depends_on.is_empty()
+        && let Some(table) = task.as_table_like_mut()
+    {
+        for key in ["depends-on", "depends_on"] {
+            remove_keeping_format(table, key);
+        }
+    }
+    stripped
+}
+
+/// Removes `key`, the last remaining value of an inline table keeps the space before the `}`
+fn remove_keeping_format(table: &mut dyn TableLike, key: &str) {
+    let was_last = table.iter().last().is_some_and(|(last, _)| last == key);
+    let Some(removed) = table.remove(key) else {
+        return;
+    };
+
+    if was_last
+        && let Some(suffix) = removed.as_value().and_then(|v| v.decor().suffix()).cloned()
+        && let Some((_, last)) = table.iter_mut().last()
+        && let Some(last) = last.as_value_mut()
+    {
+        last.decor_mut().set_suffix(suffix);
+    }
+}
+
+/// Names of the tasks a task depends on
+fn dependency_names_of(task: &Item) -> impl Iterator<Item = &str> {
+    let depends_on = if let Some(array) = task.as_array() {
+        Some(array)
+    } else {
+        task.as_table_like().and_then(|task| {
+            task.get("depends-on")
+                .or_else(|| task.get("depends_on"))
+                .and_then(Item::as_array)
+        })
+    };
+    let single = task
+        .as_table_like()
+        .and_then(|task| task.get("depends-on").or_else(|| task.get("depends_on")))
+        .and_then(Item::as_str);
+
+    depends_on
+        .into_iter()
+        .flat_map(|array| array.iter().filter_map(dependency_name))
+        .chain(single)
+}
+
+/// Calls `f` with every `tasks` table of a feature, including the platform specific ones
+fn for_each_tasks_table(feature: &mut Table, f: &mut dyn FnMut(&mut dyn TableLike)) {
+    if let Some(tasks) = feature.get_mut("tasks").and_then(Item::as_table_like_mut) {
+        f(tasks);
+    }
+
+    if let Some(targets) = feature.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (_, target) in targets.iter_mut() {
+            if let Some(tasks) = target.get_mut("tasks").and_then(Item::as_table_like_mut) {
+                f(tasks);
+            }
+        }
+    }
+}
+
+/// The `depends-on` list of a task, which is the task itself for the `task = [...]` alias form
+fn depends_on_mut(task: &mut Item) -> Option<&mut Array> {
+    if task.is_array() {
+        return task.as_array_mut();
+    }
+
+    let task = task.as_table_like_mut()?;
+    let key = if task.contains_key("depends-on") {
+        "depends-on"
+    } else {
+        "depends_on"
+    };
+    task.get_mut(key)?.as_array_mut()
+}
+
+/// Name of the task a `depends-on` entry refers to (`"name"` or `{ task = "name", ... }`)
+fn dependency_name(dependency: &Value) -> Option<&str> {
+    match dependency {
+        Value::String(name) => Some(name.value()),
+        Value::InlineTable(table) => table.get("task").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+fn rename_dependency(task: &mut Item, old: &str, new: &str) {
+    let Some(depends_on) = depends_on_mut(task) else {
+        return;
+    };
+
+    for dependency in depends_on.iter_mut() {
+        if dependency_name(dependency) != Some(old) {
+            continue;
+        }
+
+        match dependency {
+            Value::InlineTable(table) => {
+                table.insert("task", new.into());
+            }
+            _ => {
+                let decor = dependency.decor().clone();
+                *dependency = new.into();
+                *dependency.decor_mut() = decor;
+            }
+        }
+    }
+}
+
+fn task_item_mut<'a>(
+    manifest: &'a mut ManifestDocument,
+    feature: &str,
+    name: &str,
+) -> miette::Result<&'a mut Item> {
+    manifest
+        .feature_table_mut(feature)?
+        .get_mut("tasks")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|tasks| tasks.get_mut(name))
+        .ok_or_else(|| miette::miette!("Task '{name}' not found in feature '{feature}'"))
+}
+
+/// Returns the manifest entry of a task as a table.
+///
+/// The short forms `task = "cmd"` and `task = [dependencies]` get expanded into
+/// an inline table first, so additional keys can be set on them.
+fn expand_task<'a>(task: &'a mut Item, name: &str) -> miette::Result<&'a mut dyn TableLike> {
+    if let Some(cmd) = task.as_str().map(str::to_string) {
+        let mut table = InlineTable::new();
+        table.insert("cmd", cmd.into());
+        *task = value(table);
+    } else if let Some(depends_on) = task.as_array().cloned() {
+        let mut table = InlineTable::new();
+        table.insert("depends-on", Value::Array(depends_on));
+        *task = value(table);
+    }
+
+    task.as_table_like_mut()
+        .ok_or_else(|| miette::miette!("Task '{name}' has an unexpected format"))
+}
+
+/// Appends tasks to the `depends-on` list, keeping the entries that are already there
+fn add_depends_on(task: &mut dyn TableLike, depends_on: Vec<String>) {
+    if depends_on.is_empty() {
+        return;
+    }
+
+    let key = if task.contains_key("depends_on") {
+        "depends_on"
+    } else {
+        "depends-on"
+    };
+    let item = task.entry(key).or_insert_with(|| value(Array::new()));
+
+    // `depends-on = "task"` is a short form of a list with a single entry
+    if let Some(name) = item.as_str().map(str::to_string) {
+        *item = value(Array::from_iter([name]));
+    }
+
+    let Some(list) = item.as_array_mut() else {
+        return;
+    };
+    for name in depends_on {
+        if !list
+            .iter()
+            .any(|dependency| dependency_name(dependency) == Some(name.as_str()))
+        {
+            list.push(name);
+        }
+    }
+}
+
+/// Adds environment variables to a task, overriding the values of existing ones
+fn add_env(task: &mut dyn TableLike, env: IndexMap<String, String>) {
+    if env.is_empty() {
+        return;
+    }
+
+    let item = task
+        .entry("env")
+        .or_insert_with(|| value(InlineTable::new()));
+    if let Some(table) = item.as_table_like_mut() {
+        for (key, val) in env {
+            table.insert(&key, value(val));
+        }
+    }
+}
+
+/// Adds arguments to a task and references them in its command, so they don't have to be
+/// typed as `{{ name }}` by hand
+fn add_args(task: &mut dyn TableLike, args: &[TaskArg]) {
+    if args.is_empty() {
+        return;
+    }
+
+    if let Some(list) = task
+        .entry("args")
+        .or_insert_with(|| value(Array::new()))
+        .as_array_mut()
+    {
+        for arg in args {
+            let entry = match &arg.default {
+                Some(default) => {
+                    let mut table = InlineTable::new();
+                    table.insert("arg", arg.name.as_str().into());
+                    table.insert("default", default.as_str().into());
+                    Value::InlineTable(table)
+                }
+                None => arg.name.as_str().into(),
+            };
+
+            let existing = list
+                .iter()
+                .position(|existing| arg_name(existing) == Some(arg.name.as_str()));
+            match existing {
+                Some(index) => {
+                    list.replace(index, entry);
+                }
+                None => list.push(entry),
+            }
+        }
+    }
+
+    let is_referenced = |cmd: &str, name: &str| {
+        cmd.contains(&format!("{{{{ {name} }}}}")) || cmd.contains(&format!("{{{{{name}}}}}"))
+    };
+    match task.get_mut("cmd").and_then(Item::as_value_mut) {
+        Some(Value::String(cmd)) => {
+            let mut updated = cmd.value().clone();
+            for arg in args {
+                if !is_referenced(&updated, &arg.name) {
+                    updated.push_str(&format!(" {{{{ {} }}}}", arg.name));
+                }
+            }
+            let decor = cmd.decor().clone();
+            *cmd = Formatted::new(updated);
+            *cmd.decor_mut() = decor;
+        }
+        Some(Value::Array(cmd)) => {
+            for arg in args {
+                let referenced = cmd
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|part| is_referenced(part, &arg.name));
+                if !referenced {
+                    cmd.push(format!("{{{{ {} }}}}", arg.name));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Name of a task argument (`"name"` or `{ arg = "name", ... }`)
+fn arg_name(arg: &Value) -> Option<&str> {
+    match arg {
+        Value::String(name) => Some(name.value()),
+        Value::InlineTable(table) => table.get("arg").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Whether `name` can be used as a variable in a task's command template
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Argument types of a task, only arguments that aren't plain strings are listed
+fn arg_types(manifest: &ManifestDocument, task: &str) -> HashMap<String, TaskArgType> {
+    manifest
+        .gui_table()
+        .and_then(|gui| gui.get(TASK_ARG_TYPES)?.get(task)?.as_table_like())
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|(arg, arg_type)| match arg_type.as_str()? {
+                    "path" => Some((arg.to_string(), TaskArgType::Path)),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Stores the argument types of a task next to the pixi configuration, as pixi has no field for
+/// them
+fn set_arg_types(
+    manifest: &mut ManifestDocument,
+    task: &str,
+    args: &[TaskArg],
+) -> miette::Result<()> {
+    let all_types = manifest::get_or_insert_table(manifest.gui_table_mut()?, TASK_ARG_TYPES)?;
+    let types = manifest::get_or_insert_table(all_types, task)?;
+    for arg in args {
+        match arg.arg_type {
+            TaskArgType::String => {
+                types.remove(&arg.name);
+            }
+            TaskArgType::Path => {
+                types.insert(&arg.name, value("path"));
+            }
+        }
+    }
+
+    if types.is_empty() {
+        all_types.remove(task);
+    }
+    if all_types.is_empty() {
+        manifest.gui_table_mut()?.remove(TASK_ARG_TYPES);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: &str = "/workspace";
+
+    fn parse(contents: &str) -> ManifestDocument {
+        ManifestDocument::parse(PathBuf::from(ROOT).join("pixi.toml"), contents).unwrap()
+    }
+
+    fn configure(
+        manifest: &mut ManifestDocument,
+        name: &str,
+        options: AddTaskOptions,
+    ) -> Result<(), Error> {
+        configure_task(manifest, Path::new(ROOT), DEFAULT_FEATURE, name, options)
+    }
+
+    fn task<'a>(manifest: &'a ManifestDocument, name: &str) -> &'a Item {
+        &manifest.feature_table(DEFAULT_FEATURE).unwrap()["tasks"][name]
+    }
+
+    fn dependency_names(manifest: &ManifestDocument, name: &str) -> Vec<String> {
+        task(manifest, name)["depends-on"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(dependency_name)
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn task_depending_on_two_others() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+build = "cargo build"
+lint = "cargo clippy"
+check = "cargo check"
+"#,
+        );
+
+        let options = AddTaskOptions {
+            depends_on: Some(vec!["build".into(), "lint".into()]),
+            ..Default::default()
+        };
+        configure(&mut manifest, "check", options).unwrap();
+
+        let manifest = parse(&manifest.to_string());
+        assert_eq!(
+            task(&manifest, "check")["cmd"].as_str(),
+            Some("cargo check")
+        );
+        assert_eq!(dependency_names(&manifest, "check"), ["build", "lint"]);
+    }
+
+    #[test]
+    fn depends_on_keeps_existing_entries() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+build = "cargo build"
+lint = "cargo clippy"
+check = { cmd = "cargo check", depends-on = [{ task = "build", args = ["--release"] }] }
+"#,
+        );
+
+        let options = AddTaskOptions {
+            depends_on: Some(vec!["build".into(), "lint".into()]),
+            ..Default::default()
+        };
+        configure(&mut manifest, "check", options).unwrap();
+
+        assert_eq!(dependency_names(&manifest, "check"), ["build", "lint"]);
+        assert!(manifest.to_string().contains(r#"args = ["--release"]"#));
+    }
+
+    #[test]
+    fn empty_task_is_rejected() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+empty = ""
+"#,
+        );
+
+        let options = AddTaskOptions {
+            depends_on: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(configure(&mut manifest, "empty", options).is_err());
+    }
+
+    #[test]
+    fn env_is_merged() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+start = { cmd = "python app.py", env = { DEBUG = "0", PORT = "8000" } }
+"#,
+        );
+
+        let options = AddTaskOptions {
+            env: Some(IndexMap::from([("DEBUG".into(), "1".into())])),
+            ..Default::default()
+        };
+        configure(&mut manifest, "start", options).unwrap();
+
+        let env = &task(&manifest, "start")["env"];
+        assert_eq!(env["DEBUG"].as_str(), Some("1"));
+        assert_eq!(env["PORT"].as_str(), Some("8000"));
+    }
+
+    #[test]
+    fn cwd_is_normalized() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+test = "pytest"
+lint = "ruff check"
+"#,
+        );
+
+        let options = AddTaskOptions {
+            cwd: Some("./src/../tests".into()),
+            ..Default::default()
+        };
+        configure(&mut manifest, "test", options).unwrap();
+        assert_eq!(task(&manifest, "test")["cwd"].as_str(), Some("tests"));
+
+        let options = AddTaskOptions {
+            cwd: Some(".".into()),
+            ..Default::default()
+        };
+        configure(&mut manifest, "lint", options).unwrap();
+        assert_eq!(task(&manifest, "lint")["cwd"].as_str(), Some("."));
+    }
+
+    #[test]
+    fn cwd_of_the_task_is_validated() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+escape = { cmd = "ls", cwd = "../elsewhere" }
+"#,
+        );
+
+        assert!(configure(&mut manifest, "escape", AddTaskOptions::default()).is_err());
+    }
+
+    const RENAME_MANIFEST: &str = r#"
+[tasks]
+# Compile everything
+build = "cargo build"
+test = { cmd = "cargo test", depends-on = ["build"] }
+release = { cmd = "cargo build --release", depends-on = [{ task = "build", args = ["--locked"] }] }
+all = ["build", "test"]
+
+[target.win-64.tasks]
+# Needs the MSVC toolchain
+build = "cargo build --target x86_64-pc-windows-msvc"
+package = { cmd = "makensis installer.nsi", depends-on = ["build"] }
+
+[feature.docs.tasks]
+docs = { cmd = "mdbook build", depends-on = ["build"] }
+"#;
+
+    #[test]
+    fn rename_keeps_references_and_formatting() {
+        let mut manifest = parse(RENAME_MANIFEST);
+
+        rename_in_manifest(&mut manifest, "build", "compile", DEFAULT_FEATURE).unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"
+[tasks]
+# Compile everything
+compile = "cargo build"
+test = { cmd = "cargo test", depends-on = ["compile"] }
+release = { cmd = "cargo build --release", depends-on = [{ task = "compile", args = ["--locked"] }] }
+all = ["compile", "test"]
+
+[target.win-64.tasks]
+# Needs the MSVC toolchain
+compile = "cargo build --target x86_64-pc-windows-msvc"
+package = { cmd = "makensis installer.nsi", depends-on = ["compile"] }
+
+[feature.docs.tasks]
+docs = { cmd = "mdbook build", depends-on = ["compile"] }
+"#
+        );
+    }
+
+    #[test]
+    fn rename_to_existing_task_is_rejected() {
+        let mut manifest = parse(RENAME_MANIFEST);
+        assert!(rename_in_manifest(&mut manifest, "build", "docs", DEFAULT_FEATURE).is_err());
+        assert!(rename_in_manifest(&mut manifest, "missing", "other", DEFAULT_FEATURE).is_err());
+    }
+
+    #[test]
+    fn rename_of_ambiguous_task_is_rejected() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+build = "cargo build"
+
+[feature.docs.tasks]
+build = "mdbook build"
+"#,
+        );
+        assert!(rename_in_manifest(&mut manifest, "build", "compile", DEFAULT_FEATURE).is_err());
+    }
+
+    #[test]
+    fn invalid_task_names() {
+        assert!(validate_task_name("build-docs").is_ok());
+        assert!(validate_task_name("").is_err());
+        assert!(validate_task_name("build docs").is_err());
+        assert!(validate_task_name("--build").is_err());
+    }
+
+    #[test]
+    fn set_and_remove_description() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+start = "python app.py"
+"#,
+        );
+
+        set_description(&mut manifest, DEFAULT_FEATURE, "start", "Start the server").unwrap();
+        assert_eq!(
+            task(&manifest, "start")["description"].as_str(),
+            Some("Start the server")
+        );
+
+        set_description(&mut manifest, DEFAULT_FEATURE, "start", "").unwrap();
+        assert!(task(&manifest, "start").get("description").is_none());
+        assert_eq!(
+            task(&manifest, "start")["cmd"].as_str(),
+            Some("python app.py")
+        );
+    }
+
+    #[test]
+    fn args_with_types() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+convert = "python convert.py"
+"#,
+        );
+
+        let options = AddTaskOptions {
+            args: Some(vec![
+                TaskArg {
+                    name: "input".into(),
+                    default: None,
+                    arg_type: TaskArgType::Path,
+                },
+                TaskArg {
+                    name: "format".into(),
+                    default: Some("png".into()),
+                    arg_type: TaskArgType::String,
+                },
+            ]),
+            ..Default::default()
+        };
+        configure(&mut manifest, "convert", options).unwrap();
+
+        let convert = task(&manifest, "convert");
+        assert_eq!(
+            convert["cmd"].as_str(),
+            Some("python convert.py {{ input }} {{ format }}")
+        );
+        assert_eq!(
+            convert["args"].as_array().unwrap().to_string(),
+            r#"["input", { arg = "format", default = "png" }]"#
+        );
+        assert_eq!(
+            arg_types(&manifest, "convert"),
+            HashMap::from([("input".to_string(), TaskArgType::Path)])
+        );
+    }
+
+    #[test]
+    fn args_must_be_identifiers() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+convert = "python convert.py"
+"#,
+        );
+
+        let options = AddTaskOptions {
+            args: Some(vec![TaskArg {
+                name: "input file".into(),
+                default: None,
+                arg_type: TaskArgType::Path,
+            }]),
+            ..Default::default()
+        };
+        assert!(configure(&mut manifest, "convert", options).is_err());
+    }
+
+    const DEPENDENCIES: &str = r#"
+[tasks]
+lint = "cargo clippy"
+check = { cmd = "cargo check", depends-on = ["build", "lint"] }
+release = { cmd = "cargo publish", depends-on = [{ task = "build", args = ["--release"] }] }
+single = { cmd = "echo", depends-on = "build" }
+all = ["build", "lint"]
+
+[feature.docs.tasks]
+docs = { cmd = "mdbook build", depends_on = ["build"] }
+"#;
+
+    #[test]
+    fn dependents_of_a_task() {
+        let mut manifest = parse(DEPENDENCIES);
+
+        assert_eq!(
+            dependents(&mut manifest, "build").unwrap(),
+            ["check", "release", "single", "all", "docs"]
+        );
+        assert_eq!(dependents(&mut manifest, "lint").unwrap(), ["check", "all"]);
+        assert!(dependents(&mut manifest, "check").unwrap().is_empty());
+    }
+
+    #[test]
+    fn removed_task_is_stripped_from_dependencies() {
+        let mut manifest = parse(DEPENDENCIES);
+
+        assert_eq!(
+            remove_dependency(&mut manifest, "build").unwrap(),
+            ["check", "release", "single", "all", "docs"]
+        );
+        assert_eq!(dependency_names(&manifest, "check"), ["lint"]);
+        assert_eq!(
+            manifest.to_string(),
+            r#"
+[tasks]
+lint = "cargo clippy"
+check = { cmd = "cargo check", depends-on = ["lint"] }
+release = { cmd = "cargo publish" }
+single = { cmd = "echo" }
+all = ["lint"]
+
+[feature.docs.tasks]
+docs = { cmd = "mdbook build" }
+"#
+        );
+    }
+
+    #[test]
+    fn dependencies_stay_if_another_feature_defines_the_task() {
+        let mut manifest = parse(
+            r#"
+[tasks]
+check = { cmd = "cargo check", depends-on = ["build"] }
+
+[feature.rust.tasks]
+build = "cargo build"
+"#,
+        );
+
+        assert!(
+            remove_dependency(&mut manifest, "build")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(dependency_names(&manifest, "check"), ["build"]);
+    }
+
+    #[test]
+    fn task_with_dependents_is_only_removed_with_cascade() {
+        const MANIFEST: &str = r#"
+[tasks]
+build = "cargo build"
+check = { cmd = "cargo check", depends-on = ["build"] }
+"#;
+
+        let err = remove_from_manifest(&mut parse(MANIFEST), DEFAULT_FEATURE, "build", false)
+            .unwrap_err();
+        assert!(err.to_string().contains("check depend on it"), "{err}");
+
+        let mut manifest = parse(MANIFEST);
+        assert_eq!(
+            remove_from_manifest(&mut manifest, DEFAULT_FEATURE, "build", true).unwrap(),
+            ["check"]
+        );
+        assert_eq!(
+            manifest.to_string(),
+            r#"
+[tasks]
+check = { cmd = "cargo check" }
+"#
+        );
+
+        assert!(remove_from_manifest(&mut manifest, DEFAULT_FEATURE, "check", false).is_ok());
+        assert!(remove_from_manifest(&mut manifest, DEFAULT_FEATURE, "check", false).is_err());
+    }
 }
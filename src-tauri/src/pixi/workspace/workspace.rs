@@ -1,20 +1,36 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use indexmap::IndexSet;
-use pixi_api::manifest::HasFeaturesIter;
+use indexmap::{IndexMap, IndexSet};
+use miette::{Context, Diagnostic, IntoDiagnostic, Severity};
+use pixi_api::core::WorkspaceLocator;
+use pixi_api::core::environment::LockFileUsage;
+use pixi_api::core::workspace::DiscoveryStart;
 use pixi_api::manifest::{EnvironmentName, FeatureName, PrioritizedChannel};
+use pixi_api::manifest::{FeaturesExt, HasFeaturesIter};
 use pixi_api::manifest::{PixiPlatform, PixiPlatformName};
 use pixi_api::manifest::{Task, TaskName};
 use pixi_api::pypi_spec::{PixiPypiSpec, PypiPackageName};
-use pixi_api::rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform};
+use pixi_api::rattler_conda_types::{
+    GenericVirtualPackage, NamedChannelOrUrl, PackageName, ParseStrictness, Platform, Version,
+    VersionSpec,
+};
 use pixi_api::spec::PixiSpec;
 use pixi_api::workspace::ChannelOptions;
+use rattler_virtual_packages::{VirtualPackage, VirtualPackageOverrides};
 use serde::{Deserialize, Serialize};
 use tauri::{Runtime, Window};
+use tauri_plugin_opener::OpenerExt;
+use toml_edit::{Array, InlineTable, Item, Table, TableLike, Value};
+use url::Url;
 
 use crate::error::Error;
+use crate::manifest::{self, ManifestDocument, ManifestKind};
+use crate::pixi::workspace::move_dep::{find_key, normalize_pypi_name};
+use crate::pixi::{binary_pixi_version, parse_pixi_version};
+use crate::state::AppState;
+use crate::tauri_interface::run_with_progress;
 use crate::utils::{self, spawn_local};
 
 #[derive(Serialize, Deserialize)]
@@ -31,12 +47,250 @@ pub async fn root<R: Runtime>(window: Window<R>, workspace: PathBuf) -> Result<P
     Ok(workspace.root().to_path_buf())
 }
 
+/// Drops the workspace cached for the window, so the next command reads the manifest again
+#[tauri::command]
+pub async fn reload_workspace<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+) -> Result<(), Error> {
+    state
+        .workspace_cache()
+        .lock()
+        .unwrap()
+        .remove(window.label(), &workspace);
+    Ok(())
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Advice,
+}
+
+/// A problem in the manifest, `span` is the byte offset and length of the affected source
+#[derive(Serialize)]
+pub struct ManifestDiagnostic {
+    message: String,
+    span: Option<(usize, usize)>,
+    severity: DiagnosticSeverity,
+}
+
+/// Checks whether the manifest can be loaded, without changing anything on disk
+#[tauri::command]
+pub async fn validate_manifest<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Vec<ManifestDiagnostic>, Error> {
+    Ok(manifest_diagnostics(workspace))
+}
+
+fn manifest_diagnostics(workspace: PathBuf) -> Vec<ManifestDiagnostic> {
+    let result = WorkspaceLocator::for_cli()
+        .with_consider_environment(false)
+        .with_search_start(DiscoveryStart::SearchRoot(workspace))
+        .locate();
+
+    let mut diagnostics = Vec::new();
+    if let Err(err) = result {
+        collect_diagnostics(miette::Report::new(err).as_ref(), &mut diagnostics);
+    }
+
+    diagnostics
+}
+
+/// The manifest as it is on disk, for editing it as text
+#[tauri::command]
+pub async fn get_manifest_text<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<String, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let path = workspace.workspace.provenance.absolute_path();
+    Ok(std::fs::read_to_string(&path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to read manifest '{}'", path.display()))?)
+}
+
+/// Replaces the manifest with `contents` if they're a valid manifest, otherwise returns the
+/// problems and leaves the manifest as it was.
+///
+/// pixi can only check a manifest on disk, so `contents` are written and the previous manifest is
+/// restored if pixi rejects them. The windows don't reload it for either write.
+#[tauri::command]
+pub async fn save_manifest_text<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+    contents: String,
+) -> Result<Vec<ManifestDiagnostic>, Error> {
+    if let Some(diagnostic) = toml_syntax_diagnostic(&contents) {
+        return Ok(vec![diagnostic]);
+    }
+
+    let located = utils::workspace(workspace.clone())?;
+    let path = located.workspace.provenance.absolute_path();
+    let original = std::fs::read_to_string(&path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to read manifest '{}'", path.display()))?;
+    if contents == original {
+        return Ok(Vec::new());
+    }
+
+    let watcher = state.watcher().lock().await;
+    watcher.expect_own_write(&path, contents.clone());
+    std::fs::write(&path, &contents)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to write manifest '{}'", path.display()))?;
+    state.workspace_cache().lock().unwrap().invalidate(&path);
+
+    let diagnostics = manifest_diagnostics(workspace);
+    if !diagnostics.is_empty() {
+        watcher.expect_own_write(&path, original.clone());
+        std::fs::write(&path, original)
+            .into_diagnostic()
+            .wrap_err("Failed to restore the manifest")?;
+        state.workspace_cache().lock().unwrap().invalidate(&path);
+    }
+
+    Ok(diagnostics)
+}
+
+/// The error if `contents` aren't valid TOML, which doesn't need pixi to check
+fn toml_syntax_diagnostic(contents: &str) -> Option<ManifestDiagnostic> {
+    let err = contents.parse::<toml_edit::DocumentMut>().err()?;
+    Some(ManifestDiagnostic {
+        message: err.message().to_string(),
+        span: err.span().map(|span| (span.start, span.len())),
+        severity: DiagnosticSeverity::Error,
+    })
+}
+
+fn collect_diagnostics(diagnostic: &dyn Diagnostic, diagnostics: &mut Vec<ManifestDiagnostic>) {
+    // Errors like "failed to parse the manifest" carry the actual problem in their source
+    if diagnostic.labels().is_none()
+        && let Some(source) = diagnostic.diagnostic_source()
+    {
+        collect_diagnostics(source, diagnostics);
+        return;
+    }
+
+    let severity = match diagnostic.severity() {
+        Some(Severity::Warning) => DiagnosticSeverity::Warning,
+        Some(Severity::Advice) => DiagnosticSeverity::Advice,
+        Some(Severity::Error) | None => DiagnosticSeverity::Error,
+    };
+    let mut message = utils::strip_ansi_escapes(&diagnostic.to_string());
+    if let Some(help) = diagnostic.help() {
+        message.push_str(&format!("\nHelp: {help}"));
+    }
+
+    let labels: Vec<_> = diagnostic.labels().into_iter().flatten().collect();
+    if labels.is_empty() {
+        diagnostics.push(ManifestDiagnostic {
+            message: message.clone(),
+            span: None,
+            severity,
+        });
+    }
+    for label in labels {
+        let message = match label.label() {
+            Some(label) => format!("{message}: {label}"),
+            None => message.clone(),
+        };
+        diagnostics.push(ManifestDiagnostic {
+            message,
+            span: Some((label.offset(), label.len())),
+            severity,
+        });
+    }
+
+    for related in diagnostic.related().into_iter().flatten() {
+        collect_diagnostics(related, diagnostics);
+    }
+}
+
 #[tauri::command]
 pub async fn manifest<R: Runtime>(window: Window<R>, workspace: PathBuf) -> Result<PathBuf, Error> {
     let workspace = utils::workspace(workspace)?;
     Ok(workspace.workspace.provenance.absolute_path())
 }
 
+/// Whether the manifest is a `pixi.toml` or a `pyproject.toml`
+#[tauri::command]
+pub async fn manifest_kind<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<ManifestKind, Error> {
+    let workspace = utils::workspace(workspace)?;
+    Ok(ManifestKind::of(
+        &workspace.workspace.provenance.absolute_path(),
+    ))
+}
+
+/// Tidies the whitespace of the manifest, see [`ManifestDocument::format`].
+///
+/// Returns whether it changed. The windows don't reload it, since only whitespace changes.
+#[tauri::command]
+pub async fn format_manifest<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+    workspace: PathBuf,
+) -> Result<bool, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+    if !manifest.format() {
+        return Ok(false);
+    }
+
+    state.watcher().lock().await.expect_own_write(
+        &workspace.workspace.provenance.absolute_path(),
+        manifest.to_string(),
+    );
+    manifest.save()?;
+    Ok(true)
+}
+
+/// Opens the manifest with the application the OS associates with it, not an in-app editor
+#[tauri::command]
+pub async fn open_manifest_external<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<(), Error> {
+    let path = manifest(window.clone(), workspace).await?;
+    log::info!("Opening {} in the default editor", path.display());
+    window
+        .opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to open '{}'", path.display()))?;
+    Ok(())
+}
+
+/// Shows the workspace root, or `subpath` inside of it, selected in the file manager
+#[tauri::command]
+pub async fn reveal_in_file_manager<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    subpath: Option<String>,
+) -> Result<(), Error> {
+    let root = utils::workspace(workspace)?.root().to_path_buf();
+    let path = match subpath {
+        Some(subpath) => root.join(utils::path_within_workspace(&root, Path::new(&subpath))?),
+        None => root,
+    };
+
+    log::info!("Revealing {} in the file manager", path.display());
+    window
+        .opener()
+        .reveal_item_in_dir(&path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to reveal '{}'", path.display()))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn name<R: Runtime>(window: Window<R>, workspace: PathBuf) -> Result<String, Error> {
     Ok(utils::workspace_context(window, workspace)?.name().await)
@@ -78,6 +332,362 @@ pub async fn set_description<R: Runtime>(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn version<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Option<String>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    Ok(manifest
+        .workspace_field("version")
+        .and_then(Item::as_str)
+        .map(str::to_string))
+}
+
+#[tauri::command]
+pub async fn set_version<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    version: String,
+) -> Result<(), Error> {
+    let version = version.trim();
+    Version::from_str(version)
+        .map_err(|err| miette::miette!("Invalid version '{version}': {err}"))?;
+
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+    manifest
+        .workspace_table_mut()?
+        .insert("version", toml_edit::value(version));
+    manifest.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn requires_pixi<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Option<String>, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    Ok(manifest
+        .workspace_field("requires-pixi")
+        .and_then(Item::as_str)
+        .map(str::to_string))
+}
+
+/// Sets the minimum pixi version of the workspace, an empty constraint removes it.
+///
+/// Returns true if the pixi version of the GUI doesn't satisfy the constraint.
+#[tauri::command]
+pub async fn set_requires_pixi<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    constraint: String,
+) -> Result<bool, Error> {
+    let constraint = constraint.trim();
+    let outdated = !constraint.is_empty() && !satisfies(pixi_api::PIXI_VERSION, constraint)?;
+
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+    set_or_remove(manifest.workspace_table_mut()?, "requires-pixi", constraint);
+    manifest.save()?;
+
+    Ok(outdated)
+}
+
+fn satisfies(version: &str, constraint: &str) -> Result<bool, Error> {
+    let spec = VersionSpec::from_str(constraint, ParseStrictness::Lenient)
+        .map_err(|err| miette::miette!("Invalid version requirement '{constraint}': {err}"))?;
+    let version = Version::from_str(version)
+        .map_err(|err| miette::miette!("Invalid pixi version '{version}': {err}"))?;
+    Ok(spec.matches(&version))
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PixiCompatibility {
+    /// Version of the pixi binary that runs tasks and terminals
+    pixi_version: String,
+    /// The `requires-pixi` of the workspace
+    required: Option<String>,
+    compatible: bool,
+}
+
+/// Whether the pixi binary satisfies the `requires-pixi` of the workspace, e.g. to ask the user
+/// to upgrade. Without a requirement any version is compatible.
+#[tauri::command]
+pub async fn check_pixi_compatibility<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<PixiCompatibility, Error> {
+    let required = requires_pixi(window.clone(), workspace).await?;
+
+    let output = tauri::async_runtime::spawn_blocking(binary_pixi_version)
+        .await
+        .into_diagnostic()?;
+    let version = match &output {
+        Ok(Some(output)) => parse_pixi_version(output)
+            .ok_or_else(|| format!("Unexpected output of `pixi --version`: {}", output.trim())),
+        Ok(None) => Err("No pixi binary found".to_string()),
+        Err(e) => Err(e.0.to_string()),
+    };
+    let pixi_version = match version {
+        Ok(version) => version.to_string(),
+        Err(e) => {
+            // Without a binary only the bundled pixi is used
+            log::warn!("Failed to get the version of the pixi binary: {e}");
+            crate::pixi::pixi_version(window).await
+        }
+    };
+
+    let compatible = match &required {
+        Some(constraint) => satisfies(&pixi_version, constraint)?,
+        None => true,
+    };
+    Ok(PixiCompatibility {
+        pixi_version,
+        required,
+        compatible,
+    })
+}
+
+/// Preview features of the pixi version the GUI is built with
+const KNOWN_PREVIEW_FEATURES: &[&str] = &["pixi-build"];
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PreviewFeatures {
+    /// Whether `preview = true` enables every preview feature
+    all: bool,
+    enabled: Vec<PreviewFeature>,
+    known: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PreviewFeature {
+    name: String,
+    recognized: bool,
+}
+
+#[tauri::command]
+pub async fn list_preview_features<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<PreviewFeatures, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    Ok(read_preview_features(&manifest))
+}
+
+#[tauri::command]
+pub async fn set_preview_feature<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: String,
+    enabled: bool,
+) -> Result<(), Error> {
+    let feature = feature.trim();
+    if feature.is_empty() {
+        return Err(miette::miette!("Preview feature name can't be empty").into());
+    }
+
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+    write_preview_feature(manifest.workspace_table_mut()?, feature, enabled);
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn read_preview_features(manifest: &ManifestDocument) -> PreviewFeatures {
+    let preview = manifest.workspace_field("preview");
+
+    PreviewFeatures {
+        all: preview.and_then(Item::as_bool).unwrap_or(false),
+        enabled: preview
+            .and_then(Item::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|name| PreviewFeature {
+                name: name.to_string(),
+                recognized: KNOWN_PREVIEW_FEATURES.contains(&name),
+            })
+            .collect(),
+        known: KNOWN_PREVIEW_FEATURES.to_vec(),
+    }
+}
+
+/// Adds or removes `feature` from the `preview` list, unknown names are allowed because newer
+/// pixi versions might know them
+fn write_preview_feature(workspace: &mut Table, feature: &str, enabled: bool) {
+    let mut features: Vec<String> = match workspace.get("preview") {
+        Some(preview) if preview.as_bool() == Some(true) => {
+            if enabled {
+                return;
+            }
+            // Disabling one of all features leaves the other known ones
+            KNOWN_PREVIEW_FEATURES
+                .iter()
+                .map(|name| name.to_string())
+                .collect()
+        }
+        Some(preview) => preview
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let position = features.iter().position(|name| name == feature);
+    match (position, enabled) {
+        (None, true) => features.push(feature.to_string()),
+        (Some(position), false) => {
+            features.remove(position);
+        }
+        _ => {}
+    }
+
+    if features.is_empty() {
+        workspace.remove("preview");
+    } else {
+        workspace.insert("preview", toml_edit::value(Array::from_iter(features)));
+    }
+}
+
+/// Metadata that is mostly relevant when publishing the workspace.
+///
+/// When setting it, fields that are `None` are left as they are and empty values clear the field.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectInfo {
+    authors: Option<Vec<String>>,
+    license: Option<String>,
+    homepage: Option<String>,
+    repository: Option<String>,
+    documentation: Option<String>,
+}
+
+#[tauri::command]
+pub async fn project_info<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<ProjectInfo, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    Ok(read_project_info(&manifest))
+}
+
+#[tauri::command]
+pub async fn set_project_info<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    info: ProjectInfo,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    write_project_info(manifest.workspace_table_mut()?, info)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn read_project_info(manifest: &ManifestDocument) -> ProjectInfo {
+    let string = |key| {
+        manifest
+            .workspace_field(key)
+            .and_then(Item::as_str)
+            .map(str::to_string)
+    };
+
+    ProjectInfo {
+        authors: manifest
+            .workspace_field("authors")
+            .and_then(Item::as_array)
+            .map(|authors| authors.iter().filter_map(format_author).collect()),
+        license: string("license"),
+        homepage: string("homepage"),
+        repository: string("repository"),
+        documentation: string("documentation"),
+    }
+}
+
+/// Authors are strings in a pixi manifest, but tables with a name and email in a `pyproject.toml`
+fn format_author(author: &Value) -> Option<String> {
+    if let Some(author) = author.as_str() {
+        return Some(author.to_string());
+    }
+
+    let author = author.as_inline_table()?;
+    let name = author.get("name").and_then(Value::as_str);
+    let email = author.get("email").and_then(Value::as_str);
+    match (name, email) {
+        (Some(name), Some(email)) => Some(format!("{name} <{email}>")),
+        (Some(name), None) => Some(name.to_string()),
+        (None, Some(email)) => Some(format!("<{email}>")),
+        (None, None) => None,
+    }
+}
+
+fn write_project_info(table: &mut Table, info: ProjectInfo) -> Result<(), Error> {
+    if let Some(authors) = info.authors {
+        let authors: Array = authors
+            .iter()
+            .map(|author| author.trim())
+            .filter(|author| !author.is_empty())
+            .collect();
+        if authors.is_empty() {
+            table.remove("authors");
+        } else {
+            table.insert("authors", toml_edit::value(authors));
+        }
+    }
+
+    if let Some(license) = info.license {
+        let license = license.trim();
+        if !license.is_empty() {
+            spdx::Expression::parse(license).map_err(|err| {
+                miette::miette!("Invalid SPDX license expression '{license}': {err}")
+            })?;
+        }
+        set_or_remove(table, "license", license);
+    }
+
+    for (key, url) in [
+        ("homepage", info.homepage),
+        ("repository", info.repository),
+        ("documentation", info.documentation),
+    ] {
+        let Some(url) = url else {
+            continue;
+        };
+        let url = url.trim();
+        if !url.is_empty() {
+            Url::parse(url).map_err(|err| miette::miette!("Invalid {key} URL '{url}': {err}"))?;
+        }
+        set_or_remove(table, key, url);
+    }
+
+    Ok(())
+}
+
+fn set_or_remove(table: &mut Table, key: &str, value: &str) {
+    if value.is_empty() {
+        table.remove(key);
+    } else {
+        table.insert(key, toml_edit::value(value));
+    }
+}
+
 #[tauri::command]
 pub async fn list_channels<R: Runtime>(
     window: Window<R>,
@@ -88,6 +698,75 @@ pub async fn list_channels<R: Runtime>(
         .await)
 }
 
+/// A channel resolved to its URL, so e.g. `conda-forge` and `https://prefix.dev/conda-forge` can
+/// be told apart
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ChannelInfo {
+    /// The channel as it is written in the manifest
+    channel: String,
+    canonical_url: String,
+    display_name: String,
+    /// Whether the channel is resolved through the channel alias, e.g. `conda-forge`
+    is_default: bool,
+    description: Option<String>,
+}
+
+#[tauri::command]
+pub async fn describe_channels<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<HashMap<EnvironmentName, Vec<ChannelInfo>>, Error> {
+    let ctx = utils::workspace_context(window, workspace)?;
+    let channel_config = ctx.workspace().channel_config();
+    let channel_alias = channel_config.channel_alias.to_string();
+
+    let mut channels = HashMap::new();
+    for (environment, named) in ctx.list_channel().await {
+        let infos = named
+            .into_iter()
+            .map(|named| {
+                let written = named.to_string();
+                let channel = named
+                    .into_channel(&channel_config)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("Invalid channel '{written}'"))?;
+                Ok(channel_info(
+                    written,
+                    &channel.base_url.to_string(),
+                    &channel_alias,
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
+        channels.insert(environment, infos);
+    }
+
+    Ok(channels)
+}
+
+fn channel_info(channel: String, url: &str, channel_alias: &str) -> ChannelInfo {
+    let canonical_url = url.trim_end_matches('/').to_string();
+    let display_name = canonical_url
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(&canonical_url)
+        .to_string();
+    let is_default = canonical_url.starts_with(channel_alias.trim_end_matches('/'));
+
+    let description = match Url::parse(&canonical_url) {
+        Ok(url) if url.scheme() == "file" => Some("Local channel".to_string()),
+        Ok(url) if !is_default => url.host_str().map(|host| format!("Hosted on {host}")),
+        _ => None,
+    };
+
+    ChannelInfo {
+        channel,
+        canonical_url,
+        display_name,
+        is_default,
+        description,
+    }
+}
+
 #[tauri::command]
 pub async fn add_channel<R: Runtime>(
     window: Window<R>,
@@ -139,80 +818,536 @@ pub async fn set_channels<R: Runtime>(
     .await
 }
 
+/// A channel of `add_channels` or `remove_channels`
+#[derive(Debug, Deserialize)]
+pub struct ChannelChange {
+    pub channel: String,
+    /// The default feature if not given
+    #[serde(default)]
+    pub feature: Option<String>,
+    /// Only used when adding, the channel is then written as `{ channel = "...", priority = 1 }`
+    #[serde(default)]
+    pub priority: Option<i32>,
+}
+
+/// Adds all channels with one write of the manifest and one solve afterwards, channels a feature
+/// already has are skipped.
+///
+/// Nothing is changed if any of the channels is invalid, and the manifest is restored if the
+/// solve fails.
 #[tauri::command]
-pub async fn list_platforms<R: Runtime>(
+pub async fn add_channels<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
-) -> Result<HashMap<EnvironmentName, Vec<PixiPlatformName>>, Error> {
-    Ok(utils::workspace_context(window, workspace)?
-        .list_platforms()
-        .await)
+    channels: Vec<ChannelChange>,
+    prepend: bool,
+    no_install: bool,
+) -> Result<(), Error> {
+    let changes = parse_channel_changes(&channels)?;
+    let located = utils::workspace(workspace.clone())?;
+    let mut manifest = ManifestDocument::load(&located)?;
+    let original = manifest.to_string();
+    write_added_channels(&mut manifest, &changes, prepend)?;
+
+    let names = changes.iter().map(|(channel, _, _)| channel.as_str());
+    let message = format!("Adding {}", names.collect::<Vec<_>>().join(", "));
+    save_and_solve(window, workspace, manifest, original, message, no_install).await
 }
 
+/// Removes all channels with one write of the manifest and one solve afterwards.
+///
+/// Nothing is changed if any of the channels is invalid or not a channel of its feature, and the
+/// manifest is restored if the solve fails.
 #[tauri::command]
-pub async fn add_platforms<R: Runtime>(
+pub async fn remove_channels<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
-    platforms: Vec<Platform>,
+    channels: Vec<ChannelChange>,
     no_install: bool,
-    feature: Option<String>,
 ) -> Result<(), Error> {
-    spawn_local(move || async move {
-        utils::workspace_context(window, workspace)?
-            .add_platforms(
-                platforms.into_iter().map(PixiPlatform::from).collect(),
-                no_install,
-                feature,
-            )
-            .await?;
+    let changes = parse_channel_changes(&channels)?;
+    let located = utils::workspace(workspace.clone())?;
+    let mut manifest = ManifestDocument::load(&located)?;
+    let original = manifest.to_string();
+    write_removed_channels(&mut manifest, &changes)?;
 
-        Ok(())
-    })
-    .await
+    let names = changes.iter().map(|(channel, _, _)| channel.as_str());
+    let message = format!("Removing {}", names.collect::<Vec<_>>().join(", "));
+    save_and_solve(window, workspace, manifest, original, message, no_install).await
 }
 
-#[tauri::command]
-pub async fn remove_platforms<R: Runtime>(
+/// Writes the edited manifest and updates the lock file, or writes `original` back if that fails
+async fn save_and_solve<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
-    platforms: Vec<Platform>,
+    manifest: ManifestDocument,
+    original: String,
+    message: String,
     no_install: bool,
-    feature: Option<String>,
 ) -> Result<(), Error> {
+    if manifest.to_string() == original {
+        return Ok(());
+    }
+    manifest.save()?;
+
     spawn_local(move || async move {
-        utils::workspace_context(window, workspace)?
-            .remove_platforms(
-                platforms.into_iter().map(PixiPlatform::from).collect(),
-                no_install,
-                feature,
-            )
-            .await?;
+        let ctx = utils::workspace_context(window.clone(), workspace)?;
+
+        let result = run_with_progress(
+            &window,
+            None,
+            "solve",
+            message,
+            ctx.list_packages(None, None, None, false, no_install, LockFileUsage::Update),
+        )
+        .await
+        .ok_or_else(Error::cancelled)?;
+
+        if let Err(e) = result {
+            let path = ctx.workspace().workspace.provenance.absolute_path();
+            std::fs::write(&path, original)
+                .into_diagnostic()
+                .wrap_err("Failed to restore the manifest")?;
+            return Err(e.into());
+        }
 
         Ok(())
     })
     .await
 }
 
-#[tauri::command]
-pub fn current_platform() -> String {
-    Platform::current().to_string()
+/// Parses the channels of all changes, failing with all invalid ones. Returns the channel, the
+/// feature and the priority of each change.
+fn parse_channel_changes(
+    changes: &[ChannelChange],
+) -> Result<Vec<(String, String, Option<i32>)>, Error> {
+    let mut parsed = Vec::new();
+    let mut failed = Vec::new();
+    for change in changes {
+        let channel = change.channel.trim();
+        match NamedChannelOrUrl::from_str(channel) {
+            Ok(parsed_channel) if !channel.is_empty() => parsed.push((
+                parsed_channel.to_string(),
+                change
+                    .feature
+                    .clone()
+                    .unwrap_or_else(|| manifest::DEFAULT_FEATURE.to_string()),
+                change.priority,
+            )),
+            Ok(_) => failed.push("'': missing channel name".to_string()),
+            Err(err) => failed.push(format!("'{channel}': {err}")),
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(miette::miette!("Invalid channels:\n{}", failed.join("\n")).into());
+    }
+
+    Ok(parsed)
 }
 
-#[tauri::command]
-pub async fn list_features<R: Runtime>(
-    window: Window<R>,
-    workspace: PathBuf,
-) -> Result<Vec<FeatureName>, Error> {
-    Ok(utils::workspace_context(window, workspace)?
-        .list_features()
-        .await
-        .iter()
-        .map(|(name, _)| name.clone())
-        .collect())
+/// The table of a feature that holds its channels, the workspace table for the default feature
+fn channels_table_mut<'a>(
+    manifest: &'a mut ManifestDocument,
+    feature: &str,
+) -> Result<&'a mut Table, Error> {
+    if feature == manifest::DEFAULT_FEATURE {
+        Ok(manifest.workspace_table_mut()?)
+    } else {
+        Ok(manifest.feature_table_mut(feature)?)
+    }
 }
 
-#[tauri::command]
-pub async fn list_feature_channels<R: Runtime>(
+fn feature_channels<'a>(manifest: &'a ManifestDocument, feature: &str) -> Option<&'a Array> {
+    let table = if feature == manifest::DEFAULT_FEATURE {
+        manifest.workspace_table()?
+    } else {
+        manifest.feature_table(feature)?
+    };
+    table.get("channels")?.as_array()
+}
+
+fn write_added_channels(
+    manifest: &mut ManifestDocument,
+    changes: &[(String, String, Option<i32>)],
+    prepend: bool,
+) -> Result<(), Error> {
+    // Prepended channels keep the order they're given in, like `pixi workspace channel add --prepend`
+    let mut prepended: HashMap<&str, usize> = HashMap::new();
+    for (channel, feature, priority) in changes {
+        let channels = channels_table_mut(manifest, feature)?
+            .entry("channels")
+            .or_insert_with(|| toml_edit::value(Array::new()))
+            .as_array_mut()
+            .ok_or_else(|| miette::miette!("The channels of feature '{feature}' aren't a list"))?;
+
+        let name = channel.trim_end_matches('/');
+        if channels
+            .iter()
+            .any(|entry| channel_name(entry) == Some(name))
+        {
+            continue;
+        }
+        let entry: Value = match priority {
+            Some(priority) => {
+                let mut table = InlineTable::new();
+                table.insert("channel", channel.as_str().into());
+                table.insert("priority", i64::from(*priority).into());
+                table.into()
+            }
+            None => channel.as_str().into(),
+        };
+        if prepend {
+            let position = prepended.entry(feature).or_default();
+            channels.insert(*position, entry);
+            *position += 1;
+        } else {
+            channels.push(entry);
+        }
+        channels.fmt();
+    }
+
+    Ok(())
+}
+
+fn write_removed_channels(
+    manifest: &mut ManifestDocument,
+    changes: &[(String, String, Option<i32>)],
+) -> Result<(), Error> {
+    // Check all of them first, so nothing is removed if one of them is missing
+    let missing: Vec<String> = changes
+        .iter()
+        .filter(|(channel, feature, _)| {
+            let name = channel.trim_end_matches('/');
+            !feature_channels(manifest, feature).is_some_and(|channels| {
+                channels
+                    .iter()
+                    .any(|entry| channel_name(entry) == Some(name))
+            })
+        })
+        .map(|(channel, feature, _)| format!("'{channel}' isn't a channel of feature '{feature}'"))
+        .collect();
+    if !missing.is_empty() {
+        return Err(miette::miette!("{}", missing.join("\n")).into());
+    }
+
+    for (channel, feature, _) in changes {
+        let name = channel.trim_end_matches('/');
+        if let Some(channels) = channels_table_mut(manifest, feature)?
+            .get_mut("channels")
+            .and_then(Item::as_array_mut)
+        {
+            channels.retain(|entry| channel_name(entry) != Some(name));
+            channels.fmt();
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrites the channels of a feature in the given order, which has to contain exactly the
+/// current channels
+#[tauri::command]
+pub async fn reorder_channels<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+    order: Vec<NamedChannelOrUrl>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    let order: Vec<String> = order.iter().map(ToString::to_string).collect();
+    let channels = channels_table_mut(&mut manifest, feature)?
+        .get_mut("channels")
+        .and_then(Item::as_array_mut)
+        .ok_or_else(|| miette::miette!("Feature '{feature}' doesn't have any channels"))?;
+    reorder_channel_array(channels, &order)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// Channels are either a string or a table like `{ channel = "...", priority = 1 }`
+fn channel_name(channel: &Value) -> Option<&str> {
+    channel
+        .as_str()
+        .or_else(|| channel.as_inline_table()?.get("channel")?.as_str())
+        // URLs might be written with or without a trailing slash
+        .map(|name| name.trim_end_matches('/'))
+}
+
+fn reorder_channel_array(channels: &mut Array, order: &[String]) -> Result<(), Error> {
+    let current: Vec<&str> = channels.iter().filter_map(channel_name).collect();
+    let mut sorted_current = current.clone();
+    sorted_current.sort_unstable();
+    let order: Vec<&str> = order
+        .iter()
+        .map(|name| name.trim_end_matches('/'))
+        .collect();
+    let mut sorted_order = order.clone();
+    sorted_order.sort_unstable();
+    if current.len() != channels.len() || sorted_current != sorted_order {
+        return Err(miette::miette!(
+            "The new order [{}] doesn't match the channels [{}]",
+            order.join(", "),
+            current.join(", ")
+        )
+        .into());
+    }
+
+    // The formatting belongs to the position, e.g. the first entry has no leading space
+    let decors: Vec<_> = channels
+        .iter()
+        .map(|channel| channel.decor().clone())
+        .collect();
+    let mut entries: Vec<Option<Value>> = channels.iter().cloned().map(Some).collect();
+    channels.clear();
+    for (name, decor) in order.into_iter().zip(decors) {
+        let mut entry = entries
+            .iter_mut()
+            .find(|entry| entry.as_ref().and_then(channel_name) == Some(name))
+            .and_then(Option::take)
+            .ok_or_else(|| miette::miette!("Channel '{name}' is listed twice"))?;
+        *entry.decor_mut() = decor;
+        channels.push_formatted(entry);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_platforms<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<HashMap<EnvironmentName, Vec<PixiPlatformName>>, Error> {
+    Ok(utils::workspace_context(window, workspace)?
+        .list_platforms()
+        .await)
+}
+
+#[tauri::command]
+pub async fn add_platforms<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    platforms: Vec<Platform>,
+    no_install: bool,
+    feature: Option<String>,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        utils::workspace_context(window, workspace)?
+            .add_platforms(
+                platforms.into_iter().map(PixiPlatform::from).collect(),
+                no_install,
+                feature,
+            )
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_platforms<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    platforms: Vec<Platform>,
+    no_install: bool,
+    feature: Option<String>,
+) -> Result<(), Error> {
+    spawn_local(move || async move {
+        utils::workspace_context(window, workspace)?
+            .remove_platforms(
+                platforms.into_iter().map(PixiPlatform::from).collect(),
+                no_install,
+                feature,
+            )
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn current_platform() -> String {
+    Platform::current().to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct DetectedVirtualPackage {
+    name: String,
+    version: String,
+    build_string: String,
+}
+
+/// The virtual packages of this machine, e.g. `__glibc` or `__cuda`, to understand why a package
+/// doesn't install. The `CONDA_OVERRIDE_*` environment variables are respected like in pixi.
+#[tauri::command]
+pub async fn detected_virtual_packages() -> Result<Vec<DetectedVirtualPackage>, Error> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let packages = VirtualPackage::detect(&VirtualPackageOverrides::from_env())
+            .into_diagnostic()
+            .wrap_err("Failed to detect the virtual packages")?;
+        Ok(packages
+            .into_iter()
+            .map(GenericVirtualPackage::from)
+            .map(|package| DetectedVirtualPackage {
+                name: package.name.as_normalized().to_string(),
+                version: package.version.to_string(),
+                build_string: package.build_string,
+            })
+            .collect())
+    })
+    .await
+    .into_diagnostic()?
+}
+
+/// The absolute path of `executable` (e.g. `python`) in the environment, `None` if the environment
+/// doesn't have it. Only the directories the activation of the environment puts on the `PATH` are
+/// searched, the environment doesn't have to be activated or installed by the GUI for this.
+#[tauri::command]
+pub async fn which_in_environment<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: String,
+    executable: String,
+) -> Result<Option<PathBuf>, Error> {
+    let name = utils::parse_environment_name(&environment)?;
+    let workspace = utils::workspace(workspace)?;
+    let prefix = workspace
+        .environment(&name)
+        .ok_or_else(|| miette::miette!("Environment '{environment}' not found"))?
+        .dir();
+
+    find_executable(&prefix, &executable)
+}
+
+/// Directories of a conda prefix with executables, in the order activation puts them on the `PATH`
+fn executable_dirs(prefix: &Path) -> Vec<PathBuf> {
+    if cfg!(windows) {
+        vec![
+            prefix.to_path_buf(),
+            prefix.join("Library").join("mingw-w64").join("bin"),
+            prefix.join("Library").join("usr").join("bin"),
+            prefix.join("Library").join("bin"),
+            prefix.join("Scripts"),
+            prefix.join("bin"),
+        ]
+    } else {
+        vec![prefix.join("bin")]
+    }
+}
+
+fn find_executable(prefix: &Path, executable: &str) -> Result<Option<PathBuf>, Error> {
+    // A path would be resolved relative to the prefix, possibly outside of it
+    if executable.is_empty() || executable.contains(['/', '\\']) {
+        return Err(miette::miette!("Invalid executable name '{executable}'").into());
+    }
+    let paths = std::env::join_paths(executable_dirs(prefix)).into_diagnostic()?;
+    Ok(which::which_in(executable, Some(paths), prefix).ok())
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WorkspaceMember {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// The workspace root followed by the packages it builds from source, which are the conda
+/// dependencies with a `path` like `my-lib = { path = "packages/my-lib" }`
+#[tauri::command]
+pub async fn list_members<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Vec<WorkspaceMember>, Error> {
+    let context = utils::workspace_context(window, workspace)?;
+    let manifest = ManifestDocument::load(context.workspace())?;
+    let name = context.name().await;
+    Ok(workspace_members(
+        &manifest,
+        context.workspace().root(),
+        name,
+    ))
+}
+
+/// The root is named after its `[package]` if it has one, otherwise `workspace_name` is used
+fn workspace_members(
+    manifest: &ManifestDocument,
+    root: &Path,
+    workspace_name: String,
+) -> Vec<WorkspaceMember> {
+    let pixi = manifest.pixi_table();
+    let root_name = pixi
+        .and_then(|pixi| pixi.get("package")?.get("name")?.as_str())
+        .map_or(workspace_name, str::to_string);
+    let mut members = vec![WorkspaceMember {
+        name: root_name,
+        path: root.to_path_buf(),
+    }];
+    let Some(pixi) = pixi else {
+        return members;
+    };
+
+    let features = pixi
+        .get("feature")
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|features| {
+            features
+                .iter()
+                .filter_map(|(_, feature)| feature.as_table_like())
+        });
+    for feature in std::iter::once(pixi as &dyn TableLike).chain(features) {
+        let targets = feature
+            .get("target")
+            .and_then(Item::as_table_like)
+            .into_iter()
+            .flat_map(|targets| {
+                targets
+                    .iter()
+                    .filter_map(|(_, target)| target.as_table_like())
+            });
+        for table in std::iter::once(feature).chain(targets) {
+            let Some(deps) = table.get("dependencies").and_then(Item::as_table_like) else {
+                continue;
+            };
+            for (name, spec) in deps.iter() {
+                let Some(path) = spec.get("path").and_then(Item::as_str) else {
+                    continue;
+                };
+                // Collecting the components drops the `.` of paths like `./packages/my-lib`
+                let path: PathBuf = root.join(path).components().collect();
+                if !members.iter().any(|member| member.path == path) {
+                    members.push(WorkspaceMember {
+                        name: name.to_string(),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+
+    members
+}
+
+#[tauri::command]
+pub async fn list_features<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<Vec<FeatureName>, Error> {
+    Ok(utils::workspace_context(window, workspace)?
+        .list_features()
+        .await
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn list_feature_channels<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
     feature: &str,
@@ -244,6 +1379,56 @@ pub async fn list_feature_pypi_dependencies<R: Runtime>(
         .await)
 }
 
+/// Dependencies of all features of an environment, with the feature each spec comes from
+#[derive(Serialize)]
+pub struct AllDependencies {
+    conda: HashMap<PackageName, Vec<(FeatureName, PixiSpec)>>,
+    pypi: HashMap<PypiPackageName, Vec<(FeatureName, PixiPypiSpec)>>,
+}
+
+#[tauri::command]
+pub async fn list_all_dependencies<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: EnvironmentName,
+) -> Result<AllDependencies, Error> {
+    let ctx = utils::workspace_context(window, workspace)?;
+
+    let feature_names: Vec<_> = ctx
+        .workspace()
+        .environment(&environment)
+        .ok_or_else(|| miette::miette!("Environment '{}' not found", environment))?
+        .features()
+        .map(|f| f.name.clone())
+        .collect();
+
+    let mut all = AllDependencies {
+        conda: HashMap::new(),
+        pypi: HashMap::new(),
+    };
+    for feature in feature_names {
+        let conda = ctx
+            .list_feature_dependencies(feature.clone(), None)
+            .await
+            .unwrap_or_default();
+        for (name, specs) in conda {
+            let entries = all.conda.entry(name).or_default();
+            entries.extend(specs.into_iter().map(|spec| (feature.clone(), spec)));
+        }
+
+        let pypi = ctx
+            .list_feature_pypi_dependencies(feature.clone(), None)
+            .await
+            .unwrap_or_default();
+        for (name, specs) in pypi {
+            let entries = all.pypi.entry(name).or_default();
+            entries.extend(specs.into_iter().map(|spec| (feature.clone(), spec)));
+        }
+    }
+
+    Ok(all)
+}
+
 #[tauri::command]
 pub async fn list_feature_tasks<R: Runtime>(
     window: Window<R>,
@@ -263,13 +1448,35 @@ pub async fn feature_by_task<R: Runtime>(
     environment: &str,
 ) -> Result<Option<FeatureName>, Error> {
     Ok(utils::workspace_context(window, workspace)?
-        .feature_by_task(
-            &task.into(),
-            &EnvironmentName::from_str(environment).unwrap(),
-        )
+        .feature_by_task(&task.into(), &utils::parse_environment_name(environment)?)
         .await)
 }
 
+/// The environments the task can run in, i.e. those with a feature that defines it. Tasks of the
+/// default feature are in every environment that includes the default feature.
+#[tauri::command]
+pub async fn task_environments<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    task: String,
+) -> Result<Vec<EnvironmentName>, Error> {
+    let context = utils::workspace_context(window, workspace)?;
+    let task = TaskName::from(task);
+
+    let mut environments = Vec::new();
+    for environment in context.list_environments().await {
+        if context
+            .feature_by_task(&task, environment.name())
+            .await
+            .is_some()
+        {
+            environments.push(environment.name().clone());
+        }
+    }
+
+    Ok(environments)
+}
+
 #[tauri::command]
 pub async fn remove_feature<R: Runtime>(
     window: Window<R>,
@@ -277,13 +1484,138 @@ pub async fn remove_feature<R: Runtime>(
     name: &str,
 ) -> Result<bool, Error> {
     let context = utils::workspace_context(window, workspace)?;
-    let feature_name = FeatureName::from_str(name).unwrap();
+    let feature_name = utils::parse_feature_name(name)?;
 
     context.remove_feature(&feature_name).await?;
 
     Ok(!context.list_features().await.contains_key(&feature_name))
 }
 
+/// Makes a path dependency of the feature (the default feature if not given) editable or not,
+/// without changing the rest of its entry. The environment has to be installed again for it to
+/// take effect.
+#[tauri::command]
+pub async fn set_pypi_editable<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    package: String,
+    editable: bool,
+    feature: Option<String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    write_pypi_editable(&mut manifest, feature, &package, editable)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// Non-editable is pixi's default, so `editable = false` isn't written
+fn write_pypi_editable(
+    manifest: &mut ManifestDocument,
+    feature: &str,
+    package: &str,
+    editable: bool,
+) -> Result<(), Error> {
+    let not_found = || miette::miette!("Feature '{feature}' doesn't depend on '{package}'");
+    let deps = manifest
+        .feature_table_mut(feature)?
+        .get_mut("pypi-dependencies")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(not_found)?;
+    let name = find_key(deps, package, normalize_pypi_name).ok_or_else(not_found)?;
+
+    let not_a_path = || miette::miette!("'{package}' isn't a path dependency");
+    match deps.get_mut(&name).ok_or_else(not_found)? {
+        Item::Value(Value::InlineTable(spec)) if spec.contains_key("path") => {
+            if editable {
+                spec.insert("editable", true.into());
+            } else {
+                spec.remove("editable");
+            }
+            spec.fmt();
+        }
+        Item::Table(spec) if spec.contains_key("path") => {
+            if editable {
+                spec.insert("editable", toml_edit::value(true));
+            } else {
+                spec.remove("editable");
+            }
+        }
+        _ => return Err(not_a_path().into()),
+    }
+
+    Ok(())
+}
+
+/// Renames the tables of the feature and its uses in the environments
+#[tauri::command]
+pub async fn rename_feature<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    old: String,
+    new: String,
+) -> Result<(), Error> {
+    utils::parse_feature_name(&new)?;
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    write_feature_rename(&mut manifest, &old, &new)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn write_feature_rename(
+    manifest: &mut ManifestDocument,
+    old: &str,
+    new: &str,
+) -> Result<(), Error> {
+    if old == manifest::DEFAULT_FEATURE {
+        return Err(miette::miette!("The default feature can't be renamed").into());
+    }
+    if new == manifest::DEFAULT_FEATURE || manifest.feature_table(new).is_some() {
+        return Err(miette::miette!("Feature '{new}' already exists").into());
+    }
+
+    let pixi = manifest.pixi_table_mut()?;
+    let renamed = pixi
+        .get_mut("feature")
+        .and_then(Item::as_table_like_mut)
+        .is_some_and(|features| manifest::rename_key(features, old, new));
+    if !renamed {
+        return Err(miette::miette!("Feature '{old}' doesn't exist").into());
+    }
+
+    let Some(environments) = pixi
+        .get_mut("environments")
+        .and_then(Item::as_table_like_mut)
+    else {
+        return Ok(());
+    };
+    for (_, environment) in environments.iter_mut() {
+        let features = if environment.is_array() {
+            environment.as_array_mut()
+        } else {
+            environment
+                .as_table_like_mut()
+                .and_then(|definition| definition.get_mut("features"))
+                .and_then(Item::as_array_mut)
+        };
+        for feature in features.into_iter().flat_map(Array::iter_mut) {
+            if feature.as_str() == Some(old) {
+                let decor = feature.decor().clone();
+                *feature = new.into();
+                *feature.decor_mut() = decor;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn list_environments<R: Runtime>(
     window: Window<R>,
@@ -302,7 +1634,11 @@ pub async fn list_environments<R: Runtime>(
         .collect())
 }
 
+/// With `copy_channels_from` or `copy_platforms_from` the environment also gets a feature of the
+/// same name, which has the channels or platforms of that environment. Everything is written
+/// with a single save of the manifest then.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_environment<R: Runtime>(
     window: Window<R>,
     workspace: PathBuf,
@@ -311,10 +1647,59 @@ pub async fn add_environment<R: Runtime>(
     solve_group: Option<String>,
     no_default_feature: bool,
     force: bool,
+    copy_channels_from: Option<String>,
+    copy_platforms_from: Option<String>,
 ) -> Result<(), Error> {
+    if copy_channels_from.is_some() || copy_platforms_from.is_some() {
+        utils::parse_environment_name(name)?;
+        let workspace = utils::workspace(workspace)?;
+        let environment = |source: &str| {
+            workspace
+                .environment(&utils::parse_environment_name(source)?)
+                .ok_or_else(|| Error::from(miette::miette!("Environment '{source}' doesn't exist")))
+        };
+        let base = EnvironmentBase {
+            channels: copy_channels_from
+                .map(|source| {
+                    Ok::<_, Error>(
+                        environment(&source)?
+                            .channels()
+                            .into_iter()
+                            .map(ToString::to_string)
+                            .collect(),
+                    )
+                })
+                .transpose()?,
+            platforms: copy_platforms_from
+                .map(|source| {
+                    Ok::<_, Error>(
+                        environment(&source)?
+                            .platforms()
+                            .into_iter()
+                            .map(|platform| platform.to_string())
+                            .collect(),
+                    )
+                })
+                .transpose()?,
+        };
+
+        let mut manifest = ManifestDocument::load(&workspace)?;
+        write_environment_with_base(
+            &mut manifest,
+            name,
+            &features.unwrap_or_default(),
+            solve_group.as_deref(),
+            no_default_feature,
+            force,
+            base,
+        )?;
+        manifest.save()?;
+        return Ok(());
+    }
+
     utils::workspace_context(window, workspace)?
         .add_environment(
-            EnvironmentName::from_str(name).unwrap(),
+            utils::parse_environment_name(name)?,
             features,
             solve_group,
             no_default_feature,
@@ -337,3 +1722,1752 @@ pub async fn remove_environment<R: Runtime>(
 
     Ok(())
 }
+
+/// Adds `new_name` with the features, solve group and `no-default-feature` flag of `source`,
+/// plus the `extra_features`
+#[tauri::command]
+pub async fn duplicate_environment<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    source: String,
+    new_name: String,
+    extra_features: Option<Vec<String>>,
+) -> Result<(), Error> {
+    utils::parse_environment_name(&new_name)?;
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    write_duplicate_environment(
+        &mut manifest,
+        &source,
+        &new_name,
+        &extra_features.unwrap_or_default(),
+    )?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// Rewrites `[environments]` in the given order, which has to contain exactly the listed
+/// environments. The default environment can be part of the order even if it isn't listed.
+#[tauri::command]
+pub async fn reorder_environments<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    order: Vec<String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    write_environment_order(&mut manifest, &order)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_solve_group<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: String,
+    solve_group: Option<String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let solve_group = solve_group
+        .as_deref()
+        .map(str::trim)
+        .filter(|group| !group.is_empty());
+    write_solve_group(&mut manifest, &environment, solve_group)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// The environments in each solve group
+#[tauri::command]
+pub async fn list_solve_groups<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+) -> Result<HashMap<String, Vec<EnvironmentName>>, Error> {
+    let mut solve_groups: HashMap<String, Vec<EnvironmentName>> = HashMap::new();
+    for environment in utils::workspace_context(window, workspace)?
+        .list_environments()
+        .await
+    {
+        if let Some(solve_group) = environment.solve_group() {
+            solve_groups
+                .entry(solve_group.name().to_string())
+                .or_default()
+                .push(environment.name().clone());
+        }
+    }
+
+    Ok(solve_groups)
+}
+
+/// Sets the `solve-group` of an environment, solve groups don't have to be declared anywhere else
+fn write_solve_group(
+    manifest: &mut ManifestDocument,
+    environment: &str,
+    solve_group: Option<&str>,
+) -> Result<(), Error> {
+    let environments = manifest::get_or_insert_table(manifest.pixi_table_mut()?, "environments")?;
+
+    let Some(item) = environments.get_mut(environment) else {
+        // The default environment exists without being listed
+        if environment == manifest::DEFAULT_ENVIRONMENT
+            && let Some(solve_group) = solve_group
+        {
+            let mut definition = InlineTable::new();
+            definition.insert("solve-group", solve_group.into());
+            environments.insert(environment, toml_edit::value(definition));
+            return Ok(());
+        }
+        if solve_group.is_none() {
+            return Ok(());
+        }
+        return Err(miette::miette!("Environment '{environment}' doesn't exist").into());
+    };
+
+    // The short form only lists the features
+    if let Some(features) = item.as_array() {
+        let Some(solve_group) = solve_group else {
+            return Ok(());
+        };
+        let mut definition = InlineTable::new();
+        definition.insert("features", features.clone().into());
+        definition.insert("solve-group", solve_group.into());
+        *item = toml_edit::value(definition);
+        return Ok(());
+    }
+
+    let definition = item.as_table_like_mut().ok_or_else(|| {
+        miette::miette!("Expected environment '{environment}' to be a table or a list of features")
+    })?;
+    match solve_group {
+        Some(solve_group) => {
+            definition.insert("solve-group", toml_edit::value(solve_group));
+        }
+        None => {
+            definition.remove("solve-group");
+        }
+    }
+
+    // Go back to the short form when only the features are left
+    if let Some(definition) = item.as_inline_table_mut() {
+        if let Some(features) = definition.get("features").and_then(Value::as_array)
+            && definition.len() == 1
+        {
+            *item = toml_edit::value(features.clone());
+        } else {
+            definition.fmt();
+        }
+    }
+
+    Ok(())
+}
+
+fn write_duplicate_environment(
+    manifest: &mut ManifestDocument,
+    source: &str,
+    new_name: &str,
+    extra_features: &[String],
+) -> Result<(), Error> {
+    if let Some(feature) = extra_features
+        .iter()
+        .find(|feature| manifest.feature_table(feature).is_none())
+    {
+        return Err(miette::miette!("Feature '{feature}' doesn't exist").into());
+    }
+
+    let environments = manifest::get_or_insert_table(manifest.pixi_table_mut()?, "environments")?;
+    if new_name == manifest::DEFAULT_ENVIRONMENT || environments.contains_key(new_name) {
+        return Err(miette::miette!("Environment '{new_name}' already exists").into());
+    }
+
+    let mut features = Array::new();
+    let mut solve_group = None;
+    let mut no_default_feature = None;
+    match environments.get(source) {
+        Some(item) if item.is_array() => {
+            features = item.as_array().cloned().unwrap_or_default();
+        }
+        Some(item) => {
+            let definition = item.as_table_like().ok_or_else(|| {
+                miette::miette!(
+                    "Expected environment '{source}' to be a table or a list of features"
+                )
+            })?;
+            if let Some(source_features) = definition.get("features").and_then(Item::as_array) {
+                features = source_features.clone();
+            }
+            solve_group = definition.get("solve-group").and_then(Item::as_str);
+            no_default_feature = definition
+                .get("no-default-feature")
+                .and_then(Item::as_bool)
+                .filter(|&no_default_feature| no_default_feature);
+        }
+        // The default environment exists without being listed
+        None if source == manifest::DEFAULT_ENVIRONMENT => {}
+        None => return Err(miette::miette!("Environment '{source}' doesn't exist").into()),
+    }
+
+    for feature in extra_features {
+        if !features.iter().any(|f| f.as_str() == Some(feature)) {
+            features.push(feature.as_str());
+        }
+    }
+    features.fmt();
+
+    let definition = if solve_group.is_none() && no_default_feature.is_none() {
+        toml_edit::value(features)
+    } else {
+        let mut definition = InlineTable::new();
+        definition.insert("features", features.into());
+        if let Some(solve_group) = solve_group {
+            definition.insert("solve-group", solve_group.into());
+        }
+        if let Some(no_default_feature) = no_default_feature {
+            definition.insert("no-default-feature", no_default_feature.into());
+        }
+        definition.fmt();
+        toml_edit::value(definition)
+    };
+    environments.insert(new_name, definition);
+
+    Ok(())
+}
+
+fn write_environment_order(manifest: &mut ManifestDocument, order: &[String]) -> Result<(), Error> {
+    let environments = manifest
+        .pixi_table_mut()?
+        .get_mut("environments")
+        .ok_or_else(|| miette::miette!("The manifest doesn't list any environments"))?;
+
+    let current: Vec<String> = environments
+        .as_table_like()
+        .ok_or_else(|| miette::miette!("Expected `environments` to be a table in the manifest"))?
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    // The default environment exists without being listed
+    let order: Vec<&str> = order
+        .iter()
+        .map(String::as_str)
+        .filter(|name| *name != manifest::DEFAULT_ENVIRONMENT || current.iter().any(|c| c == name))
+        .collect();
+    let mut sorted_current: Vec<&str> = current.iter().map(String::as_str).collect();
+    sorted_current.sort_unstable();
+    let mut sorted_order = order.clone();
+    sorted_order.sort_unstable();
+    if sorted_current != sorted_order {
+        return Err(miette::miette!(
+            "The new order [{}] doesn't match the environments [{}]",
+            order.join(", "),
+            current.join(", ")
+        )
+        .into());
+    }
+
+    let rank = |name: &str| order.iter().position(|ordered| *ordered == name);
+    match environments {
+        Item::Table(table) => {
+            table.sort_values_by(|a, _, b, _| rank(a).cmp(&rank(b)));
+            // Environments written as `[environments.name]` are ordered by their position in the
+            // document, so they swap positions instead
+            let mut positions: Vec<isize> = table
+                .iter()
+                .filter_map(|(_, item)| item.as_table()?.position())
+                .collect();
+            positions.sort_unstable();
+            let mut positions = positions.into_iter();
+            for (_, item) in table.iter_mut() {
+                if let Some(definition) = item.as_table_mut()
+                    && definition.position().is_some()
+                {
+                    definition.set_position(positions.next());
+                }
+            }
+        }
+        Item::Value(Value::InlineTable(table)) => {
+            table.sort_values_by(|a, _, b, _| rank(a).cmp(&rank(b)));
+            table.fmt();
+        }
+        // Checked to be table-like above
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Channels and platforms a new environment copies from another one
+#[derive(Debug, Default)]
+struct EnvironmentBase {
+    channels: Option<Vec<String>>,
+    platforms: Option<Vec<String>>,
+}
+
+/// Adds the environment `name` with `features` and a feature of its own name that holds the
+/// channels and platforms of the `base`
+fn write_environment_with_base(
+    manifest: &mut ManifestDocument,
+    name: &str,
+    features: &[String],
+    solve_group: Option<&str>,
+    no_default_feature: bool,
+    force: bool,
+    base: EnvironmentBase,
+) -> Result<(), Error> {
+    if let Some(feature) = features
+        .iter()
+        .find(|feature| manifest.feature_table(feature).is_none())
+    {
+        return Err(miette::miette!("Feature '{feature}' doesn't exist").into());
+    }
+    if !force {
+        let exists = manifest
+            .pixi_table()
+            .and_then(|pixi| pixi.get("environments"))
+            .is_some_and(|environments| environments.get(name).is_some());
+        if exists {
+            return Err(miette::miette!("Environment '{name}' already exists").into());
+        }
+        if manifest.feature_table(name).is_some() {
+            return Err(miette::miette!("Feature '{name}' already exists").into());
+        }
+    }
+
+    let feature = manifest.feature_table_mut(name)?;
+    for (key, values) in [("channels", base.channels), ("platforms", base.platforms)] {
+        if let Some(values) = values {
+            let mut array = Array::from_iter(values);
+            array.fmt();
+            feature.insert(key, toml_edit::value(array));
+        }
+    }
+
+    let mut feature_names = Array::from_iter(features);
+    if !features.iter().any(|feature| feature == name) {
+        feature_names.push(name);
+    }
+    feature_names.fmt();
+    let definition = if solve_group.is_none() && !no_default_feature {
+        toml_edit::value(feature_names)
+    } else {
+        let mut definition = InlineTable::new();
+        definition.insert("features", feature_names.into());
+        if let Some(solve_group) = solve_group {
+            definition.insert("solve-group", solve_group.into());
+        }
+        if no_default_feature {
+            definition.insert("no-default-feature", true.into());
+        }
+        definition.fmt();
+        toml_edit::value(definition)
+    };
+    manifest::get_or_insert_table(manifest.pixi_table_mut()?, "environments")?
+        .insert(name, definition);
+
+    Ok(())
+}
+
+/// The `[system-requirements]` of a feature, all of them are versions except `archspec`
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SystemRequirements {
+    linux: Option<String>,
+    libc: Option<String>,
+    cuda: Option<String>,
+    macos: Option<String>,
+    archspec: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_system_requirements<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+) -> Result<SystemRequirements, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    Ok(read_system_requirements(&manifest, feature))
+}
+
+#[tauri::command]
+pub async fn set_system_requirements<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+    reqs: SystemRequirements,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    write_system_requirements(manifest.feature_table_mut(feature)?, reqs)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn read_system_requirements(manifest: &ManifestDocument, feature: &str) -> SystemRequirements {
+    let Some(table) = manifest
+        .feature_table(feature)
+        .and_then(|feature| feature.get("system-requirements"))
+        .and_then(Item::as_table_like)
+    else {
+        return SystemRequirements::default();
+    };
+
+    let string = |key| table.get(key).and_then(Item::as_str).map(str::to_string);
+
+    SystemRequirements {
+        linux: string("linux"),
+        // libc can also be a table with the `family` next to the `version`
+        libc: string("libc").or_else(|| {
+            table
+                .get("libc")
+                .and_then(|libc| libc.get("version"))
+                .and_then(Item::as_str)
+                .map(str::to_string)
+        }),
+        cuda: string("cuda"),
+        macos: string("macos"),
+        archspec: string("archspec"),
+    }
+}
+
+/// Replaces the `[system-requirements]` of a feature, removing the table when nothing is left
+fn write_system_requirements(feature: &mut Table, reqs: SystemRequirements) -> Result<(), Error> {
+    let versions = [
+        ("linux", reqs.linux),
+        ("libc", reqs.libc),
+        ("cuda", reqs.cuda),
+        ("macos", reqs.macos),
+    ]
+    .map(|(key, version)| (key, version.filter(|version| !version.trim().is_empty())));
+    for (key, version) in &versions {
+        if let Some(version) = version {
+            let version = version.trim();
+            Version::from_str(version)
+                .map_err(|err| miette::miette!("Invalid {key} version '{version}': {err}"))?;
+        }
+    }
+    let archspec = reqs.archspec.filter(|archspec| !archspec.trim().is_empty());
+
+    let table = manifest::get_or_insert_table(feature, "system-requirements")?;
+    for (key, version) in versions {
+        let Some(version) = version else {
+            table.remove(key);
+            continue;
+        };
+        let version = version.trim();
+        // Keep the `family` of a libc table
+        if let Some(libc) = table.get_mut(key).and_then(Item::as_table_like_mut)
+            && key == "libc"
+        {
+            libc.insert("version", toml_edit::value(version));
+        } else {
+            table.insert(key, toml_edit::value(version));
+        }
+    }
+    match archspec {
+        Some(archspec) => {
+            table.insert("archspec", toml_edit::value(archspec.trim()));
+        }
+        None => {
+            table.remove("archspec");
+        }
+    }
+
+    remove_if_empty(feature, "system-requirements");
+
+    Ok(())
+}
+
+/// Activation of a feature, env vars are ordered because they can reference each other
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Activation {
+    scripts: Vec<String>,
+    env: IndexMap<String, String>,
+}
+
+#[tauri::command]
+pub async fn list_activation<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+) -> Result<Activation, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    Ok(read_activation(&manifest, feature))
+}
+
+#[tauri::command]
+pub async fn set_activation_env<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+    env: IndexMap<String, String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    write_activation_env(manifest.feature_table_mut(feature)?, env)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_activation_scripts<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+    scripts: Vec<String>,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    write_activation_scripts(manifest.feature_table_mut(feature)?, scripts)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn read_activation(manifest: &ManifestDocument, feature: &str) -> Activation {
+    let Some(activation) = manifest
+        .feature_table(feature)
+        .and_then(|feature| feature.get("activation"))
+    else {
+        return Activation::default();
+    };
+
+    Activation {
+        scripts: activation
+            .get("scripts")
+            .and_then(Item::as_array)
+            .map(|scripts| {
+                scripts
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        env: activation
+            .get("env")
+            .and_then(Item::as_table_like)
+            .map(|env| {
+                env.iter()
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.as_str()?.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn write_activation_env(feature: &mut Table, env: IndexMap<String, String>) -> Result<(), Error> {
+    if let Some(name) = env.keys().find(|name| name.trim().is_empty()) {
+        return Err(miette::miette!("Invalid environment variable name '{name}'").into());
+    }
+
+    let activation = manifest::get_or_insert_table(feature, "activation")?;
+    if env.is_empty() {
+        activation.remove("env");
+    } else {
+        let mut table = Table::new();
+        for (name, value) in env {
+            table.insert(&name, toml_edit::value(value));
+        }
+        activation.insert("env", Item::Table(table));
+    }
+    remove_if_empty(feature, "activation");
+
+    Ok(())
+}
+
+fn write_activation_scripts(feature: &mut Table, scripts: Vec<String>) -> Result<(), Error> {
+    let scripts: Array = scripts
+        .iter()
+        .map(|script| script.trim())
+        .filter(|script| !script.is_empty())
+        .collect();
+
+    let activation = manifest::get_or_insert_table(feature, "activation")?;
+    if scripts.is_empty() {
+        activation.remove("scripts");
+    } else {
+        activation.insert("scripts", toml_edit::value(scripts));
+    }
+    remove_if_empty(feature, "activation");
+
+    Ok(())
+}
+
+/// Variables of the activated environment, like `PATH` and `CONDA_PREFIX`, e.g. to start tools in
+/// the environment without going through a shell. Installs the environment if needed.
+#[tauri::command]
+pub async fn activated_env<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    environment: Option<String>,
+) -> Result<HashMap<String, String>, Error> {
+    if let Some(environment) = &environment {
+        utils::parse_environment_name(environment)?;
+    }
+    let manifest = manifest(window, workspace).await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let environment = environment.as_deref();
+        let json = shell_hook(&manifest, environment, &["--json"])
+            .and_then(|output| Ok(serde_json::from_str::<ShellHook>(&output).into_diagnostic()?));
+        match json {
+            Ok(hook) => Ok(hook.environment_variables),
+            Err(e) => {
+                // Older pixi versions can't print the variables directly
+                log::debug!("Falling back to parsing the shell hook: {}", e.0);
+                let script = shell_hook(&manifest, environment, &["--shell", "bash"])?;
+                Ok(parse_shell_hook(&script))
+            }
+        }
+    })
+    .await
+    .into_diagnostic()?
+}
+
+#[derive(Deserialize)]
+struct ShellHook {
+    environment_variables: HashMap<String, String>,
+}
+
+/// Output of `pixi shell-hook` with the given arguments
+fn shell_hook(manifest: &Path, environment: Option<&str>, args: &[&str]) -> Result<String, Error> {
+    let mut command = crate::pty::pixi_command();
+    command
+        .arg("shell-hook")
+        .arg("--manifest-path")
+        .arg(manifest)
+        .args(args);
+    if let Some(environment) = environment {
+        command.args(["--environment", environment]);
+    }
+
+    let output = command
+        .output()
+        .into_diagnostic()
+        .wrap_err("Failed to run pixi")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(miette::miette!(
+            "Failed to activate the environment: {}",
+            utils::strip_ansi_escapes(stderr.trim())
+        )
+        .into());
+    }
+    Ok(String::from_utf8(output.stdout).into_diagnostic()?)
+}
+
+/// The variables exported by a bash shell hook. Variables set by activation scripts the hook
+/// sources aren't included.
+fn parse_shell_hook(script: &str) -> HashMap<String, String> {
+    script
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("export "))
+        .filter_map(|assignment| {
+            let (name, value) = assignment.split_once('=')?;
+            Some((name.trim().to_string(), shell_word(value)))
+        })
+        .collect()
+}
+
+/// Unquotes the first shell word of `value`, e.g. `"a b"'c'` becomes `a bc`
+fn shell_word(value: &str) -> String {
+    let mut word = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => word.extend(chars.by_ref().take_while(|&c| c != '\'')),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => word.push(escaped),
+                            Some(other) => word.extend(['\\', other]),
+                            None => word.push('\\'),
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.extend(chars.next()),
+            c if c.is_whitespace() || c == ';' => break,
+            c => word.push(c),
+        }
+    }
+    word
+}
+
+fn remove_if_empty(table: &mut Table, key: &str) {
+    if table
+        .get(key)
+        .and_then(Item::as_table_like)
+        .is_some_and(|table| table.is_empty())
+    {
+        table.remove(key);
+    }
+}
+
+/// The `[pypi-options]` of a feature that are about where packages come from
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PypiOptions {
+    index_url: Option<String>,
+    extra_index_urls: Vec<String>,
+    /// URLs or paths of directories with distributions
+    find_links: Vec<String>,
+    no_build_isolation: Option<NoBuildIsolation>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NoBuildIsolation {
+    All(bool),
+    Packages(Vec<String>),
+}
+
+#[tauri::command]
+pub async fn get_pypi_options<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+) -> Result<PypiOptions, Error> {
+    let workspace = utils::workspace(workspace)?;
+    let manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    Ok(read_pypi_options(&manifest, feature))
+}
+
+#[tauri::command]
+pub async fn set_pypi_options<R: Runtime>(
+    window: Window<R>,
+    workspace: PathBuf,
+    feature: Option<String>,
+    options: PypiOptions,
+) -> Result<(), Error> {
+    let workspace = utils::workspace(workspace)?;
+    let mut manifest = ManifestDocument::load(&workspace)?;
+
+    let feature = feature.as_deref().unwrap_or(manifest::DEFAULT_FEATURE);
+    write_pypi_options(manifest.feature_table_mut(feature)?, options)?;
+    manifest.save()?;
+
+    Ok(())
+}
+
+fn read_pypi_options(manifest: &ManifestDocument, feature: &str) -> PypiOptions {
+    let Some(options) = manifest
+        .feature_table(feature)
+        .and_then(|feature| feature.get("pypi-options"))
+    else {
+        return PypiOptions::default();
+    };
+    let strings = |item: &Item| -> Vec<String> {
+        item.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    };
+
+    PypiOptions {
+        index_url: options
+            .get("index-url")
+            .and_then(Item::as_str)
+            .map(str::to_string),
+        extra_index_urls: options
+            .get("extra-index-urls")
+            .map(strings)
+            .unwrap_or_default(),
+        // Each link is a table like `{ url = "..." }` or `{ path = "..." }`
+        find_links: options
+            .get("find-links")
+            .and_then(Item::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_inline_table)
+            .filter_map(|link| link.get("url").or_else(|| link.get("path")))
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        no_build_isolation: options.get("no-build-isolation").and_then(|item| {
+            match item.as_bool() {
+                Some(all) => Some(NoBuildIsolation::All(all)),
+                None => item
+                    .is_array()
+                    .then(|| NoBuildIsolation::Packages(strings(item))),
+            }
+        }),
+    }
+}
+
+/// Replaces the index settings in `[pypi-options]`, other settings in the table are kept
+/// Anything that doesn't parse as a URL is a local directory. Windows paths like `C:\wheels`
+/// parse as a URL with the scheme `c`, so single letter schemes are drive letters.
+fn is_find_links_url(link: &str) -> bool {
+    Url::parse(link).is_ok_and(|url| url.scheme().len() > 1)
+}
+
+fn write_pypi_options(feature: &mut Table, options: PypiOptions) -> Result<(), Error> {
+    let index_url = options
+        .index_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    if let Some(url) = index_url {
+        validate_index_url(url)?;
+    }
+
+    let mut extra_index_urls = IndexSet::new();
+    for url in &options.extra_index_urls {
+        let url = url.trim();
+        if !url.is_empty() {
+            validate_index_url(url)?;
+            extra_index_urls.insert(url);
+        }
+    }
+
+    let mut find_links = Array::new();
+    for link in &options.find_links {
+        let link = link.trim();
+        if link.is_empty() {
+            continue;
+        }
+        let mut table = InlineTable::new();
+        if is_find_links_url(link) {
+            table.insert("url", link.into());
+        } else {
+            table.insert("path", link.into());
+        }
+        find_links.push(table);
+    }
+
+    let table = manifest::get_or_insert_table(feature, "pypi-options")?;
+    set_or_remove(table, "index-url", index_url.unwrap_or_default());
+    if extra_index_urls.is_empty() {
+        table.remove("extra-index-urls");
+    } else {
+        table.insert(
+            "extra-index-urls",
+            toml_edit::value(extra_index_urls.into_iter().collect::<Array>()),
+        );
+    }
+    if find_links.is_empty() {
+        table.remove("find-links");
+    } else {
+        table.insert("find-links", toml_edit::value(find_links));
+    }
+    match options.no_build_isolation {
+        None | Some(NoBuildIsolation::All(false)) => {
+            table.remove("no-build-isolation");
+        }
+        Some(NoBuildIsolation::All(true)) => {
+            table.insert("no-build-isolation", toml_edit::value(true));
+        }
+        Some(NoBuildIsolation::Packages(packages)) => {
+            let packages: Array = packages
+                .iter()
+                .map(|package| package.trim())
+                .filter(|package| !package.is_empty())
+                .collect();
+            if packages.is_empty() {
+                table.remove("no-build-isolation");
+            } else {
+                table.insert("no-build-isolation", toml_edit::value(packages));
+            }
+        }
+    }
+    remove_if_empty(feature, "pypi-options");
+
+    Ok(())
+}
+
+fn validate_index_url(url: &str) -> Result<(), Error> {
+    Url::parse(url).map_err(|err| miette::miette!("Invalid index URL '{url}': {err}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> ManifestDocument {
+        ManifestDocument::parse(PathBuf::from("pixi.toml"), contents).unwrap()
+    }
+
+    #[test]
+    fn toml_syntax_errors_point_at_the_problem() {
+        assert!(toml_syntax_diagnostic("[workspace]\nname = \"demo\"\n").is_none());
+
+        let contents = "[workspace]\nname = \"demo\nchannels = []\n";
+        let diagnostic = toml_syntax_diagnostic(contents).unwrap();
+        let (offset, _) = diagnostic.span.unwrap();
+        assert!(contents[..offset].starts_with("[workspace]\nname = "));
+        assert!(!diagnostic.message.is_empty());
+    }
+
+    #[test]
+    fn editable_is_toggled_in_place() {
+        let mut manifest = parse(
+            r#"[pypi-dependencies]
+My_Lib = { path = "./lib", extras = ["cli"] }
+requests = ">=2"
+"#,
+        );
+
+        write_pypi_editable(&mut manifest, manifest::DEFAULT_FEATURE, "my-lib", true).unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[pypi-dependencies]
+My_Lib = { path = "./lib", extras = ["cli"], editable = true }
+requests = ">=2"
+"#
+        );
+
+        write_pypi_editable(&mut manifest, manifest::DEFAULT_FEATURE, "my-lib", false).unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[pypi-dependencies]
+My_Lib = { path = "./lib", extras = ["cli"] }
+requests = ">=2"
+"#
+        );
+
+        assert!(
+            write_pypi_editable(&mut manifest, manifest::DEFAULT_FEATURE, "requests", true)
+                .is_err()
+        );
+        assert!(write_pypi_editable(&mut manifest, "dev", "my-lib", true).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn members_are_the_root_and_path_dependencies() {
+        let manifest = parse(
+            r#"[workspace]
+name = "demo"
+
+[dependencies]
+python = "3.12.*"
+core = { path = "./packages/core" }
+
+[feature.cli.target.linux-64.dependencies]
+cli = { path = "packages/cli" }
+core = { path = "packages/core" }
+"#,
+        );
+
+        assert_eq!(
+            workspace_members(&manifest, Path::new("/repo"), "demo".into()),
+            [
+                WorkspaceMember {
+                    name: "demo".into(),
+                    path: "/repo".into(),
+                },
+                WorkspaceMember {
+                    name: "core".into(),
+                    path: "/repo/packages/core".into(),
+                },
+                WorkspaceMember {
+                    name: "cli".into(),
+                    path: "/repo/packages/cli".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn root_package_names_the_root() {
+        let manifest = parse(
+            r#"[workspace]
+name = "demo"
+
+[package]
+name = "demo-app"
+"#,
+        );
+
+        let members = workspace_members(&manifest, Path::new("repo"), "demo".into());
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "demo-app");
+    }
+
+    #[test]
+    fn write_and_clear_project_info() {
+        let mut manifest = parse(
+            r#"[workspace]
+name = "demo"
+homepage = "https://example.com"
+"#,
+        );
+
+        write_project_info(
+            manifest.workspace_table_mut().unwrap(),
+            ProjectInfo {
+                authors: Some(vec!["Jane Doe <jane@example.com>".into(), " ".into()]),
+                license: Some("MIT OR Apache-2.0".into()),
+                homepage: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[workspace]
+name = "demo"
+authors = ["Jane Doe <jane@example.com>"]
+license = "MIT OR Apache-2.0"
+"#
+        );
+    }
+
+    #[test]
+    fn invalid_url_is_rejected() {
+        let mut manifest = parse("[workspace]\nname = \"demo\"\n");
+
+        let result = write_project_info(
+            manifest.workspace_table_mut().unwrap(),
+            ProjectInfo {
+                repository: Some("not a url".into()),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn read_pyproject_authors() {
+        let manifest = ManifestDocument::parse(
+            PathBuf::from("pyproject.toml"),
+            r#"[project]
+name = "demo"
+authors = [{ name = "Jane Doe", email = "jane@example.com" }, { name = "John" }]
+license = "MIT"
+"#,
+        )
+        .unwrap();
+
+        let info = read_project_info(&manifest);
+        assert_eq!(
+            info.authors,
+            Some(vec!["Jane Doe <jane@example.com>".into(), "John".into()])
+        );
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn solve_group_on_short_environment() {
+        let mut manifest = parse(
+            r#"[environments]
+test = ["test"]
+lint = { features = ["lint"], solve-group = "default" }
+"#,
+        );
+
+        write_solve_group(&mut manifest, "test", Some("default")).unwrap();
+        write_solve_group(&mut manifest, "lint", None).unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[environments]
+test = { features = ["test"], solve-group = "default" }
+lint = ["lint"]
+"#
+        );
+    }
+
+    #[test]
+    fn solve_group_on_implicit_default_environment() {
+        let mut manifest = parse("[workspace]\nname = \"demo\"\n");
+
+        write_solve_group(&mut manifest, "default", Some("main")).unwrap();
+        assert!(write_solve_group(&mut manifest, "missing", Some("main")).is_err());
+
+        assert_eq!(
+            manifest.to_string(),
+            "[workspace]\nname = \"demo\"\n\n[environments]\ndefault = { solve-group = \"main\" }\n"
+        );
+    }
+
+    #[test]
+    fn pixi_version_against_requirement() {
+        assert_eq!(parse_pixi_version("pixi 0.50.2\n"), Some("0.50.2"));
+        assert_eq!(parse_pixi_version("error: not found"), None);
+
+        assert!(satisfies("0.50.2", ">=0.40").unwrap());
+        assert!(!satisfies("0.39.0", ">=0.40").unwrap());
+        assert!(satisfies("0.50.2", "not a spec").is_err());
+    }
+
+    #[test]
+    fn rename_feature_tables_and_environments() {
+        let mut manifest = parse(
+            r#"[workspace]
+name = "demo"
+
+# Testing tools
+[feature.test.dependencies]
+pytest = "*"
+
+[feature.test.target.linux-64.dependencies]
+pytest-xdist = "*"
+
+[feature.testing-extras]
+tasks = { cover = "pytest --cov" }
+
+[feature.lint.dependencies]
+ruff = "*"
+
+[environments]
+test = ["test"]
+all = { features = [ "lint",  "test" ], solve-group = "default" }
+lint = ["lint"]
+
+[environments.dev]
+features = ["test"]
+"#,
+        );
+
+        write_feature_rename(&mut manifest, "test", "tests").unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[workspace]
+name = "demo"
+
+# Testing tools
+[feature.tests.dependencies]
+pytest = "*"
+
+[feature.tests.target.linux-64.dependencies]
+pytest-xdist = "*"
+
+[feature.testing-extras]
+tasks = { cover = "pytest --cov" }
+
+[feature.lint.dependencies]
+ruff = "*"
+
+[environments]
+test = ["tests"]
+all = { features = [ "lint",  "tests" ], solve-group = "default" }
+lint = ["lint"]
+
+[environments.dev]
+features = ["tests"]
+"#
+        );
+    }
+
+    #[test]
+    fn rename_feature_in_pyproject() {
+        let mut manifest = ManifestDocument::parse(
+            PathBuf::from("pyproject.toml"),
+            r#"[project]
+name = "demo"
+
+[tool.pixi.feature.docs.dependencies]
+mkdocs = "*"
+
+[tool.pixi.environments]
+docs = ["docs"]
+"#,
+        )
+        .unwrap();
+
+        write_feature_rename(&mut manifest, "docs", "documentation").unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[project]
+name = "demo"
+
+[tool.pixi.feature.documentation.dependencies]
+mkdocs = "*"
+
+[tool.pixi.environments]
+docs = ["documentation"]
+"#
+        );
+    }
+
+    #[test]
+    fn rename_feature_rejects_invalid_names() {
+        let mut manifest = parse(
+            r#"[feature.test.dependencies]
+pytest = "*"
+
+[feature.lint.dependencies]
+ruff = "*"
+"#,
+        );
+
+        assert!(write_feature_rename(&mut manifest, "default", "base").is_err());
+        assert!(write_feature_rename(&mut manifest, "test", "default").is_err());
+        assert!(write_feature_rename(&mut manifest, "test", "lint").is_err());
+        assert!(write_feature_rename(&mut manifest, "missing", "other").is_err());
+        assert!(manifest.feature_table("test").is_some());
+    }
+
+    #[test]
+    fn duplicate_environment_inherits_the_solve_group() {
+        let mut manifest = parse(
+            r#"[feature.test.dependencies]
+pytest = "*"
+
+[feature.cuda.dependencies]
+cuda = "*"
+
+[environments]
+test = { features = ["test"], solve-group = "default", no-default-feature = true }
+lint = ["lint"]
+"#,
+        );
+
+        write_duplicate_environment(&mut manifest, "test", "test-cuda", &["cuda".into()]).unwrap();
+        write_duplicate_environment(&mut manifest, "lint", "lint-test", &["test".into()]).unwrap();
+        write_duplicate_environment(&mut manifest, "default", "plain", &[]).unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[feature.test.dependencies]
+pytest = "*"
+
+[feature.cuda.dependencies]
+cuda = "*"
+
+[environments]
+test = { features = ["test"], solve-group = "default", no-default-feature = true }
+lint = ["lint"]
+test-cuda = { features = ["test", "cuda"], solve-group = "default", no-default-feature = true }
+lint-test = ["lint", "test"]
+plain = []
+"#
+        );
+    }
+
+    #[test]
+    fn duplicate_environment_rejects_existing_names() {
+        let mut manifest = parse("[environments]\ntest = [\"test\"]\n");
+
+        assert!(write_duplicate_environment(&mut manifest, "test", "test", &[]).is_err());
+        assert!(write_duplicate_environment(&mut manifest, "test", "default", &[]).is_err());
+        assert!(write_duplicate_environment(&mut manifest, "missing", "new", &[]).is_err());
+        assert!(
+            write_duplicate_environment(&mut manifest, "test", "new", &["missing".into()]).is_err()
+        );
+    }
+
+    #[test]
+    fn reorder_environments_keeps_their_definitions() {
+        let mut manifest = parse(
+            r#"[environments]
+# Runs the tests
+test = { features = ["test"], solve-group = "default" }
+lint = ["lint"]
+
+[environments.docs]
+features = ["docs"]
+no-default-feature = true
+
+[environments.cuda]
+features = ["cuda"]
+"#,
+        );
+
+        write_environment_order(
+            &mut manifest,
+            &[
+                "cuda".into(),
+                "default".into(),
+                "lint".into(),
+                "docs".into(),
+                "test".into(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[environments]
+lint = ["lint"]
+# Runs the tests
+test = { features = ["test"], solve-group = "default" }
+
+[environments.cuda]
+features = ["cuda"]
+
+[environments.docs]
+features = ["docs"]
+no-default-feature = true
+"#
+        );
+
+        let mut manifest = parse("environments = { test = [\"test\"], lint = [\"lint\"] }\n");
+        write_environment_order(&mut manifest, &["lint".into(), "test".into()]).unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            "environments = { lint = [\"lint\"], test = [\"test\"] }\n"
+        );
+    }
+
+    #[test]
+    fn reorder_environments_needs_all_environments() {
+        let mut manifest = parse("[environments]\ntest = [\"test\"]\nlint = [\"lint\"]\n");
+
+        assert!(write_environment_order(&mut manifest, &["test".into()]).is_err());
+        assert!(write_environment_order(&mut manifest, &["test".into(), "test".into()]).is_err());
+        assert!(
+            write_environment_order(
+                &mut manifest,
+                &["lint".into(), "test".into(), "docs".into()]
+            )
+            .is_err()
+        );
+        assert!(write_environment_order(&mut parse("[workspace]\n"), &[]).is_err());
+    }
+
+    #[test]
+    fn environment_with_base_gets_its_own_feature() {
+        let mut manifest = parse("[feature.test.dependencies]\npytest = \"*\"\n");
+
+        write_environment_with_base(
+            &mut manifest,
+            "ci",
+            &["test".into()],
+            Some("default"),
+            false,
+            false,
+            EnvironmentBase {
+                channels: Some(vec!["conda-forge".into()]),
+                platforms: Some(vec!["linux-64".into(), "osx-arm64".into()]),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[feature.test.dependencies]
+pytest = "*"
+
+[feature.ci]
+channels = ["conda-forge"]
+platforms = ["linux-64", "osx-arm64"]
+
+[environments]
+ci = { features = ["test", "ci"], solve-group = "default" }
+"#
+        );
+
+        let base = || EnvironmentBase::default();
+        assert!(
+            write_environment_with_base(&mut manifest, "ci", &[], None, false, false, base())
+                .is_err()
+        );
+        assert!(
+            write_environment_with_base(&mut manifest, "ci", &[], None, false, true, base())
+                .is_ok()
+        );
+        assert!(
+            write_environment_with_base(
+                &mut manifest,
+                "new",
+                &["missing".into()],
+                None,
+                false,
+                false,
+                base()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn write_system_requirements_of_feature() {
+        let mut manifest = parse(
+            r#"[feature.gpu.system-requirements]
+libc = { family = "glibc", version = "2.17" }
+macos = "12.0"
+"#,
+        );
+
+        write_system_requirements(
+            manifest.feature_table_mut("gpu").unwrap(),
+            SystemRequirements {
+                libc: Some("2.28".into()),
+                cuda: Some("12".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[feature.gpu.system-requirements]
+libc = { family = "glibc", version = "2.28" }
+cuda = "12"
+"#
+        );
+        assert_eq!(
+            read_system_requirements(&manifest, "gpu"),
+            SystemRequirements {
+                libc: Some("2.28".into()),
+                cuda: Some("12".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_system_requirement_is_rejected() {
+        let reqs = serde_json::from_str::<SystemRequirements>(r#"{ "glibc": "2.17" }"#);
+        assert!(reqs.is_err());
+    }
+
+    #[test]
+    fn activation_round_trip() {
+        let mut manifest = parse("[feature.dev.dependencies]\npython = \"*\"\n");
+
+        let feature = manifest.feature_table_mut("dev").unwrap();
+        write_activation_scripts(feature, vec!["scripts/activate.sh".into()]).unwrap();
+        write_activation_env(
+            feature,
+            IndexMap::from([
+                (
+                    "DATA_DIR".to_string(),
+                    "$PIXI_PROJECT_ROOT/data".to_string(),
+                ),
+                ("CACHE_DIR".to_string(), "$DATA_DIR/cache".to_string()),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_activation(&manifest, "dev"),
+            Activation {
+                scripts: vec!["scripts/activate.sh".into()],
+                env: IndexMap::from([
+                    (
+                        "DATA_DIR".to_string(),
+                        "$PIXI_PROJECT_ROOT/data".to_string()
+                    ),
+                    ("CACHE_DIR".to_string(), "$DATA_DIR/cache".to_string()),
+                ]),
+            }
+        );
+
+        let feature = manifest.feature_table_mut("dev").unwrap();
+        write_activation_scripts(feature, Vec::new()).unwrap();
+        write_activation_env(feature, IndexMap::new()).unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            "[feature.dev.dependencies]\npython = \"*\"\n"
+        );
+    }
+
+    #[test]
+    fn parse_bash_shell_hook() {
+        let script = r#"export PATH="/ws/.pixi/envs/default/bin:/usr/bin"
+export CONDA_PREFIX='/ws/.pixi/envs/default'
+export GREETING="say \"hi\" for \$5"
+export QUOTED='it'"'"'s'
+. "/ws/.pixi/envs/default/etc/conda/activate.d/activate.sh"
+"#;
+
+        assert_eq!(
+            parse_shell_hook(script),
+            HashMap::from([
+                (
+                    "PATH".to_string(),
+                    "/ws/.pixi/envs/default/bin:/usr/bin".to_string()
+                ),
+                (
+                    "CONDA_PREFIX".to_string(),
+                    "/ws/.pixi/envs/default".to_string()
+                ),
+                ("GREETING".to_string(), "say \"hi\" for $5".to_string()),
+                ("QUOTED".to_string(), "it's".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn write_pypi_options_keeps_other_settings() {
+        let mut manifest = parse(
+            r#"[pypi-options]
+index-strategy = "unsafe-best-match"
+"#,
+        );
+
+        write_pypi_options(
+            manifest
+                .feature_table_mut(manifest::DEFAULT_FEATURE)
+                .unwrap(),
+            PypiOptions {
+                index_url: Some("https://pypi.example.com/simple".into()),
+                extra_index_urls: vec![
+                    "https://mirror.example.com/simple".into(),
+                    "https://mirror.example.com/simple ".into(),
+                ],
+                find_links: vec![
+                    "./wheels".into(),
+                    r"C:\wheels".into(),
+                    "https://download.example.com/wheels".into(),
+                ],
+                no_build_isolation: Some(NoBuildIsolation::Packages(vec!["detectron2".into()])),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_string(),
+            r#"[pypi-options]
+index-strategy = "unsafe-best-match"
+index-url = "https://pypi.example.com/simple"
+extra-index-urls = ["https://mirror.example.com/simple"]
+find-links = [{ path = "./wheels" }, { path = 'C:\wheels' }, { url = "https://download.example.com/wheels" }]
+no-build-isolation = ["detectron2"]
+"#
+        );
+        assert_eq!(
+            read_pypi_options(&manifest, manifest::DEFAULT_FEATURE).find_links,
+            vec![
+                "./wheels".to_string(),
+                r"C:\wheels".to_string(),
+                "https://download.example.com/wheels".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_index_url_is_rejected() {
+        let mut manifest = parse("");
+
+        let result = write_pypi_options(
+            manifest.feature_table_mut("private").unwrap(),
+            PypiOptions {
+                index_url: Some("pypi.example.com".into()),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn toggle_preview_features() {
+        let mut manifest = parse("[workspace]\nname = \"demo\"\n");
+
+        let workspace = manifest.workspace_table_mut().unwrap();
+        write_preview_feature(workspace, "pixi-build", true);
+        write_preview_feature(workspace, "future-feature", true);
+        write_preview_feature(workspace, "pixi-build", true);
+
+        assert_eq!(
+            read_preview_features(&manifest).enabled,
+            vec![
+                PreviewFeature {
+                    name: "pixi-build".into(),
+                    recognized: true,
+                },
+                PreviewFeature {
+                    name: "future-feature".into(),
+                    recognized: false,
+                },
+            ]
+        );
+
+        let workspace = manifest.workspace_table_mut().unwrap();
+        write_preview_feature(workspace, "pixi-build", false);
+        write_preview_feature(workspace, "future-feature", false);
+        assert_eq!(manifest.to_string(), "[workspace]\nname = \"demo\"\n");
+    }
+
+    #[test]
+    fn reorder_channels_keeps_entries() {
+        let mut manifest = parse(
+            r#"[workspace]
+channels = ["conda-forge", { channel = "bioconda", priority = 1 }, "pytorch"]
+"#,
+        );
+
+        let channels = manifest.workspace_table_mut().unwrap()["channels"]
+            .as_array_mut()
+            .unwrap();
+        reorder_channel_array(
+            channels,
+            &["pytorch".into(), "conda-forge".into(), "bioconda".into()],
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[workspace]
+channels = ["pytorch", "conda-forge", { channel = "bioconda", priority = 1 }]
+"#
+        );
+
+        let channels = manifest.workspace_table_mut().unwrap()["channels"]
+            .as_array_mut()
+            .unwrap();
+        assert!(
+            reorder_channel_array(channels, &["pytorch".into(), "conda-forge".into()]).is_err()
+        );
+    }
+
+    fn change(
+        channel: &str,
+        feature: &str,
+        priority: Option<i32>,
+    ) -> (String, String, Option<i32>) {
+        (channel.to_string(), feature.to_string(), priority)
+    }
+
+    #[test]
+    fn channels_are_added_together() {
+        let mut manifest = parse(
+            r#"[workspace]
+channels = ["conda-forge"]
+"#,
+        );
+
+        write_added_channels(
+            &mut manifest,
+            &[
+                change("bioconda", manifest::DEFAULT_FEATURE, None),
+                change("pytorch", manifest::DEFAULT_FEATURE, Some(2)),
+                change("conda-forge", manifest::DEFAULT_FEATURE, None),
+                change("nvidia", "gpu", None),
+            ],
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[workspace]
+channels = ["bioconda", { channel = "pytorch", priority = 2 }, "conda-forge"]
+
+[feature.gpu]
+channels = ["nvidia"]
+"#
+        );
+    }
+
+    #[test]
+    fn channels_are_only_removed_if_all_exist() {
+        let contents = r#"[workspace]
+channels = ["conda-forge", { channel = "bioconda", priority = 1 }]
+
+[feature.gpu]
+channels = ["nvidia"]
+"#;
+        let mut manifest = parse(contents);
+
+        let err = write_removed_channels(
+            &mut manifest,
+            &[
+                change("bioconda", manifest::DEFAULT_FEATURE, None),
+                change("pytorch", "gpu", None),
+            ],
+        )
+        .unwrap_err();
+        assert!(
+            err.0
+                .to_string()
+                .contains("'pytorch' isn't a channel of feature 'gpu'")
+        );
+        assert_eq!(manifest.to_string(), contents);
+
+        write_removed_channels(
+            &mut manifest,
+            &[
+                change("bioconda", manifest::DEFAULT_FEATURE, None),
+                change("nvidia", "gpu", None),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.to_string(),
+            r#"[workspace]
+channels = ["conda-forge"]
+
+[feature.gpu]
+channels = []
+"#
+        );
+    }
+
+    #[test]
+    fn invalid_channels_are_reported_together() {
+        let changes = ["conda-forge", " ", "https://"].map(|channel| ChannelChange {
+            channel: channel.to_string(),
+            feature: None,
+            priority: None,
+        });
+        let err = parse_channel_changes(&changes).unwrap_err();
+        let message = err.0.to_string();
+        assert!(message.starts_with("Invalid channels:"), "{message}");
+        assert!(!message.contains("conda-forge"), "{message}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_is_found_in_the_environment() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let prefix = tempfile::tempdir().unwrap();
+        let prefix = prefix.path();
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        let python = prefix.join("bin").join("python");
+        std::fs::write(&python, "").unwrap();
+        std::fs::set_permissions(&python, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(find_executable(prefix, "python").unwrap(), Some(python));
+        assert_eq!(find_executable(prefix, "ruff").unwrap(), None);
+        assert!(find_executable(prefix, "../bin/python").is_err());
+    }
+
+    #[test]
+    fn prefix_conda_forge_differs_from_short_name() {
+        let alias = "https://conda.anaconda.org/";
+
+        let short = channel_info(
+            "conda-forge".into(),
+            "https://conda.anaconda.org/conda-forge/",
+            alias,
+        );
+        let prefix = channel_info(
+            "https://prefix.dev/conda-forge".into(),
+            "https://prefix.dev/conda-forge/",
+            alias,
+        );
+
+        assert_eq!(short.display_name, prefix.display_name);
+        assert_ne!(short.canonical_url, prefix.canonical_url);
+        assert!(short.is_default);
+        assert_eq!(short.description, None);
+        assert!(!prefix.is_default);
+        assert_eq!(prefix.description.as_deref(), Some("Hosted on prefix.dev"));
+    }
+}
@@ -1,5 +1,6 @@
 use log::error;
 use std::os::windows::process::CommandExt;
+use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
 use windows_sys::Win32::System::Threading::{CREATE_NEW_PROCESS_GROUP, DETACHED_PROCESS};
 
 /// Relaunch the app as a detached process so the terminal is not blocked.
@@ -36,3 +37,14 @@ pub fn relaunch_detached(workspace: Option<&str>) -> bool {
         }
     }
 }
+
+/// Attach to the console of the terminal the app was started from.
+///
+/// Release builds use the windows subsystem, so they don't get a console for their output
+/// otherwise.
+pub fn attach_parent_console() {
+    // SAFETY: only fails if there's no parent console, which is fine.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
@@ -0,0 +1,284 @@
+//! Progress of pixi operations, read from the progress bars pixi draws.
+//!
+//! pixi_api has no progress callbacks, pixi reports progress by drawing indicatif bars to its
+//! global `MultiProgress`. [`install`] replaces their terminal with a [`BarReader`], which parses
+//! every drawn frame and forwards the bars to the subscribed operations.
+
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use indicatif::{ProgressDrawTarget, TermLike};
+
+use crate::utils;
+
+/// How often pixi's bars get drawn per second
+const REFRESH_RATE: u8 = 10;
+
+/// Wide enough that pixi doesn't truncate the text next to its bars
+const WIDTH: u16 = 500;
+
+/// Tall enough that pixi doesn't skip any of its bars
+const HEIGHT: u16 = 200;
+
+/// A progress bar as pixi drew it, e.g. `solving default [00:00:01] [━━━━╸───]    3/10`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bar {
+    /// The text in front of the bar, e.g. `solving default`
+    pub label: String,
+    /// The finished and the total steps of bars like `3/10`
+    pub steps: Option<(u64, u64)>,
+    /// The downloaded bytes and, if the bar shows it, the total bytes
+    pub bytes: Option<(u64, Option<u64>)>,
+}
+
+pub trait BarListener: Send + Sync {
+    /// Called with all bars pixi shows whenever it draws them
+    fn bars(&self, bars: &[Bar]);
+}
+
+type Listeners = Vec<(u64, Arc<dyn BarListener>)>;
+
+static LISTENERS: LazyLock<Mutex<Listeners>> = LazyLock::new(Default::default);
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Draws pixi's progress bars to the subscribed listeners instead of the terminal
+pub fn install() {
+    let target = ProgressDrawTarget::term_like_with_hz(Box::<BarReader>::default(), REFRESH_RATE);
+    pixi_progress::global_multi_progress().set_draw_target(target);
+}
+
+/// Forwards pixi's progress bars to `listener` until the subscription is dropped.
+///
+/// pixi's bars are global, concurrent operations see each other's bars.
+pub fn subscribe(listener: Arc<dyn BarListener>) -> Subscription {
+    let id = NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    LISTENERS.lock().unwrap().push((id, listener));
+    Subscription { id }
+}
+
+/// Unsubscribes its listener when dropped
+pub struct Subscription {
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        LISTENERS.lock().unwrap().retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// A terminal that collects the lines indicatif draws, a frame ends with `flush`
+#[derive(Debug, Default)]
+struct BarReader {
+    frame: Mutex<String>,
+}
+
+impl TermLike for BarReader {
+    fn width(&self) -> u16 {
+        WIDTH
+    }
+
+    fn height(&self) -> u16 {
+        HEIGHT
+    }
+
+    fn move_cursor_up(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_down(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_line(&self, s: &str) -> io::Result<()> {
+        let mut frame = self.frame.lock().unwrap();
+        frame.push_str(s);
+        frame.push('\n');
+        Ok(())
+    }
+
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.frame.lock().unwrap().push_str(s);
+        Ok(())
+    }
+
+    fn clear_line(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        let frame = std::mem::take(&mut *self.frame.lock().unwrap());
+        let bars: Vec<Bar> = frame.lines().filter_map(parse_bar).collect();
+        if bars.is_empty() {
+            return Ok(());
+        }
+
+        // Listeners may take a while, they don't block new subscriptions meanwhile
+        let listeners: Vec<_> = LISTENERS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener.bars(&bars);
+        }
+        Ok(())
+    }
+}
+
+/// Parses a line of pixi's progress bars, the label comes before the first `[` (the elapsed time
+/// or the bar itself). Lines without steps or bytes aren't bars, e.g. messages printed above them.
+fn parse_bar(line: &str) -> Option<Bar> {
+    let line = utils::strip_ansi_escapes(line);
+    let (label, rest) = line.split_once(" [")?;
+    // Drops the spinner in front of the label
+    let label = label
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim();
+    let steps = rest.split_whitespace().find_map(parse_steps);
+    let bytes = parse_bytes(rest);
+
+    if label.is_empty() || (steps.is_none() && bytes.is_none()) {
+        return None;
+    }
+    Some(Bar {
+        label: label.to_string(),
+        steps,
+        bytes,
+    })
+}
+
+/// `3/10` as rendered by indicatif's `{pos}/{len}`
+fn parse_steps(token: &str) -> Option<(u64, u64)> {
+    let (pos, len) = token.split_once('/')?;
+    Some((pos.parse().ok()?, len.parse().ok()?))
+}
+
+/// The first two byte amounts, e.g. `1.20 MiB/40.00 MiB`. Download rates (`@ 2.00 MiB/s`) are
+/// skipped.
+fn parse_bytes(text: &str) -> Option<(u64, Option<u64>)> {
+    let text = text.split('@').next().unwrap_or_default();
+    let tokens: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    let mut amounts = Vec::new();
+    for (index, pair) in tokens.windows(2).enumerate() {
+        let Some(amount) = byte_amount(pair[0], pair[1]) else {
+            continue;
+        };
+        if tokens.get(index + 2) != Some(&"s") {
+            amounts.push(amount);
+        }
+    }
+
+    let mut amounts = amounts.into_iter();
+    Some((amounts.next()?, amounts.next()))
+}
+
+/// A byte amount as indicatif's `HumanBytes` and `DecimalBytes` format it, e.g. `1.20 MiB`
+fn byte_amount(number: &str, unit: &str) -> Option<u64> {
+    let factor: f64 = match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "kB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * factor).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_bars() {
+        let bar = parse_bar(
+            "\u{1b}[2m⠁\u{1b}[0m solving default      [00:00:01] [━━━━━━━╸────────────]    3/10   python",
+        )
+        .unwrap();
+        assert_eq!(bar.label, "solving default");
+        assert_eq!(bar.steps, Some((3, 10)));
+        assert_eq!(bar.bytes, None);
+    }
+
+    #[test]
+    fn byte_bars() {
+        let bar = parse_bar(
+            "⠁ downloading          [00:00:02] [━━━━━╸──────────────] 1.50 MiB/6.00 MiB @ 2.00 MiB/s",
+        )
+        .unwrap();
+        assert_eq!(bar.label, "downloading");
+        assert_eq!(bar.steps, None);
+        assert_eq!(bar.bytes, Some((1_572_864, Some(6_291_456))));
+
+        let bar = parse_bar("⠁ repodata  [00:00:01] [━━━━━━──────] 512 B @ 1.00 KiB/s").unwrap();
+        assert_eq!(bar.bytes, Some((512, None)));
+    }
+
+    #[test]
+    fn other_lines_are_no_bars() {
+        assert_eq!(parse_bar(""), None);
+        assert_eq!(
+            parse_bar("✔ The default environment has been installed."),
+            None
+        );
+        assert_eq!(parse_bar("⠁ updating lock-file [00:00:01]"), None);
+    }
+
+    #[test]
+    fn frames_are_forwarded_on_flush() {
+        #[derive(Default)]
+        struct Frames(Mutex<Vec<Vec<Bar>>>);
+        impl BarListener for Frames {
+            fn bars(&self, bars: &[Bar]) {
+                self.0.lock().unwrap().push(bars.to_vec());
+            }
+        }
+
+        let frames = Arc::new(Frames::default());
+        let subscription = subscribe(frames.clone());
+        let reader = BarReader::default();
+        reader
+            .write_str("\r⠁ linking [00:00:01] [━━━━╸───] 1/4")
+            .unwrap();
+        reader.write_line("").unwrap();
+        reader
+            .write_str("⠁ solving [00:00:01] [━━━━━━━━] 2/2")
+            .unwrap();
+        reader.flush().unwrap();
+        drop(subscription);
+        reader
+            .write_str("⠁ linking [00:00:01] [━━━━━━━━] 4/4")
+            .unwrap();
+        reader.flush().unwrap();
+
+        let frames = frames.0.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0]
+                .iter()
+                .map(|bar| (bar.label.as_str(), bar.steps))
+                .collect::<Vec<_>>(),
+            [("linking", Some((1, 4))), ("solving", Some((2, 2)))]
+        );
+    }
+}
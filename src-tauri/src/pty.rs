@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{collections::VecDeque, ffi::OsString, io::Write};
@@ -7,7 +7,8 @@ use log::warn;
 use miette::{IntoDiagnostic, Result};
 use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, Runtime, Window};
+use tauri::{Emitter, Manager, Runtime, UserAttentionType, Window};
+use tauri_plugin_notification::NotificationExt;
 use tokio::sync::Mutex;
 use tokio::sync::watch;
 use tokio::time::timeout;
@@ -33,7 +34,7 @@ enum TerminationKind {
 /// 3. `~/.pixi/bin/pixi` as last resort
 ///
 /// Returns the full path if found, otherwise falls back to "pixi" for PATH resolution.
-fn find_pixi_binary() -> String {
+pub(crate) fn find_pixi_binary() -> String {
     // 1. Check PATH first
     if let Ok(path) = which::which("pixi") {
         return path.to_string_lossy().into_owned();
@@ -59,8 +60,20 @@ fn find_pixi_binary() -> String {
     "pixi".into()
 }
 
+/// A command running the pixi binary, without a console window popping up on Windows
+pub(crate) fn pixi_command() -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut command = std::process::Command::new(find_pixi_binary());
+    #[cfg(windows)]
+    std::os::windows::process::CommandExt::creation_flags(
+        &mut command,
+        windows_sys::Win32::System::Threading::CREATE_NO_WINDOW,
+    );
+    command
+}
+
 /// Get the user's home directory.
-fn home_dir() -> Option<PathBuf> {
+pub(crate) fn home_dir() -> Option<PathBuf> {
     #[cfg(windows)]
     {
         std::env::var("USERPROFILE").ok().map(PathBuf::from)
@@ -71,10 +84,19 @@ fn home_dir() -> Option<PathBuf> {
     }
 }
 
+/// The terminal of xterm.js, which the processes are told about unless the invocation overrides it
+pub const DEFAULT_TERM: &str = "xterm-256color";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PtyInvocation {
     pub cwd: String,
     pub manifest: String,
+    /// `TERM` of the process, [`DEFAULT_TERM`] if not given
+    #[serde(default)]
+    pub term: Option<String>,
+    /// `COLORTERM` of the process, e.g. `truecolor`. It's inherited from the app if not given.
+    #[serde(default)]
+    pub colorterm: Option<String>,
     pub kind: PtyInvocationKind,
 }
 
@@ -84,6 +106,7 @@ pub enum PtyInvocationKind {
     Shell(PtyShellInvocation),
     Task(PtyTaskInvocation),
     Command(PtyCommandInvocation),
+    Init(PtyInitInvocation),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,6 +119,14 @@ pub struct PtyTaskInvocation {
     pub task: String,
     pub environment: Option<String>,
     pub args: Vec<String>,
+    /// Runs the task in this directory instead of `cwd`, relative to the workspace or absolute
+    /// but inside of it
+    #[serde(default)]
+    pub cwd_override: Option<String>,
+    /// Shows a desktop notification with the outcome when the task exits while its window isn't
+    /// focused
+    #[serde(default)]
+    pub notify_on_exit: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +135,14 @@ pub struct PtyCommandInvocation {
     pub environment: String,
 }
 
+/// `pixi init` with live output, `manifest` is ignored because it doesn't exist yet.
+/// Use `init::init` instead if only the result matters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PtyInitInvocation {
+    /// Arguments passed to `pixi init`, e.g. the path and `--channel`s
+    pub args: Vec<String>,
+}
+
 impl PtyInvocation {
     pub fn argv(&self) -> Vec<String> {
         let pixi = find_pixi_binary();
@@ -144,7 +183,99 @@ impl PtyInvocation {
                     data.command.clone(),
                 ]
             }
+            PtyInvocationKind::Init(data) => {
+                let mut argv = vec![pixi, "init".into()];
+                argv.extend(data.args.clone());
+                argv
+            }
+        }
+    }
+
+    /// The variables that tell the process what the terminal supports
+    fn terminal_env(&self) -> Vec<(&'static str, &str)> {
+        let mut env = vec![("TERM", self.term.as_deref().unwrap_or(DEFAULT_TERM))];
+        if let Some(colorterm) = &self.colorterm {
+            env.push(("COLORTERM", colorterm.as_str()));
+        }
+        env
+    }
+
+    /// The directory to start the process in, `cwd` unless the task overrides it
+    pub fn working_dir(&self) -> Result<PathBuf> {
+        let cwd = PathBuf::from(&self.cwd);
+        match &self.kind {
+            PtyInvocationKind::Task(PtyTaskInvocation {
+                cwd_override: Some(dir),
+                ..
+            }) => {
+                let relative =
+                    crate::utils::path_within_workspace(&cwd, Path::new(dir)).map_err(|e| e.0)?;
+                Ok(cwd.join(relative))
+            }
+            _ => Ok(cwd),
+        }
+    }
+
+    /// Runs the invocation in the terminal of this process instead of a PTY.
+    ///
+    /// Returns the exit code, or 128 + the signal if it got killed (like shells do).
+    pub fn run_attached(&self) -> Result<i32> {
+        let argv = self.argv();
+        let status = std::process::Command::new(&argv[0])
+            .args(&argv[1..])
+            .current_dir(self.working_dir()?)
+            .status()
+            .into_diagnostic()?;
+
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Ok(128 + signal);
+        }
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+/// Output is sent to the frontend at most this often, so chatty processes don't flood the IPC
+const PTY_DATA_FLUSH_INTERVAL: Duration = Duration::from_millis(16);
+/// Pending output is sent right away once it's this large
+const PTY_DATA_FLUSH_BYTES: usize = 64 * 1024;
+
+/// Passes the received chunks to `flush` combined, at most every `interval` unless `max_bytes`
+/// are pending. What's pending when the sender is dropped is flushed before returning.
+fn coalesce(
+    chunks: std::sync::mpsc::Receiver<String>,
+    interval: Duration,
+    max_bytes: usize,
+    mut flush: impl FnMut(String),
+) {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let mut pending = String::new();
+    let mut deadline: Option<std::time::Instant> = None;
+    loop {
+        let received = match deadline {
+            Some(deadline) => {
+                chunks.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+            }
+            None => chunks.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(chunk) => {
+                pending.push_str(&chunk);
+                deadline.get_or_insert_with(|| std::time::Instant::now() + interval);
+                if pending.len() < max_bytes {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
         }
+        flush(std::mem::take(&mut pending));
+        deadline = None;
+    }
+
+    if !pending.is_empty() {
+        flush(pending);
     }
 }
 
@@ -152,6 +283,8 @@ impl PtyInvocation {
 pub struct PtyHandle {
     pub id: String,
     pub invocation: PtyInvocation,
+    /// The window that started the PTY, it gets the events of the PTY
+    window_label: String,
     #[serde(skip)]
     process_id: Option<u32>,
     #[serde(skip)]
@@ -164,12 +297,18 @@ pub struct PtyHandle {
     writer: Mutex<Option<Box<dyn Write + Send>>>,
     #[serde(skip)]
     reader: std::sync::Mutex<Box<dyn std::io::Read + Send>>,
+    /// The start of a UTF-8 character the last read ended in the middle of
+    #[serde(skip)]
+    undecoded: std::sync::Mutex<Vec<u8>>,
     #[serde(skip)]
     buffer: std::sync::Mutex<PtyBuffer>,
     #[serde(skip)]
     termination_kind: std::sync::Mutex<TerminationKind>,
     #[serde(skip)]
     started_at: std::time::Instant,
+    /// Last applied (cols, rows)
+    #[serde(skip)]
+    size: std::sync::Mutex<(u16, u16)>,
 }
 
 #[derive(Clone, Serialize)]
@@ -178,13 +317,20 @@ pub struct PtyStartEvent {
     pub invocation: PtyInvocation,
 }
 
+#[derive(Clone, Serialize)]
+pub struct PtyResizeEvent {
+    pub id: String,
+    pub cols: u16,
+    pub rows: u16,
+}
+
 #[derive(Clone, Serialize)]
 pub struct PtyDataEvent {
     pub id: String,
     pub data: String,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PtyExitEvent {
     pub id: String,
     pub invocation: PtyInvocation,
@@ -194,12 +340,72 @@ pub struct PtyExitEvent {
     pub success: bool,
 }
 
+/// Sends the output of the PTY to `chunks` until the process is gone. A character the process
+/// got killed in the middle of is sent last, so its line isn't lost.
+fn forward_output(pty: &PtyHandle, chunks: &std::sync::mpsc::Sender<String>) {
+    loop {
+        match pty.read() {
+            Ok(Some(data)) => {
+                if chunks.send(data).is_err() {
+                    return;
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                warn!("Reader error ({}): {}", err, pty.id);
+                break;
+            }
+        }
+    }
+
+    if let Some(rest) = pty.drain_undecoded() {
+        let _ = chunks.send(rest);
+    }
+}
+
+/// Decodes `bytes` after the `undecoded` ones of the previous read. An incomplete character at
+/// the end is kept in `undecoded` for the next read instead of turning into `U+FFFD`.
+fn decode_utf8(undecoded: &mut Vec<u8>, bytes: &[u8]) -> String {
+    undecoded.extend_from_slice(bytes);
+    let rest = undecoded.split_off(undecoded.len() - incomplete_char_len(undecoded));
+    let decoded = String::from_utf8_lossy(undecoded).into_owned();
+    *undecoded = rest;
+    decoded
+}
+
+/// The number of bytes at the end that start a character without its remaining bytes
+fn incomplete_char_len(bytes: &[u8]) -> usize {
+    // A character has at most 4 bytes, so an incomplete one starts in the last 3
+    for (index, byte) in bytes.iter().rev().take(3).enumerate() {
+        let len = match byte {
+            0x80..=0xBF => continue,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if len > index + 1 { index + 1 } else { 0 };
+    }
+    0
+}
+
 #[derive(Default)]
 struct PtyBuffer {
     chunks: VecDeque<String>,
     total_bytes: usize,
 }
 
+/// Larger sizes are a frontend bug rather than a huge terminal
+const MAX_PTY_DIMENSION: u16 = 10_000;
+
+/// Some programs hang with 0 rows or columns
+fn clamp_size(cols: u16, rows: u16) -> (u16, u16) {
+    (
+        cols.clamp(1, MAX_PTY_DIMENSION),
+        rows.clamp(1, MAX_PTY_DIMENSION),
+    )
+}
+
 /// # Safety
 /// The caller must ensure `pid` still refers to the child process we spawned.
 #[cfg(unix)]
@@ -229,9 +435,30 @@ impl PtyHandle {
     pub fn new(
         id: String,
         invocation: PtyInvocation,
+        window_label: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(Self, Box<dyn Child + Send>)> {
+        let cwd = invocation.working_dir()?;
+        let argv = invocation.argv().into_iter().map(OsString::from).collect();
+        let mut command = CommandBuilder::from_argv(argv);
+        for (key, value) in invocation.terminal_env() {
+            command.env(key, value);
+        }
+        command.cwd(cwd);
+
+        Self::spawn(id, invocation, window_label, command, cols, rows)
+    }
+
+    fn spawn(
+        id: String,
+        invocation: PtyInvocation,
+        window_label: String,
+        command: CommandBuilder,
         cols: u16,
         rows: u16,
     ) -> Result<(Self, Box<dyn Child + Send>)> {
+        let (cols, rows) = clamp_size(cols, rows);
         let pty_system = native_pty_system();
         let size = PtySize {
             rows,
@@ -244,11 +471,6 @@ impl PtyHandle {
             .openpty(size)
             .map_err(|err| miette::miette!("failed to open PTY: {err}"))?;
 
-        let argv = invocation.argv().into_iter().map(OsString::from).collect();
-        let mut command = CommandBuilder::from_argv(argv);
-        command.env("TERM", "xterm-256color");
-        command.cwd(invocation.cwd.clone());
-
         let child = pair
             .slave
             .spawn_command(command)
@@ -271,33 +493,37 @@ impl PtyHandle {
             Self {
                 id,
                 invocation,
+                window_label,
                 process_id,
                 exit_tx: std::sync::Mutex::new(Some(exit_tx)),
                 exit_rx,
                 writer: Mutex::new(Some(writer)),
                 master: Mutex::new(Some(master)),
                 reader: std::sync::Mutex::new(reader),
+                undecoded: std::sync::Mutex::default(),
                 buffer: std::sync::Mutex::default(),
                 termination_kind: std::sync::Mutex::new(TerminationKind::Finished),
                 started_at: std::time::Instant::now(),
+                size: std::sync::Mutex::new((cols, rows)),
             },
             child,
         ))
     }
 
-    pub async fn write(&self, data: String) -> Result<()> {
+    pub async fn write(&self, data: &[u8]) -> Result<()> {
         let mut writer_guard = self.writer.lock().await;
 
         let writer = writer_guard
             .as_mut()
             .ok_or_else(|| miette::miette!("PTY writer closed"))?;
 
-        writer.write_all(data.as_bytes()).into_diagnostic()?;
+        writer.write_all(data).into_diagnostic()?;
         writer.flush().into_diagnostic()?;
 
         Ok(())
     }
 
+    /// Characters split across reads are only returned once the read with their end arrived
     pub fn read(&self) -> Result<Option<String>> {
         let reader = self.reader.lock().unwrap();
 
@@ -309,12 +535,24 @@ impl PtyHandle {
         if size == 0 {
             Ok(None)
         } else {
-            let chunk = String::from_utf8_lossy(&buffer[..size]).to_string();
+            let chunk = decode_utf8(&mut self.undecoded.lock().unwrap(), &buffer[..size]);
             self.store_chunk(chunk.clone());
             Ok(Some(chunk))
         }
     }
 
+    /// The bytes of an incomplete character left after the last read, once nothing follows
+    /// anymore, e.g. because the process got killed while writing it
+    fn drain_undecoded(&self) -> Option<String> {
+        let undecoded = std::mem::take(&mut *self.undecoded.lock().unwrap());
+        if undecoded.is_empty() {
+            return None;
+        }
+        let rest = String::from_utf8_lossy(&undecoded).into_owned();
+        self.store_chunk(rest.clone());
+        Some(rest)
+    }
+
     fn store_chunk(&self, chunk: String) {
         if let Ok(mut buffer) = self.buffer.lock() {
             buffer.total_bytes += chunk.len();
@@ -339,7 +577,9 @@ impl PtyHandle {
         Ok(guard.chunks.iter().cloned().collect())
     }
 
-    pub async fn resize(&self, cols: u16, rows: u16) -> Result<()> {
+    /// Returns the applied size, which is clamped to sane dimensions
+    pub async fn resize(&self, cols: u16, rows: u16) -> Result<(u16, u16)> {
+        let (cols, rows) = clamp_size(cols, rows);
         let master_guard = self.master.lock().await;
 
         let master = master_guard
@@ -354,8 +594,14 @@ impl PtyHandle {
                 pixel_height: 0,
             })
             .map_err(|err| miette::miette!("failed to resize PTY: {err}"))?;
+        *self.size.lock().unwrap() = (cols, rows);
 
-        Ok(())
+        Ok((cols, rows))
+    }
+
+    /// Last applied (cols, rows), e.g. to start a replacement PTY with the same size
+    pub fn size(&self) -> (u16, u16) {
+        *self.size.lock().unwrap()
     }
 
     pub async fn kill(&self) -> Result<()> {
@@ -363,7 +609,7 @@ impl PtyHandle {
         *self.termination_kind.lock().unwrap() = TerminationKind::Stopped;
 
         // First, try graceful shutdown by sending Ctrl+C (ETX, 0x03) to the PTY.
-        let _write_result = self.write("\x03".into()).await;
+        let _write_result = self.write(b"\x03").await;
 
         // Wait for the process to exit gracefully.
         let mut rx = self.exit_rx.clone();
@@ -387,6 +633,11 @@ impl PtyHandle {
         Ok(())
     }
 
+    /// The window that started the PTY
+    pub fn window_label(&self) -> &str {
+        &self.window_label
+    }
+
     pub fn is_running(&self) -> bool {
         !*self.exit_rx.borrow()
     }
@@ -405,7 +656,13 @@ pub async fn pty_create<R: Runtime>(
     let app_state = state.inner().clone();
     let id_clone = id.clone();
 
-    let (handle, child) = PtyHandle::new(id.clone(), invocation.clone(), cols, rows)?;
+    let (handle, child) = PtyHandle::new(
+        id.clone(),
+        invocation.clone(),
+        window_label.clone(),
+        cols,
+        rows,
+    )?;
     let exit_tx = handle.exit_tx.lock().unwrap().take().unwrap();
     let pty = Arc::new(handle);
 
@@ -425,30 +682,30 @@ pub async fn pty_create<R: Runtime>(
     // Channel for the reader thread to signal it has finished reading all data.
     let (reader_done_tx, reader_done_rx) = std::sync::mpsc::channel::<()>();
 
-    // Reader thread: reads PTY output and emits pty-data events.
+    // Reader thread: reads PTY output and hands it to the emitter thread.
+    let (chunk_tx, chunk_rx) = std::sync::mpsc::channel::<String>();
     let pty_reader = pty.clone();
-    let window_reader = window.clone();
-    let window_label_reader = window_label.clone();
-    let id_reader = id.clone();
+    tauri::async_runtime::spawn_blocking(move || forward_output(&pty_reader, &chunk_tx));
+
+    // Emitter thread: combines the chunks read in quick succession into one pty-data event.
+    let window_emitter = window.clone();
+    let window_label_emitter = window_label.clone();
+    let id_emitter = id.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        while let Some(data) = {
-            match pty_reader.read() {
-                Ok(Some(data)) => Some(data),
-                Ok(None) => None,
-                Err(err) => {
-                    warn!("Reader error ({}): {}", err, id_reader);
-                    None
-                }
-            }
-        } {
-            let data_event = PtyDataEvent {
-                id: id_reader.clone(),
-                data,
-            };
-            window_reader
-                .emit_to(&window_label_reader, "pty-data", data_event)
-                .unwrap();
-        }
+        coalesce(
+            chunk_rx,
+            PTY_DATA_FLUSH_INTERVAL,
+            PTY_DATA_FLUSH_BYTES,
+            |data| {
+                let data_event = PtyDataEvent {
+                    id: id_emitter.clone(),
+                    data,
+                };
+                window_emitter
+                    .emit_to(&window_label_emitter, "pty-data", data_event)
+                    .unwrap();
+            },
+        );
         let _ = reader_done_tx.send(());
     });
 
@@ -503,11 +760,18 @@ pub async fn pty_create<R: Runtime>(
         tauri::async_runtime::block_on(async {
             app_state.remove_pty(&id_clone, exit_event.clone()).await;
         });
+        crate::scrollback::save(window.app_handle(), &exit_event);
 
         window
             .emit_to(&window_label, "pty-exit", &exit_event)
             .unwrap();
 
+        if let PtyInvocationKind::Task(task) = &invocation.kind
+            && task.notify_on_exit
+        {
+            notify_task_exit(&window, &task.task, &exit_event);
+        }
+
         // Signal that the process has fully exited and cleanup is complete.
         let _ = exit_tx.send(true);
     });
@@ -515,6 +779,47 @@ pub async fn pty_create<R: Runtime>(
     Ok(())
 }
 
+/// Notifies about the outcome of a task unless its window is focused. The notification plugin can't
+/// handle clicks on desktop, so the window also requests attention to be easy to get back to.
+fn notify_task_exit<R: Runtime>(window: &Window<R>, task: &str, event: &PtyExitEvent) {
+    if window.is_focused().unwrap_or(false) {
+        return;
+    }
+
+    let (title, body) = task_exit_notification(task, event);
+    if let Err(err) = window
+        .app_handle()
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        warn!("Failed to notify about the exit of '{task}': {err}");
+    }
+    if let Err(err) = window.request_user_attention(Some(UserAttentionType::Informational)) {
+        warn!(
+            "Failed to request attention for '{}': {err}",
+            window.label()
+        );
+    }
+}
+
+/// Title and body of the notification about the exit of `task`
+fn task_exit_notification(task: &str, event: &PtyExitEvent) -> (&'static str, String) {
+    let title = if event.success {
+        "Task completed"
+    } else {
+        "Task failed"
+    };
+    let body = match (&event.signal, event.exit_code) {
+        (Some(signal), _) => format!("\"{task}\" ended due to signal {signal}."),
+        (None, Some(code)) => format!("\"{task}\" exited with code {code}."),
+        (None, None) => format!("\"{task}\" was terminated."),
+    };
+    (title, body)
+}
+
 #[tauri::command]
 pub async fn pty_write(
     state: tauri::State<'_, AppState>,
@@ -522,19 +827,40 @@ pub async fn pty_write(
     data: String,
 ) -> Result<(), Error> {
     let pty = require_pty(&state, &id).await?;
-    pty.write(data).await?;
+    pty.write(data.as_bytes()).await?;
+    Ok(())
+}
+
+/// Like [`pty_write`] but for input that isn't text, e.g. raw control sequences
+#[tauri::command]
+pub async fn pty_write_bytes(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    data: Vec<u8>,
+) -> Result<(), Error> {
+    let pty = require_pty(&state, &id).await?;
+    pty.write(&data).await?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn pty_resize(
+pub async fn pty_resize<R: Runtime>(
+    window: Window<R>,
     state: tauri::State<'_, AppState>,
     id: String,
     cols: u16,
     rows: u16,
 ) -> Result<(), Error> {
     let pty = require_pty(&state, &id).await?;
-    pty.resize(cols, rows).await?;
+    let (cols, rows) = pty.resize(cols, rows).await?;
+
+    window
+        .emit_to(
+            window.label(),
+            "pty-resized",
+            PtyResizeEvent { id, cols, rows },
+        )
+        .into_diagnostic()?;
     Ok(())
 }
 
@@ -555,6 +881,16 @@ pub async fn pty_get_buffer(
     Ok(String::new())
 }
 
+/// Like `pty_get_buffer`, but without colors and other ANSI escape codes
+#[tauri::command]
+pub async fn pty_get_buffer_plain(
+    state: tauri::State<'_, AppState>,
+    id: String,
+) -> Result<String, Error> {
+    let buffer = pty_get_buffer(state, id).await?;
+    Ok(crate::utils::strip_ansi_escapes(&buffer))
+}
+
 #[tauri::command]
 pub async fn pty_kill(state: tauri::State<'_, AppState>, id: String) -> Result<(), Error> {
     let pty = require_pty(&state, &id).await?;
@@ -562,6 +898,33 @@ pub async fn pty_kill(state: tauri::State<'_, AppState>, id: String) -> Result<(
     Ok(())
 }
 
+/// Kills all running PTYs of the window that calls it
+#[tauri::command]
+pub async fn pty_kill_all<R: Runtime>(
+    window: Window<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), Error> {
+    kill_window_ptys(&state, window.label()).await
+}
+
+/// Kills all running PTYs that were started by the window, all at once since a graceful
+/// shutdown can take a few seconds
+pub async fn kill_window_ptys(state: &AppState, window_label: &str) -> Result<(), Error> {
+    let handles: Vec<_> = state
+        .ptys_for_window(window_label)
+        .await
+        .into_iter()
+        .filter(|pty| pty.is_running())
+        .map(|pty| tauri::async_runtime::spawn(async move { pty.kill().await }))
+        .collect();
+
+    for handle in handles {
+        handle.await.into_diagnostic()??;
+    }
+
+    Ok(())
+}
+
 async fn require_pty(
     state: &tauri::State<'_, AppState>,
     id: &str,
@@ -584,3 +947,241 @@ pub async fn pty_is_running(state: tauri::State<'_, AppState>, id: String) -> Re
 pub async fn pty_list(state: tauri::State<'_, AppState>) -> Result<Vec<Arc<PtyHandle>>, Error> {
     Ok(state.ptys().await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_exit_notifications() {
+        let exit = |exit_code, signal: Option<&str>| PtyExitEvent {
+            id: "1".into(),
+            invocation: task_in(None),
+            buffer: String::new(),
+            exit_code,
+            signal: signal.map(str::to_string),
+            success: exit_code == Some(0),
+        };
+
+        assert_eq!(
+            task_exit_notification("build", &exit(Some(0), None)),
+            ("Task completed", "\"build\" exited with code 0.".into())
+        );
+        assert_eq!(
+            task_exit_notification("build", &exit(Some(2), None)),
+            ("Task failed", "\"build\" exited with code 2.".into())
+        );
+        assert_eq!(
+            task_exit_notification("build", &exit(Some(1), Some("Terminated"))),
+            (
+                "Task failed",
+                "\"build\" ended due to signal Terminated.".into()
+            )
+        );
+    }
+
+    #[test]
+    fn chunks_are_coalesced() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        for chunk in ["a", "b", "c"] {
+            tx.send(chunk.to_string()).unwrap();
+        }
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            tx.send("d".repeat(8)).unwrap();
+            tx.send("e".into()).unwrap();
+        });
+
+        let mut flushed = Vec::new();
+        coalesce(rx, Duration::from_millis(50), 8, |data| flushed.push(data));
+        sender.join().unwrap();
+
+        // The last chunk is flushed once the sender is gone
+        assert_eq!(flushed, ["abc", "dddddddd", "e"]);
+    }
+
+    #[test]
+    fn split_characters_are_decoded_once_complete() {
+        let mut undecoded = Vec::new();
+        let euro = "€".as_bytes();
+
+        assert_eq!(
+            decode_utf8(&mut undecoded, &[b"price: ", &euro[..1]].concat()),
+            "price: "
+        );
+        assert_eq!(decode_utf8(&mut undecoded, &euro[1..2]), "");
+        assert_eq!(
+            decode_utf8(&mut undecoded, &[&euro[2..], b"5"].concat()),
+            "€5"
+        );
+        assert!(undecoded.is_empty());
+
+        // Invalid bytes aren't held back
+        assert_eq!(decode_utf8(&mut undecoded, b"a\xff"), "a\u{FFFD}");
+        assert!(undecoded.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn partial_line_is_kept_when_killed() {
+        // `exec` so no child of the shell keeps the PTY open once it's killed
+        let mut command = CommandBuilder::new("sh");
+        command.args(["-c", r"printf 'Downloading \342\202'; exec sleep 30"]);
+        command.cwd(std::env::temp_dir());
+        let (pty, mut child) =
+            PtyHandle::spawn("1".into(), task_in(None), "main".into(), command, 80, 24).unwrap();
+        let pty = Arc::new(pty);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let reader = {
+            let pty = pty.clone();
+            std::thread::spawn(move || forward_output(&pty, &tx))
+        };
+        let started = std::time::Instant::now();
+        while !pty.buffered_output().unwrap().contains("Downloading") {
+            assert!(started.elapsed() < Duration::from_secs(10), "no output");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        // SAFETY: the child is only waited for afterwards, so the PID is still ours
+        unsafe { force_kill(child.process_id().unwrap()) };
+        child.wait().unwrap();
+        *pty.writer.blocking_lock() = None;
+        *pty.master.blocking_lock() = None;
+        reader.join().unwrap();
+
+        assert_eq!(pty.buffered_output().unwrap(), "Downloading \u{FFFD}");
+        assert_eq!(rx.iter().collect::<String>(), "Downloading \u{FFFD}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn terminal_env_reaches_the_process() {
+        assert_eq!(task_in(None).terminal_env(), [("TERM", DEFAULT_TERM)]);
+
+        let invocation = PtyInvocation {
+            term: Some("xterm-kitty".into()),
+            colorterm: Some("truecolor".into()),
+            ..task_in(None)
+        };
+        let mut command = CommandBuilder::new("sh");
+        command.args(["-c", r#"echo "$TERM/$COLORTERM""#]);
+        command.cwd(std::env::temp_dir());
+        for (key, value) in invocation.terminal_env() {
+            command.env(key, value);
+        }
+        let (pty, mut child) =
+            PtyHandle::spawn("1".into(), invocation, "main".into(), command, 80, 24).unwrap();
+        let pty = Arc::new(pty);
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let reader = {
+            let pty = pty.clone();
+            std::thread::spawn(move || forward_output(&pty, &tx))
+        };
+        child.wait().unwrap();
+        *pty.writer.blocking_lock() = None;
+        *pty.master.blocking_lock() = None;
+        reader.join().unwrap();
+
+        assert_eq!(
+            pty.buffered_output().unwrap().trim_end(),
+            "xterm-kitty/truecolor"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ptys_belong_to_their_window() {
+        let state = AppState::default();
+        let mut children = Vec::new();
+        tauri::async_runtime::block_on(async {
+            for (id, window) in [("a/1", "a"), ("a/2", "a"), ("b/1", "b")] {
+                let mut command = CommandBuilder::new("sleep");
+                command.arg("30");
+                command.cwd(std::env::temp_dir());
+                let (pty, child) =
+                    PtyHandle::spawn(id.into(), task_in(None), window.into(), command, 80, 24)
+                        .unwrap();
+                state.add_pty(id.into(), Arc::new(pty)).await;
+                children.push(child);
+            }
+
+            let mut ids: Vec<_> = state
+                .ptys_for_window("a")
+                .await
+                .iter()
+                .map(|pty| pty.id.clone())
+                .collect();
+            ids.sort();
+            assert_eq!(ids, ["a/1", "a/2"]);
+            assert!(state.ptys_for_window("c").await.is_empty());
+        });
+
+        for mut child in children {
+            child.kill().unwrap();
+            child.wait().unwrap();
+        }
+    }
+
+    #[test]
+    fn sizes_are_clamped() {
+        assert_eq!(clamp_size(80, 24), (80, 24));
+        assert_eq!(clamp_size(0, 0), (1, 1));
+        assert_eq!(clamp_size(u16::MAX, 50), (MAX_PTY_DIMENSION, 50));
+    }
+
+    #[test]
+    fn init_argv() {
+        let invocation = PtyInvocation {
+            cwd: "/projects".into(),
+            manifest: String::new(),
+            term: None,
+            colorterm: None,
+            kind: PtyInvocationKind::Init(PtyInitInvocation {
+                args: vec![
+                    "my-project".into(),
+                    "--channel".into(),
+                    "conda-forge".into(),
+                ],
+            }),
+        };
+
+        assert_eq!(
+            invocation.argv()[1..],
+            ["init", "my-project", "--channel", "conda-forge"]
+        );
+    }
+
+    fn task_in(cwd_override: Option<&str>) -> PtyInvocation {
+        PtyInvocation {
+            cwd: "/repo".into(),
+            manifest: "/repo/pixi.toml".into(),
+            term: None,
+            colorterm: None,
+            kind: PtyInvocationKind::Task(PtyTaskInvocation {
+                task: "test".into(),
+                environment: None,
+                args: Vec::new(),
+                cwd_override: cwd_override.map(str::to_string),
+                notify_on_exit: false,
+            }),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn working_dir_override() {
+        assert_eq!(task_in(None).working_dir().unwrap(), Path::new("/repo"));
+        assert_eq!(
+            task_in(Some("packages/core")).working_dir().unwrap(),
+            Path::new("/repo/packages/core")
+        );
+        assert_eq!(
+            task_in(Some("/repo/packages")).working_dir().unwrap(),
+            Path::new("/repo/packages")
+        );
+        assert!(task_in(Some("../other")).working_dir().is_err());
+        assert!(task_in(Some("/other")).working_dir().is_err());
+    }
+}
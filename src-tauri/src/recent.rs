@@ -0,0 +1,172 @@
+//! Workspaces that were opened before, shown on the start screen.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use toml_edit::Item;
+
+use crate::{error::Error, manifest::ManifestDocument, utils};
+
+const STORE_PATH: &str = "recent-workspaces.json";
+const STORE_KEY: &str = "recentWorkspaces";
+const MAX_RECENT_WORKSPACES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspace {
+    manifest: PathBuf,
+    // Entries written by older versions only have the manifest
+    #[serde(default)]
+    name: String,
+    /// Milliseconds since the Unix epoch
+    #[serde(default)]
+    last_opened: u64,
+}
+
+#[tauri::command]
+pub async fn list_recent_workspaces<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<RecentWorkspace>, Error> {
+    load(&app)
+}
+
+/// Moves the workspace at `path` to the top, `path` can be its manifest or its root directory
+#[tauri::command]
+pub async fn add_recent_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    path: PathBuf,
+) -> Result<(), Error> {
+    record(&app, &path)
+}
+
+/// Removes the workspace, `path` can be its manifest or its root directory
+#[tauri::command]
+pub async fn remove_recent_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    path: PathBuf,
+) -> Result<(), Error> {
+    let mut recents = load(&app)?;
+    recents.retain(|recent| recent.manifest != path && recent.manifest.parent() != Some(&path));
+    save(&app, &recents)
+}
+
+#[tauri::command]
+pub async fn clear_recent_workspaces<R: Runtime>(app: AppHandle<R>) -> Result<(), Error> {
+    save(&app, &[])
+}
+
+/// Moves the workspace at `path` to the top of the recent workspaces
+pub fn record<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), Error> {
+    let (manifest, name) = manifest_and_name(path)?;
+    let last_opened = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+    let recent = RecentWorkspace {
+        manifest,
+        name,
+        last_opened,
+    };
+
+    let recents = push(load(app)?, recent);
+    save(app, &recents)
+}
+
+/// Whether the workspace at `path` is one of the recent workspaces
+pub(crate) fn contains<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<bool, Error> {
+    let (manifest, _) = manifest_and_name(path)?;
+    Ok(load(app)?.iter().any(|recent| recent.manifest == manifest))
+}
+
+/// The manifest of the workspace at `path` and the name it's listed with
+pub(crate) fn manifest_and_name(path: &Path) -> Result<(PathBuf, String), Error> {
+    let workspace = utils::workspace(path.to_path_buf())?;
+    let manifest = ManifestDocument::load(&workspace)?;
+    // pixi names workspaces without a name after their directory
+    let name = manifest
+        .workspace_field("name")
+        .and_then(Item::as_str)
+        .map(str::to_string)
+        .or_else(|| {
+            workspace
+                .root()
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+
+    Ok((workspace.workspace.provenance.absolute_path(), name))
+}
+
+/// Puts `recent` first, dropping an older entry of the same workspace and the oldest entries
+/// beyond the limit
+fn push(mut recents: Vec<RecentWorkspace>, recent: RecentWorkspace) -> Vec<RecentWorkspace> {
+    recents.retain(|entry| entry.manifest != recent.manifest);
+    recents.insert(0, recent);
+    recents.truncate(MAX_RECENT_WORKSPACES);
+    recents
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<RecentWorkspace>, Error> {
+    let store = app.store(STORE_PATH).into_diagnostic()?;
+    Ok(store
+        .get(STORE_KEY)
+        .and_then(|recents| serde_json::from_value(recents).ok())
+        .unwrap_or_default())
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, recents: &[RecentWorkspace]) -> Result<(), Error> {
+    let store = app.store(STORE_PATH).into_diagnostic()?;
+    store.set(STORE_KEY, serde_json::to_value(recents).into_diagnostic()?);
+    store.save().into_diagnostic()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent(manifest: &str, last_opened: u64) -> RecentWorkspace {
+        RecentWorkspace {
+            manifest: PathBuf::from(manifest),
+            name: String::new(),
+            last_opened,
+        }
+    }
+
+    #[test]
+    fn reopened_workspace_moves_to_the_top() {
+        let recents = vec![recent("/a/pixi.toml", 2), recent("/b/pixi.toml", 1)];
+
+        assert_eq!(
+            push(recents, recent("/b/pixi.toml", 3)),
+            vec![recent("/b/pixi.toml", 3), recent("/a/pixi.toml", 2)]
+        );
+    }
+
+    #[test]
+    fn oldest_workspaces_are_dropped() {
+        let recents = (0..MAX_RECENT_WORKSPACES)
+            .map(|i| recent(&format!("/{i}/pixi.toml"), i as u64))
+            .collect();
+
+        let recents = push(recents, recent("/new/pixi.toml", 100));
+        assert_eq!(recents.len(), MAX_RECENT_WORKSPACES);
+        assert_eq!(recents[0], recent("/new/pixi.toml", 100));
+        assert!(!recents.contains(&recent(
+            &format!("/{}/pixi.toml", MAX_RECENT_WORKSPACES - 1),
+            (MAX_RECENT_WORKSPACES - 1) as u64
+        )));
+    }
+
+    #[test]
+    fn entries_without_name_can_be_read() {
+        let recents: Vec<RecentWorkspace> =
+            serde_json::from_str(r#"[{ "manifest": "/a/pixi.toml" }]"#).unwrap();
+        assert_eq!(recents, vec![recent("/a/pixi.toml", 0)]);
+    }
+}
@@ -0,0 +1,237 @@
+//! Opt-in saving of the output of exited terminals, so it's still there after a restart.
+//!
+//! Every terminal gets a file `<workspace hash>-<id hash>.json` in the `scrollback` directory
+//! of the app data dir, the oldest files are removed once they exceed [`MAX_TOTAL_BYTES`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use log::warn;
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::Error;
+use crate::pty::PtyExitEvent;
+
+const SETTINGS_STORE_PATH: &str = "session.json";
+const ENABLED_KEY: &str = "persistScrollback";
+const SCROLLBACK_DIR: &str = "scrollback";
+const MAX_TOTAL_BYTES: u64 = 20 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct SavedPty {
+    workspace: String,
+    event: PtyExitEvent,
+}
+
+#[tauri::command]
+pub fn scrollback_persistence_enabled<R: Runtime>(app: AppHandle<R>) -> Result<bool, Error> {
+    let store = app.store(SETTINGS_STORE_PATH).into_diagnostic()?;
+    Ok(store
+        .get(ENABLED_KEY)
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or(false))
+}
+
+/// Disabling it also removes the saved output
+#[tauri::command]
+pub fn set_scrollback_persistence<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), Error> {
+    let store = app.store(SETTINGS_STORE_PATH).into_diagnostic()?;
+    store.set(ENABLED_KEY, enabled);
+    store.save().into_diagnostic()?;
+
+    if !enabled && let Ok(dir) = scrollback_dir(&app) {
+        match fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(miette::miette!("Failed to remove '{}': {e}", dir.display()).into());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The saved terminals of `workspace`, most recently exited first
+#[tauri::command]
+pub async fn pty_load_saved<R: Runtime>(
+    app: AppHandle<R>,
+    workspace: PathBuf,
+) -> Result<Vec<PtyExitEvent>, Error> {
+    let dir = scrollback_dir(&app)?;
+    let workspace = workspace_key(&workspace);
+
+    tauri::async_runtime::spawn_blocking(move || Ok(load(&dir, &workspace)))
+        .await
+        .into_diagnostic()?
+}
+
+/// Saves the output of an exited terminal if enabled, replacing an earlier run with the same id
+pub fn save<R: Runtime>(app: &AppHandle<R>, event: &PtyExitEvent) {
+    if !scrollback_persistence_enabled(app.clone()).unwrap_or(false) {
+        return;
+    }
+
+    let result = scrollback_dir(app).and_then(|dir| {
+        let workspace = workspace_key(Path::new(&event.invocation.cwd));
+        write(&dir, &workspace, event)?;
+        prune(&dir, MAX_TOTAL_BYTES);
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!("Failed to save the output of {}: {}", event.id, e.0);
+    }
+}
+
+fn scrollback_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, Error> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .into_diagnostic()?
+        .join(SCROLLBACK_DIR))
+}
+
+fn workspace_key(workspace: &Path) -> String {
+    dunce::canonicalize(workspace)
+        .unwrap_or_else(|_| workspace.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn write(dir: &Path, workspace: &str, event: &PtyExitEvent) -> Result<(), Error> {
+    fs::create_dir_all(dir).into_diagnostic()?;
+    let saved = SavedPty {
+        workspace: workspace.to_string(),
+        event: event.clone(),
+    };
+    let content = serde_json::to_vec(&saved).into_diagnostic()?;
+    fs::write(dir.join(file_name(workspace, &event.id)), content).into_diagnostic()?;
+    Ok(())
+}
+
+fn load(dir: &Path, workspace: &str) -> Vec<PtyExitEvent> {
+    let prefix = format!("{:016x}-", fnv1a(workspace));
+    let mut saved: Vec<(SystemTime, PtyExitEvent)> = entries(dir)
+        .into_iter()
+        .filter(|(path, ..)| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .filter_map(|(path, modified, _)| {
+            let content = fs::read(&path).ok()?;
+            let saved: SavedPty = serde_json::from_slice(&content).ok()?;
+            // Guards against hash collisions
+            (saved.workspace == workspace).then_some((modified, saved.event))
+        })
+        .collect();
+
+    saved.sort_by(|(a, _), (b, _)| b.cmp(a));
+    saved.into_iter().map(|(_, event)| event).collect()
+}
+
+/// Removes the oldest files until the rest fits into `max_bytes`
+fn prune(dir: &Path, max_bytes: u64) {
+    let mut entries = entries(dir);
+    entries.sort_by(|(_, a, _), (_, b, _)| b.cmp(a));
+
+    let mut total = 0;
+    for (path, _, size) in entries {
+        total += size;
+        if total > max_bytes
+            && let Err(e) = fs::remove_file(&path)
+        {
+            warn!("Failed to remove '{}': {e}", path.display());
+        }
+    }
+}
+
+/// Path, modification time and size of the saved files
+fn entries(dir: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+fn file_name(workspace: &str, id: &str) -> String {
+    format!("{:016x}-{:016x}.json", fnv1a(workspace), fnv1a(id))
+}
+
+/// Unlike `DefaultHasher`, the result is the same across Rust versions
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pty::{PtyInvocation, PtyInvocationKind, PtyShellInvocation};
+
+    fn event(id: &str, buffer: &str) -> PtyExitEvent {
+        PtyExitEvent {
+            id: id.into(),
+            invocation: PtyInvocation {
+                cwd: "/project".into(),
+                manifest: "/project/pixi.toml".into(),
+                term: None,
+                colorterm: None,
+                kind: PtyInvocationKind::Shell(PtyShellInvocation {
+                    environment: "default".into(),
+                }),
+            },
+            buffer: buffer.into(),
+            exit_code: Some(0),
+            signal: None,
+            success: true,
+        }
+    }
+
+    #[test]
+    fn saved_per_workspace_and_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write(dir, "/a", &event("shell", "first")).unwrap();
+        write(dir, "/a", &event("shell", "second")).unwrap();
+        write(dir, "/b", &event("shell", "other")).unwrap();
+
+        let saved = load(dir, "/a");
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].buffer, "second");
+        assert_eq!(load(dir, "/c").len(), 0);
+    }
+
+    #[test]
+    fn prune_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write(dir, "/a", &event("old", &"x".repeat(100))).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        write(dir, "/a", &event("new", &"x".repeat(100))).unwrap();
+
+        let size = fs::metadata(dir.join(file_name("/a", "new")))
+            .unwrap()
+            .len();
+        prune(dir, size);
+
+        let saved = load(dir, "/a");
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].id, "new");
+    }
+}
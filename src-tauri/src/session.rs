@@ -0,0 +1,144 @@
+//! Opt-in restoring of the workspace windows that were open when the app was closed.
+
+use std::path::PathBuf;
+
+use log::{info, warn};
+use miette::IntoDiagnostic;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::{error::Error, utils, window};
+
+const SESSION_STORE_PATH: &str = "session.json";
+const RESTORE_KEY: &str = "restoreSession";
+const WORKSPACES_KEY: &str = "workspaces";
+
+#[tauri::command]
+pub fn session_restore_enabled<R: Runtime>(app: AppHandle<R>) -> Result<bool, Error> {
+    let store = app.store(SESSION_STORE_PATH).into_diagnostic()?;
+    Ok(store
+        .get(RESTORE_KEY)
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn set_session_restore<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result<(), Error> {
+    let store = app.store(SESSION_STORE_PATH).into_diagnostic()?;
+    store.set(RESTORE_KEY, enabled);
+    if !enabled {
+        store.delete(WORKSPACES_KEY);
+    }
+    store.save().into_diagnostic()?;
+
+    Ok(())
+}
+
+/// Remembers the workspaces of the open windows if session restore is enabled.
+///
+/// `closing` is the label of a window that is about to close, which is left out unless it's
+/// the last one, because closing the last window quits the app.
+pub fn save_open_workspaces<R: Runtime>(app: &AppHandle<R>, closing: Option<&str>) {
+    if !session_restore_enabled(app.clone()).unwrap_or(false) {
+        return;
+    }
+
+    let windows = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| {
+            let workspace = window
+                .url()
+                .ok()
+                .and_then(|url| window::workspace_path(url.path()));
+            (label, workspace)
+        })
+        .collect();
+    let workspaces = workspaces_to_save(windows, closing);
+
+    let result = app.store(SESSION_STORE_PATH).and_then(|store| {
+        store.set(WORKSPACES_KEY, workspaces);
+        store.save()
+    });
+    if let Err(e) = result {
+        warn!("Failed to save the open workspaces: {}", e);
+    }
+}
+
+/// Opens a window for every workspace of the last session if session restore is enabled.
+///
+/// Returns false if no window was opened.
+pub fn restore<R: Runtime>(app: &AppHandle<R>) -> bool {
+    if !session_restore_enabled(app.clone()).unwrap_or(false) {
+        return false;
+    }
+    let Ok(store) = app.store(SESSION_STORE_PATH) else {
+        return false;
+    };
+    let workspaces: Vec<PathBuf> = store
+        .get(WORKSPACES_KEY)
+        .and_then(|workspaces| serde_json::from_value(workspaces).ok())
+        .unwrap_or_default();
+
+    let mut restored = false;
+    for workspace in workspaces {
+        // The workspace might have been moved or deleted since
+        if utils::workspace(workspace.clone()).is_err() {
+            info!(
+                "Not restoring {}, it no longer contains a valid manifest",
+                workspace.display()
+            );
+            continue;
+        }
+        window::ensure_workspace_window(app, &workspace);
+        restored = true;
+    }
+
+    restored
+}
+
+fn workspaces_to_save(
+    mut windows: Vec<(String, Option<String>)>,
+    closing: Option<&str>,
+) -> Vec<String> {
+    if windows
+        .iter()
+        .any(|(label, _)| Some(label.as_str()) != closing)
+    {
+        windows.retain(|(label, _)| Some(label.as_str()) != closing);
+    }
+
+    let mut workspaces: Vec<String> = Vec::new();
+    for workspace in windows.into_iter().filter_map(|(_, workspace)| workspace) {
+        if !workspaces.contains(&workspace) {
+            workspaces.push(workspace);
+        }
+    }
+    workspaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(entries: &[(&str, Option<&str>)]) -> Vec<(String, Option<String>)> {
+        entries
+            .iter()
+            .map(|(label, workspace)| (label.to_string(), workspace.map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn closing_window_is_left_out() {
+        let windows = windows(&[("a", Some("/a")), ("b", Some("/b")), ("c", None)]);
+
+        assert_eq!(workspaces_to_save(windows, Some("a")), vec!["/b"]);
+    }
+
+    #[test]
+    fn last_window_is_kept() {
+        let windows = windows(&[("a", Some("/a"))]);
+
+        assert_eq!(workspaces_to_save(windows, Some("a")), vec!["/a"]);
+    }
+}
@@ -2,8 +2,12 @@ use std::{collections::HashMap, sync::Arc};
 
 use log::warn;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
+use crate::editor::EditorCache;
+use crate::pixi::workspace::search::PypiIndexCache;
 use crate::pty::{PtyExitEvent, PtyHandle};
+use crate::utils::{self, WorkspaceCache};
 use crate::watcher::Watcher;
 
 #[derive(Clone, Default)]
@@ -11,6 +15,9 @@ pub struct AppState {
     ptys: Arc<Mutex<HashMap<String, Arc<PtyHandle>>>>,
     exited_ptys: Arc<Mutex<HashMap<String, PtyExitEvent>>>,
     watcher: Arc<Mutex<Watcher>>,
+    editor_cache: Arc<Mutex<EditorCache>>,
+    pypi_index_cache: Arc<Mutex<PypiIndexCache>>,
+    operations: Arc<Mutex<HashMap<String, CancellationToken>>>,
 }
 
 impl AppState {
@@ -22,6 +29,17 @@ impl AppState {
         self.ptys.lock().await.values().cloned().collect()
     }
 
+    /// The PTYs started by the window, including those still shutting down
+    pub async fn ptys_for_window(&self, window_label: &str) -> Vec<Arc<PtyHandle>> {
+        self.ptys
+            .lock()
+            .await
+            .values()
+            .filter(|pty| pty.window_label() == window_label)
+            .cloned()
+            .collect()
+    }
+
     pub async fn add_pty(&self, id: String, pty: Arc<PtyHandle>) {
         let mut ptys = self.ptys.lock().await;
         if ptys.contains_key(&id) {
@@ -52,4 +70,58 @@ impl AppState {
     pub fn watcher(&self) -> &Arc<Mutex<Watcher>> {
         &self.watcher
     }
+
+    pub fn editor_cache(&self) -> &Arc<Mutex<EditorCache>> {
+        &self.editor_cache
+    }
+
+    pub fn pypi_index_cache(&self) -> &Arc<Mutex<PypiIndexCache>> {
+        &self.pypi_index_cache
+    }
+
+    /// Used from sync code like `utils::workspace_context` and never held across an await
+    pub fn workspace_cache(&self) -> &Arc<std::sync::Mutex<WorkspaceCache>> {
+        utils::workspace_cache()
+    }
+
+    pub async fn start_operation(&self, id: String) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.operations.lock().await.insert(id, token.clone());
+        token
+    }
+
+    pub async fn finish_operation(&self, id: &str) {
+        self.operations.lock().await.remove(id);
+    }
+
+    /// Returns false if no operation with that id is running
+    pub async fn cancel_operation(&self, id: &str) -> bool {
+        match self.operations.lock().await.get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_running_operation() {
+        let state = AppState::default();
+        tauri::async_runtime::block_on(async {
+            let token = state.start_operation("add".into()).await;
+
+            assert!(!state.cancel_operation("reinstall").await);
+            assert!(state.cancel_operation("add").await);
+            assert!(token.is_cancelled());
+
+            state.finish_operation("add").await;
+            assert!(!state.cancel_operation("add").await);
+        });
+    }
 }
@@ -1,9 +1,18 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
 use miette::{IntoDiagnostic, Result};
 use pixi_api::Interface;
+use serde::Serialize;
 use tauri::{Emitter, Manager, Runtime, Window};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 use tokio::sync::oneshot;
 
+use crate::progress_bars::{self, Bar, BarListener};
+use crate::state::AppState;
 use crate::utils;
 
 pub struct TauriInterface<R: Runtime> {
@@ -72,3 +81,281 @@ impl<R: Runtime> TauriInterface<R> {
         }
     }
 }
+
+pub const SOLVE_PROGRESS_EVENT: &str = "solve-progress";
+/// Emitted while pixi downloads packages, with the bytes of all its downloads
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+/// Emitted when an operation finishes, with the milliseconds spent in each phase
+pub const OPERATION_METRICS_EVENT: &str = "operation-metrics";
+
+/// Minimum time between two progress events of the same phase and message
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Progress {
+    pub phase: String,
+    pub current: u64,
+    pub total: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// Unknown while pixi doesn't know the size of one of the downloads
+    pub total: Option<u64>,
+}
+
+/// Runs a pixi operation of the window and forwards its progress to it. `phase` and `message`
+/// are reported until pixi draws its own progress bars.
+///
+/// With an `operation_id` the operation can be cancelled like with [`utils::cancelable`], `None`
+/// is returned then.
+pub async fn run_with_progress<R: Runtime, T>(
+    window: &Window<R>,
+    operation_id: Option<String>,
+    phase: &str,
+    message: impl Into<String>,
+    operation: impl Future<Output = T>,
+) -> Option<T> {
+    let progress = Arc::new(ProgressEmitter::new(window.clone(), SOLVE_PROGRESS_EVENT));
+    let _subscription = progress_bars::subscribe(progress.clone());
+
+    progress.start(phase, message);
+    let state = window.state::<AppState>();
+    let result = utils::cancelable(&state, operation_id, operation).await;
+    progress.finish();
+    result
+}
+
+/// Forwards the progress of a long running operation to its window.
+///
+/// Events are throttled per phase and message, their first and last event are always sent.
+pub struct ProgressEmitter<R: Runtime> {
+    window: Window<R>,
+    event: &'static str,
+    last_emitted: Mutex<HashMap<(String, Option<String>), Instant>>,
+    timings: Mutex<PhaseTimings>,
+}
+
+impl<R: Runtime> ProgressEmitter<R> {
+    pub fn new(window: Window<R>, event: &'static str) -> Self {
+        Self {
+            window,
+            event,
+            last_emitted: Mutex::new(HashMap::new()),
+            timings: Mutex::new(PhaseTimings::default()),
+        }
+    }
+
+    pub fn report(&self, progress: Progress) {
+        {
+            let mut last_emitted = self.last_emitted.lock().unwrap();
+            let key = (progress.phase.clone(), progress.message.clone());
+            let now = Instant::now();
+            if !should_emit(last_emitted.get(&key), now, &progress) {
+                return;
+            }
+            last_emitted.insert(key, now);
+        }
+
+        let payload = Progress {
+            message: progress
+                .message
+                .map(|message| utils::strip_ansi_escapes(&message)),
+            ..progress
+        };
+        if let Err(e) = self
+            .window
+            .emit_to(self.window.label(), self.event, payload)
+        {
+            log::error!("Failed to emit {}: {}", self.event, e);
+        }
+    }
+
+    /// Reports a phase of which pixi doesn't tell the progress
+    pub fn start(&self, phase: &str, message: impl Into<String>) {
+        self.report(Progress {
+            phase: phase.to_string(),
+            current: 0,
+            total: 1,
+            message: Some(message.into()),
+        });
+    }
+
+    /// Reports the end of the operation and emits how long each phase of pixi's bars took
+    pub fn finish(&self) {
+        self.report(Progress {
+            phase: "done".to_string(),
+            current: 1,
+            total: 1,
+            message: None,
+        });
+
+        let metrics = self.timings.lock().unwrap().take();
+        if let Err(e) = self
+            .window
+            .emit_to(self.window.label(), OPERATION_METRICS_EVENT, metrics)
+        {
+            log::error!("Failed to emit {}: {}", OPERATION_METRICS_EVENT, e);
+        }
+    }
+}
+
+impl<R: Runtime> BarListener for ProgressEmitter<R> {
+    /// Reports the bars with steps, and the bytes of all download bars together
+    fn bars(&self, bars: &[Bar]) {
+        let now = Instant::now();
+        let mut downloads: Option<DownloadProgress> = None;
+        for bar in bars {
+            let phase = bar_phase(&bar.label);
+            self.timings.lock().unwrap().seen(phase, now);
+
+            if let Some((current, total)) = bar.steps {
+                self.report(Progress {
+                    phase: phase.to_string(),
+                    current,
+                    total,
+                    message: Some(bar.label.clone()),
+                });
+            }
+
+            if let Some((downloaded, total)) = bar.bytes {
+                let sum = downloads.get_or_insert(DownloadProgress {
+                    downloaded: 0,
+                    total: Some(0),
+                });
+                sum.downloaded += downloaded;
+                sum.total = sum.total.zip(total).map(|(sum, total)| sum + total);
+            }
+        }
+
+        if let Some(downloads) = downloads
+            && let Err(e) =
+                self.window
+                    .emit_to(self.window.label(), DOWNLOAD_PROGRESS_EVENT, downloads)
+        {
+            log::error!("Failed to emit {}: {}", DOWNLOAD_PROGRESS_EVENT, e);
+        }
+    }
+}
+
+/// The phase of one of pixi's bars, by the words pixi labels them with
+fn bar_phase(label: &str) -> &'static str {
+    let label = label.to_lowercase();
+    if label.contains("solv") || label.contains("lock") {
+        "solve"
+    } else if ["download", "fetch", "repodata", "extract"]
+        .iter()
+        .any(|word| label.contains(word))
+    {
+        "fetch"
+    } else {
+        "link"
+    }
+}
+
+/// How long pixi showed the bars of each phase. Phases overlap, e.g. pixi already downloads the
+/// packages of one environment while it solves the next.
+#[derive(Default)]
+struct PhaseTimings {
+    /// When a bar of the phase was first and last seen
+    seen: IndexMap<String, (Instant, Instant)>,
+}
+
+impl PhaseTimings {
+    fn seen(&mut self, phase: &str, now: Instant) {
+        self.seen
+            .entry(phase.to_string())
+            .and_modify(|(_, last)| *last = now)
+            .or_insert((now, now));
+    }
+
+    /// Milliseconds per phase, in the order they were first seen
+    fn take(&mut self) -> IndexMap<String, u64> {
+        std::mem::take(&mut self.seen)
+            .into_iter()
+            .map(|(phase, (first, last))| (phase, last.duration_since(first).as_millis() as u64))
+            .collect()
+    }
+}
+
+fn should_emit(last_emitted: Option<&Instant>, now: Instant, progress: &Progress) -> bool {
+    let Some(emitted_at) = last_emitted else {
+        return true;
+    };
+
+    progress.current >= progress.total || now.duration_since(*emitted_at) >= PROGRESS_INTERVAL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(phase: &str, current: u64, total: u64) -> Progress {
+        Progress {
+            phase: phase.to_string(),
+            current,
+            total,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn phase_timings() {
+        let start = Instant::now();
+        let mut timings = PhaseTimings::default();
+        timings.seen("solve", start);
+        timings.seen("fetch", start + Duration::from_millis(50));
+        timings.seen("solve", start + Duration::from_millis(120));
+        timings.seen("fetch", start + Duration::from_millis(200));
+        timings.seen("link", start + Duration::from_millis(230));
+
+        let metrics = timings.take();
+        assert_eq!(
+            metrics.keys().collect::<Vec<_>>(),
+            ["solve", "fetch", "link"]
+        );
+        assert_eq!(metrics["solve"], 120);
+        assert_eq!(metrics["fetch"], 150);
+        assert_eq!(metrics["link"], 0);
+        assert!(timings.take().is_empty());
+    }
+
+    #[test]
+    fn progress_within_the_interval_is_dropped() {
+        let start = Instant::now();
+
+        assert!(!should_emit(
+            Some(&start),
+            start + Duration::from_millis(10),
+            &progress("download", 5, 10)
+        ));
+        assert!(should_emit(
+            Some(&start),
+            start + PROGRESS_INTERVAL,
+            &progress("download", 5, 10)
+        ));
+    }
+
+    #[test]
+    fn first_and_last_progress_are_always_emitted() {
+        let start = Instant::now();
+
+        assert!(should_emit(None, start, &progress("download", 0, 10)));
+        assert!(should_emit(
+            Some(&start),
+            start,
+            &progress("download", 10, 10)
+        ));
+    }
+
+    #[test]
+    fn bar_phases() {
+        assert_eq!(bar_phase("solving default"), "solve");
+        assert_eq!(bar_phase("updating lock-file"), "solve");
+        assert_eq!(bar_phase("fetching repodata"), "fetch");
+        assert_eq!(bar_phase("downloading"), "fetch");
+        assert_eq!(bar_phase("linking"), "link");
+    }
+}
@@ -1,17 +1,27 @@
-use std::{future::Future, io::IsTerminal, path::PathBuf};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::IsTerminal,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
 
-use miette::IntoDiagnostic;
+use miette::{Context, IntoDiagnostic};
 use pixi_api::{
     WorkspaceContext,
     core::{Workspace, WorkspaceLocator, workspace::DiscoveryStart},
+    manifest::{EnvironmentName, FeatureName},
+    rattler_conda_types::Platform,
 };
 use strip_ansi_escapes::strip;
 use tauri::{
-    Runtime, Window,
+    Manager, Runtime, Window,
     async_runtime::{block_on, spawn_blocking},
 };
 
-use crate::{TauriInterface, error::Error};
+use crate::{TauriInterface, error::Error, state::AppState};
 
 /// Execute a non-`Send` future on the current thread while still exposing a `Send`
 /// handle to the Tauri runtime.
@@ -27,26 +37,226 @@ where
         .map_err(|e| E::from(format!("Execution failed: {}", e)))?
 }
 
+/// Locates the workspace at `workspace`, which can be its directory, a directory inside of it or
+/// its manifest.
 pub fn workspace(workspace: PathBuf) -> Result<Workspace, Error> {
+    let root = workspace_search_root(&workspace)?;
     let workspace = WorkspaceLocator::for_cli()
         .with_consider_environment(false)
-        .with_search_start(DiscoveryStart::SearchRoot(workspace))
+        .with_search_start(DiscoveryStart::SearchRoot(root))
         .locate()
         .into_diagnostic()?;
 
     Ok(workspace)
 }
 
+/// Files pixi locates a workspace by, `pyproject.toml` only counts with a `[tool.pixi]` table
+const MANIFEST_FILE_NAMES: &[&str] = &["pixi.toml", "pyproject.toml", "mojoproject.toml"];
+
+/// The canonical directory to locate the workspace from, the directory of `path` if it's a file.
+///
+/// Fails if neither the directory nor one of its parents has a manifest, which pixi would only
+/// report as a generic error.
+fn workspace_search_root(path: &Path) -> Result<PathBuf, Error> {
+    let canonical = dunce::canonicalize(path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Workspace '{}' doesn't exist", path.display()))?;
+    let root = match canonical.parent() {
+        Some(parent) if canonical.is_file() => parent.to_path_buf(),
+        _ => canonical,
+    };
+
+    let has_manifest = root.ancestors().any(|dir| {
+        MANIFEST_FILE_NAMES
+            .iter()
+            .any(|name| dir.join(name).is_file())
+    });
+    if !has_manifest {
+        return Err(miette::miette!(
+            help = "A pixi workspace has a pixi.toml or a pyproject.toml with a [tool.pixi] table",
+            "'{}' is not a pixi workspace",
+            root.display()
+        )
+        .into());
+    }
+
+    Ok(root)
+}
+
 pub fn workspace_context<R: Runtime>(
     window: Window<R>,
     path: PathBuf,
 ) -> Result<WorkspaceContext<TauriInterface<R>>, Error> {
-    let interface = TauriInterface::new(window);
-    let workspace = workspace(path)?;
+    let cache = window.state::<AppState>().workspace_cache().clone();
+    let label = window.label().to_string();
+
+    let cached = cache.lock().unwrap().get(&label, &path);
+    let workspace = match cached {
+        Some(workspace) => workspace,
+        None => {
+            let workspace = workspace(path.clone())?;
+            cache.lock().unwrap().insert(label, path, workspace.clone());
+            workspace
+        }
+    };
 
+    let interface = TauriInterface::new(window);
     Ok(WorkspaceContext::new(interface, workspace))
 }
 
+/// There's a single cache for the app, so `ManifestDocument::save` can drop the workspaces it
+/// makes stale without access to the app state
+static WORKSPACE_CACHE: LazyLock<Arc<Mutex<WorkspaceCache>>> = LazyLock::new(Default::default);
+
+pub fn workspace_cache() -> &'static Arc<Mutex<WorkspaceCache>> {
+    &WORKSPACE_CACHE
+}
+
+/// Drops the cached workspaces of the manifest after it was written. The modification time alone
+/// doesn't catch writes within its resolution, which is coarse on some file systems.
+pub fn invalidate_cached_workspace(manifest: &Path) {
+    WORKSPACE_CACHE.lock().unwrap().invalidate(manifest);
+}
+
+/// Workspaces loaded by `workspace_context`, per window and workspace path
+#[derive(Default)]
+pub struct WorkspaceCache {
+    entries: HashMap<(String, PathBuf), CachedWorkspace>,
+}
+
+struct CachedWorkspace {
+    workspace: Workspace,
+    manifest: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl WorkspaceCache {
+    pub fn get(&self, window_label: &str, path: &Path) -> Option<Workspace> {
+        let entry = self
+            .entries
+            .get(&(window_label.to_string(), path.to_path_buf()))?;
+
+        // Catches edits before the watcher reports them
+        let unchanged = entry.modified.is_some() && modified(&entry.manifest) == entry.modified;
+        unchanged.then(|| entry.workspace.clone())
+    }
+
+    pub fn insert(&mut self, window_label: String, path: PathBuf, workspace: Workspace) {
+        let manifest = workspace.workspace.provenance.absolute_path();
+        let entry = CachedWorkspace {
+            modified: modified(&manifest),
+            manifest,
+            workspace,
+        };
+        self.entries.insert((window_label, path), entry);
+    }
+
+    /// Drops the cached workspace of the window
+    pub fn remove(&mut self, window_label: &str, path: &Path) {
+        self.entries
+            .remove(&(window_label.to_string(), path.to_path_buf()));
+    }
+
+    /// Drops all cached workspaces with this manifest (e.g. because it changed)
+    pub fn invalidate(&mut self, manifest: &Path) {
+        self.entries.retain(|_, entry| entry.manifest != manifest);
+    }
+
+    /// Drops all cached workspaces of the window (e.g. because it was closed)
+    pub fn invalidate_window(&mut self, window_label: &str) {
+        self.entries.retain(|(label, _), _| label != window_label);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Runs `operation` so it can be stopped with `cancel_operation(operation_id)`.
+///
+/// Cancelling drops the future wherever it is, in which case `None` is returned.
+/// Without an id the operation can't be cancelled.
+pub async fn cancelable<T>(
+    state: &AppState,
+    operation_id: Option<String>,
+    operation: impl Future<Output = T>,
+) -> Option<T> {
+    let Some(id) = operation_id else {
+        return Some(operation.await);
+    };
+
+    let token = state.start_operation(id.clone()).await;
+    let result = token.run_until_cancelled(operation).await;
+    state.finish_operation(&id).await;
+    result
+}
+
+/// Resolves `path` against the workspace `root` and makes sure it doesn't escape it.
+///
+/// Accepts relative paths and absolute paths inside the workspace.
+/// Returns the normalized path relative to `root`.
+pub fn path_within_workspace(root: &Path, path: &Path) -> Result<PathBuf, Error> {
+    let relative = if path.is_absolute() {
+        path.strip_prefix(root).map_err(|_| {
+            miette::miette!(
+                "Path '{}' is outside of the workspace '{}'",
+                path.display(),
+                root.display()
+            )
+        })?
+    } else {
+        path
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => normalized.push(part),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(miette::miette!(
+                        "Path '{}' escapes the workspace '{}'",
+                        path.display(),
+                        root.display()
+                    )
+                    .into());
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(miette::miette!(
+                    "Path '{}' must be relative to the workspace '{}'",
+                    path.display(),
+                    root.display()
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(normalized)
+}
+
+pub fn parse_environment_name(name: &str) -> Result<EnvironmentName, Error> {
+    Ok(EnvironmentName::from_str(name)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Invalid environment name '{name}'"))?)
+}
+
+pub fn parse_feature_name(name: &str) -> Result<FeatureName, Error> {
+    Ok(FeatureName::from_str(name)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Invalid feature name '{name}'"))?)
+}
+
+pub fn parse_platform(platform: &str) -> Result<Platform, Error> {
+    Ok(Platform::from_str(platform)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Unknown platform '{platform}'"))?)
+}
+
 /// Removes ANSI escape sequences from a string
 pub fn strip_ansi_escapes(str: &str) -> String {
     String::from_utf8(strip(str.as_bytes())).unwrap_or_else(|_| str.to_string())
@@ -68,3 +278,104 @@ pub fn launched_via_terminal() -> bool {
 
     is_tty || has_shell_env
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    const ROOT: &str = "/home/user/workspace";
+    #[cfg(windows)]
+    const ROOT: &str = r"C:\Users\user\workspace";
+
+    #[test]
+    fn relative_paths_are_normalized() {
+        assert_eq!(
+            path_within_workspace(Path::new(ROOT), Path::new("src/../scripts/./tools")).unwrap(),
+            Path::new("scripts").join("tools")
+        );
+    }
+
+    #[test]
+    fn parent_directories_cannot_escape() {
+        assert!(path_within_workspace(Path::new(ROOT), Path::new("../other")).is_err());
+        assert!(path_within_workspace(Path::new(ROOT), Path::new("src/../../other")).is_err());
+    }
+
+    #[test]
+    fn absolute_path_inside_the_root() {
+        let path = Path::new(ROOT).join("src");
+        assert_eq!(
+            path_within_workspace(Path::new(ROOT), &path).unwrap(),
+            Path::new("src")
+        );
+    }
+
+    #[test]
+    fn invalid_environment_name_is_an_error() {
+        let err = parse_environment_name("Not Valid!").unwrap_err();
+        assert!(err.to_string().contains("'Not Valid!'"));
+        assert!(parse_environment_name("py312").is_ok());
+    }
+
+    #[test]
+    fn invalid_platform_is_an_error() {
+        let err = parse_platform("linux-128").unwrap_err();
+        assert!(err.to_string().contains("'linux-128'"));
+        assert!(parse_platform("linux-64").is_ok());
+    }
+
+    #[test]
+    fn absolute_path_outside_the_root() {
+        let path = Path::new(ROOT).parent().unwrap().join("other");
+        assert!(path_within_workspace(Path::new(ROOT), &path).is_err());
+    }
+
+    /// The directory gets removed when the returned `TempDir` is dropped
+    fn temp_workspace() -> (tempfile::TempDir, PathBuf) {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("pixi.toml"), "").unwrap();
+        let dir = dunce::canonicalize(temp.path()).unwrap();
+        (temp, dir)
+    }
+
+    #[test]
+    fn manifest_path_is_resolved_to_its_directory() {
+        let (_temp, dir) = temp_workspace();
+
+        assert_eq!(workspace_search_root(&dir.join("pixi.toml")).unwrap(), dir);
+        assert_eq!(workspace_search_root(&dir.join("src/..")).unwrap(), dir);
+    }
+
+    #[test]
+    fn trailing_slash_is_ignored() {
+        let (_temp, dir) = temp_workspace();
+
+        let with_slash = PathBuf::from(format!("{}/", dir.display()));
+        assert_eq!(workspace_search_root(&with_slash).unwrap(), dir);
+        // Directories inside the workspace are searched upwards by pixi
+        assert_eq!(
+            workspace_search_root(&dir.join("src")).unwrap(),
+            dir.join("src")
+        );
+    }
+
+    #[test]
+    fn directory_without_manifest_is_no_workspace() {
+        let (_temp, dir) = temp_workspace();
+        std::fs::remove_file(dir.join("pixi.toml")).unwrap();
+
+        let err = workspace_search_root(&dir).unwrap_err();
+        assert!(err.to_string().contains("is not a pixi workspace"));
+        assert!(workspace_search_root(&dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn current_directory_is_the_root() {
+        assert_eq!(
+            path_within_workspace(Path::new(ROOT), Path::new(".")).unwrap(),
+            PathBuf::new()
+        );
+    }
+}
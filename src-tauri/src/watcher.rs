@@ -1,16 +1,23 @@
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use log::{debug, error};
 use miette::IntoDiagnostic;
 use notify::{EventKind, RecursiveMode};
 use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
-use tauri::{AppHandle, Emitter, Manager, Runtime, Window};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
-use crate::error::Error;
+use crate::state::AppState;
 
 #[derive(Default)]
 pub struct Watcher {
     watchers: HashMap<String, Debouncer<notify::RecommendedWatcher, RecommendedCache>>,
+    /// Contents of manifests written by the app itself, keyed by their canonical path
+    own_writes: Arc<std::sync::Mutex<HashMap<PathBuf, String>>>,
 }
 
 impl Watcher {
@@ -24,6 +31,8 @@ impl Watcher {
 
         let window_label_clone = window_label.clone();
         let manifest_path_clone = manifest.clone();
+        let own_writes = self.own_writes.clone();
+        let own_writes_key = canonical(&manifest);
 
         // Create debounced watcher with 500ms delay
         let mut debouncer = new_debouncer(
@@ -42,7 +51,21 @@ impl Watcher {
 
                     if manifest_modified {
                         debug!("Manifest changed: {:?}", manifest_path_clone);
-                        if let Some(window) = app.get_webview_window(&window_label_clone)
+
+                        // Cached editor detection results may be outdated now
+                        let state = app.state::<AppState>();
+                        if let Some(root) = manifest_path_clone.parent() {
+                            state.editor_cache().blocking_lock().invalidate(root);
+                        }
+                        state
+                            .workspace_cache()
+                            .lock()
+                            .unwrap()
+                            .invalidate(&manifest_path_clone);
+
+                        if is_own_write(&own_writes, &own_writes_key) {
+                            debug!("Ignoring the app's own write of {:?}", manifest_path_clone);
+                        } else if let Some(window) = app.get_webview_window(&window_label_clone)
                             && let Err(e) =
                                 window.emit_to(&window_label_clone, "manifest-changed", ())
                         {
@@ -81,26 +104,30 @@ impl Watcher {
             debug!("Stopped watcher for window {}", window_label);
         }
     }
+
+    /// Call before the app writes `contents` to `manifest` itself, so the change doesn't make the
+    /// windows reload it
+    pub fn expect_own_write(&self, manifest: &Path, contents: String) {
+        self.own_writes
+            .lock()
+            .unwrap()
+            .insert(canonical(manifest), contents);
+    }
 }
 
-#[tauri::command]
-pub async fn watch_manifest<R: Runtime>(
-    app: AppHandle<R>,
-    window: Window<R>,
-    state: tauri::State<'_, crate::state::AppState>,
-    manifest_path: PathBuf,
-) -> Result<(), Error> {
-    let mut watcher = state.watcher().lock().await;
-    watcher.watch(app, window.label().to_string(), manifest_path)?;
-    Ok(())
+fn canonical(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
 }
 
-#[tauri::command]
-pub async fn unwatch_manifest<R: Runtime>(
-    window: Window<R>,
-    state: tauri::State<'_, crate::state::AppState>,
-) -> Result<(), Error> {
-    let mut watcher = state.watcher().lock().await;
-    watcher.unwatch(window.label());
-    Ok(())
+/// Whether the manifest still has the contents the app wrote, changes by others are forgotten
+fn is_own_write(own_writes: &std::sync::Mutex<HashMap<PathBuf, String>>, manifest: &Path) -> bool {
+    let mut own_writes = own_writes.lock().unwrap();
+    let Some(expected) = own_writes.get(manifest) else {
+        return false;
+    };
+    if std::fs::read_to_string(manifest).is_ok_and(|contents| contents == *expected) {
+        return true;
+    }
+    own_writes.remove(manifest);
+    false
 }
@@ -1,62 +1,291 @@
 use log::info;
+use miette::IntoDiagnostic;
 use percent_encoding::{NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
-use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_store::StoreExt;
 use uuid::Uuid;
 
+use crate::{error::Error, lifecycle, recent, state::AppState};
+
+const DEFAULT_SIZE: WindowSize = WindowSize {
+    width: 900.0,
+    height: 700.0,
+};
+const MIN_SIZE: WindowSize = WindowSize {
+    width: 500.0,
+    height: 400.0,
+};
+/// Saved window geometries, keyed by the path the window was opened with
+const WINDOW_STORE_PATH: &str = "windows.json";
+/// Preferred size of new windows, paths always start with `/` so it can't clash with them
+const DEFAULT_SIZE_KEY: &str = "defaultSize";
+
 pub fn create_default_window<R: Runtime>(app: &AppHandle<R>) {
-    create_window(app, "/")
+    create_window(app, "/", None);
 }
 
 pub fn ensure_workspace_window<R: Runtime>(app: &AppHandle<R>, path: &std::path::Path) {
-    // If a file is passed (e.g., pixi.toml), use its parent directory
-    let workspace = if path.is_file() {
-        path.parent().unwrap_or(path)
-    } else {
-        path
-    };
+    let workspace = workspace_dir(path);
+
+    if let Some(window) = workspace_window(app, workspace) {
+        info!(
+            "Focusing existing window for workspace: {}",
+            workspace.display()
+        );
+        let _ = window.set_focus();
+        record_recent_workspace(app, workspace);
+        return;
+    }
+
+    info!("Creating new window for workspace: {}", workspace.display());
+    if create_window(app, &workspace_url(workspace), None) {
+        record_recent_workspace(app, workspace);
+    }
+}
 
-    let url = workspace_url(workspace);
+/// Opens another window of the workspace at `path`, even if it's open already. Everything a
+/// window holds (PTYs, the manifest watcher, cached workspaces) is keyed by its unique label, so
+/// the windows don't share any of it.
+#[tauri::command]
+pub async fn duplicate_workspace_window(
+    app: AppHandle,
+    workspace: std::path::PathBuf,
+) -> Result<(), Error> {
+    let workspace = workspace_dir(&workspace);
+    if !workspace.is_dir() {
+        return Err(miette::miette!("Workspace '{}' doesn't exist", workspace.display()).into());
+    }
+
+    info!(
+        "Opening another window for workspace: {}",
+        workspace.display()
+    );
+    if !create_window(&app, &workspace_url(workspace), None) {
+        return Err(miette::miette!("Failed to open another window").into());
+    }
+    Ok(())
+}
+
+/// The open window of the workspace at `path`, which can be its manifest or its directory
+pub fn workspace_window<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &std::path::Path,
+) -> Option<WebviewWindow<R>> {
+    let url = workspace_url(workspace_dir(path));
     let decoded_url = percent_decode_str(&url).decode_utf8_lossy();
+    let decoded_url = decoded_url.trim_end_matches('/');
 
-    // Check if a window for this workspace already exists
-    for (_label, window) in app.webview_windows() {
-        if let Ok(window_url) = window.url() {
+    app.webview_windows().into_values().find(|window| {
+        window.url().is_ok_and(|window_url| {
             let window_path = percent_decode_str(window_url.path()).decode_utf8_lossy();
-            let window_path = window_path.trim_end_matches('/');
-            let decoded_url = decoded_url.trim_end_matches('/');
-
-            if window_path == decoded_url {
-                info!(
-                    "Focusing existing window for workspace: {}",
-                    workspace.display()
-                );
-                let _ = window.set_focus();
-                return;
-            }
-        }
+            window_path.trim_end_matches('/') == decoded_url
+        })
+    })
+}
+
+fn workspace_dir(path: &std::path::Path) -> &std::path::Path {
+    // If a file is passed (e.g., pixi.toml), use its parent directory
+    if path.is_file() {
+        path.parent().unwrap_or(path)
+    } else {
+        path
     }
+}
 
-    info!("Creating new window for workspace: {}", workspace.display());
-    create_window(app, &url);
+pub(crate) fn record_recent_workspace<R: Runtime>(app: &AppHandle<R>, workspace: &std::path::Path) {
+    if let Err(e) = recent::record(app, workspace) {
+        log::warn!(
+            "Failed to add {} to the recent workspaces: {}",
+            workspace.display(),
+            e
+        );
+    }
 }
 
-pub fn create_window<R: Runtime, M: Manager<R>>(manager: &M, path: &str) {
+/// Opens a window with the saved geometry of `path`. Without one it gets the `size`, or the
+/// preferred default size.
+///
+/// Returns false if the window couldn't be created
+pub fn create_window<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    path: &str,
+    size: Option<WindowSize>,
+) -> bool {
     // Windows need a unique label
     let label = format!("pixi-gui-window-{}", Uuid::new_v4());
     let url = tauri::WebviewUrl::App(path.trim_start_matches('/').into());
 
-    let builder = WebviewWindowBuilder::new(manager, &label, url)
-        .inner_size(900.0, 700.0)
-        .min_inner_size(500.0, 400.0)
+    let app = manager.app_handle();
+    let size = size.unwrap_or_else(|| default_size(app)).at_least(MIN_SIZE);
+    let mut builder = WebviewWindowBuilder::new(manager, &label, url)
+        .inner_size(size.width, size.height)
+        .min_inner_size(MIN_SIZE.width, MIN_SIZE.height)
         .title("Pixi GUI");
 
+    // The geometry is saved per workspace, the path of the default window is just `/`
+    let monitors = monitor_areas(app);
+    if let Some(geometry) = load_geometry(app, path).and_then(|saved| saved.fit_into(&monitors)) {
+        builder = builder
+            .inner_size(geometry.width, geometry.height)
+            .position(geometry.x, geometry.y);
+    }
+
     // Needed, otherwise you get a gliched transparent look when you use macOS Tahoe (26) or newer
     #[cfg(target_os = "macos")]
     let builder = builder.title_bar_style(tauri::TitleBarStyle::Transparent);
 
     match builder.build() {
-        Ok(_) => {}
-        Err(e) => log::error!("Failed to create window: {}", e),
+        Ok(window) => {
+            let key = path.to_string();
+            let handle = window.clone();
+            window.on_window_event(move |event| {
+                if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+                    save_geometry(&handle, &key);
+                }
+            });
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to create window: {}", e);
+            false
+        }
+    }
+}
+
+/// Inner size of a window in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WindowSize {
+    fn at_least(self, min: WindowSize) -> WindowSize {
+        WindowSize {
+            width: self.width.max(min.width),
+            height: self.height.max(min.height),
+        }
+    }
+}
+
+/// The preferred size of new windows without a saved geometry
+fn default_size<R: Runtime>(app: &AppHandle<R>) -> WindowSize {
+    app.store(WINDOW_STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(DEFAULT_SIZE_KEY))
+        .and_then(|size| serde_json::from_value(size).ok())
+        .unwrap_or(DEFAULT_SIZE)
+}
+
+#[tauri::command]
+pub fn get_default_window_size(app: AppHandle) -> WindowSize {
+    default_size(&app)
+}
+
+/// Sets the size of new windows that have no saved geometry yet, `None` restores the built-in size
+#[tauri::command]
+pub fn set_default_window_size(app: AppHandle, size: Option<WindowSize>) -> Result<(), Error> {
+    let store = app.store(WINDOW_STORE_PATH).into_diagnostic()?;
+    match size {
+        Some(size) => store.set(
+            DEFAULT_SIZE_KEY,
+            serde_json::to_value(size.at_least(MIN_SIZE)).into_diagnostic()?,
+        ),
+        None => {
+            store.delete(DEFAULT_SIZE_KEY);
+        }
+    }
+    store.save().into_diagnostic()?;
+    Ok(())
+}
+
+/// Position and size of a window or the area of a monitor, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Geometry {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Geometry {
+    /// Moves the window onto the monitor it overlaps the most, so it can't end up off-screen
+    /// (e.g. after a monitor was disconnected). Returns `None` if it isn't on any monitor.
+    fn fit_into(self, monitors: &[Geometry]) -> Option<Geometry> {
+        let monitor = monitors
+            .iter()
+            .map(|monitor| (self.overlap(monitor), monitor))
+            .filter(|(overlap, _)| *overlap > 0.0)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, monitor)| monitor)?;
+
+        let width = self.width.min(monitor.width);
+        let height = self.height.min(monitor.height);
+        Some(Geometry {
+            x: self.x.clamp(monitor.x, monitor.x + monitor.width - width),
+            y: self.y.clamp(monitor.y, monitor.y + monitor.height - height),
+            width,
+            height,
+        })
+    }
+
+    fn overlap(&self, other: &Geometry) -> f64 {
+        let width = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+        width.max(0.0) * height.max(0.0)
+    }
+}
+
+fn monitor_areas<R: Runtime>(app: &AppHandle<R>) -> Vec<Geometry> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            let position = monitor.position().to_logical::<f64>(scale);
+            let size = monitor.size().to_logical::<f64>(scale);
+            Geometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        })
+        .collect()
+}
+
+fn load_geometry<R: Runtime>(app: &AppHandle<R>, key: &str) -> Option<Geometry> {
+    let store = app.store(WINDOW_STORE_PATH).ok()?;
+    serde_json::from_value(store.get(key)?).ok()
+}
+
+fn save_geometry<R: Runtime>(window: &WebviewWindow<R>, key: &str) {
+    // Minimized windows are moved off-screen on some platforms
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let geometry = (|| {
+        let scale = window.scale_factor()?;
+        let position = window.outer_position()?.to_logical::<f64>(scale);
+        let size = window.inner_size()?.to_logical::<f64>(scale);
+        tauri::Result::Ok(Geometry {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    })();
+
+    // The store saves itself shortly after the last change
+    match (geometry, window.app_handle().store(WINDOW_STORE_PATH)) {
+        (Ok(geometry), Ok(store)) => match serde_json::to_value(geometry) {
+            Ok(geometry) => store.set(key, geometry),
+            Err(e) => log::warn!("Failed to serialize the window geometry: {}", e),
+        },
+        (Err(e), _) => log::warn!("Failed to get the window geometry: {}", e),
+        (_, Err(e)) => log::warn!("Failed to open the window store: {}", e),
     }
 }
 
@@ -67,9 +296,134 @@ pub fn open_new_window(app: AppHandle) {
     });
 }
 
+/// Like [`open_new_window`], with this size unless the start screen has a saved geometry
+#[tauri::command]
+pub fn open_new_window_sized(app: AppHandle, width: f64, height: f64) {
+    tauri::async_runtime::spawn(async move {
+        create_window(&app, "/", Some(WindowSize { width, height }));
+    });
+}
+
+#[derive(Serialize)]
+pub struct WindowInfo {
+    label: String,
+    workspace_path: Option<String>,
+    title: String,
+}
+
+#[tauri::command]
+pub fn list_windows(app: AppHandle) -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = app
+        .webview_windows()
+        .into_values()
+        .map(|window| WindowInfo {
+            label: window.label().to_string(),
+            workspace_path: window.url().ok().and_then(|url| workspace_path(url.path())),
+            title: window.title().unwrap_or_default(),
+        })
+        .collect();
+    windows.sort_by(|a, b| a.title.cmp(&b.title));
+    windows
+}
+
+/// Closes the window after killing its PTYs and stopping its manifest watcher
+#[tauri::command]
+pub async fn close_window(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    label: String,
+) -> Result<(), Error> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| miette::miette!("Window `{label}` not found"))?;
+
+    lifecycle::release(&state, &label).await?;
+    window.close().into_diagnostic()?;
+
+    Ok(())
+}
+
+/// The workspace of a window URL path like `/workspace/<encoded path>`
+pub fn workspace_path(url_path: &str) -> Option<String> {
+    let encoded = url_path.strip_prefix("/workspace/")?.trim_end_matches('/');
+    let path = percent_decode_str(encoded).decode_utf8_lossy();
+    (!path.is_empty()).then(|| path.into_owned())
+}
+
 fn workspace_url(file: &std::path::Path) -> String {
     let path = file.to_string_lossy();
     let path = path.trim_end_matches('/');
     let encoded = utf8_percent_encode(path, NON_ALPHANUMERIC);
     format!("/workspace/{encoded}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Geometry = Geometry {
+        x: 0.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1080.0,
+    };
+
+    #[test]
+    fn workspace_path_round_trip() {
+        let url = workspace_url(std::path::Path::new("/home/user/my project"));
+
+        assert_eq!(
+            workspace_path(&url).as_deref(),
+            Some("/home/user/my project")
+        );
+        assert_eq!(workspace_path("/"), None);
+    }
+
+    #[test]
+    fn window_is_moved_back_onto_the_monitor() {
+        let window = Geometry {
+            x: 1800.0,
+            y: -50.0,
+            width: 900.0,
+            height: 700.0,
+        };
+
+        assert_eq!(
+            window.fit_into(&[MONITOR]),
+            Some(Geometry {
+                x: 1020.0,
+                y: 0.0,
+                width: 900.0,
+                height: 700.0,
+            })
+        );
+    }
+
+    #[test]
+    fn window_of_a_disconnected_monitor_is_not_restored() {
+        let window = Geometry {
+            x: 2500.0,
+            y: 100.0,
+            width: 900.0,
+            height: 700.0,
+        };
+
+        assert_eq!(window.fit_into(&[MONITOR]), None);
+    }
+
+    #[test]
+    fn default_size_is_not_smaller_than_the_minimum() {
+        let size = WindowSize {
+            width: 300.0,
+            height: 800.0,
+        };
+
+        assert_eq!(
+            size.at_least(MIN_SIZE),
+            WindowSize {
+                width: 500.0,
+                height: 800.0,
+            }
+        );
+    }
+}
@@ -12,7 +12,11 @@ import {
   DialogTitle,
 } from "@/components/shadcn/dialog";
 
-import { getAppVersion, getPixiVersion } from "@/lib/pixi/version";
+import {
+  getAppVersion,
+  getPixiVersion,
+  getResolvedPixiVersion,
+} from "@/lib/pixi/version";
 
 interface AboutDialogProps {
   open: boolean;
@@ -23,17 +27,22 @@ export function AboutDialog({ open, onOpenChange }: AboutDialogProps) {
   const [appName, setAppName] = useState<string>("");
   const [appVersion, setAppVersion] = useState<string>("");
   const [pixiVersion, setPixiVersion] = useState<string>("");
+  const [resolvedPixiVersion, setResolvedPixiVersion] = useState<
+    string | null
+  >("");
 
   useEffect(() => {
     const loadAppInfo = async () => {
-      const [name, version, pixi] = await Promise.all([
+      const [name, version, pixi, resolvedPixi] = await Promise.all([
         getName(),
         getAppVersion(),
         getPixiVersion(),
+        getResolvedPixiVersion().catch(() => null),
       ]);
       setAppName(name);
       setAppVersion(version);
       setPixiVersion(pixi);
+      setResolvedPixiVersion(resolvedPixi);
     };
 
     if (open) {
@@ -60,6 +69,11 @@ export function AboutDialog({ open, onOpenChange }: AboutDialogProps) {
           <div className="w-full space-y-pfx-s">
             <Row title={`${appName} Version`} subtitle={appVersion} property />
             <Row title="Pixi API Version" subtitle={pixiVersion} property />
+            <Row
+              title="Pixi Version"
+              subtitle={resolvedPixiVersion ?? "Not installed"}
+              property
+            />
           </div>
 
           <Button
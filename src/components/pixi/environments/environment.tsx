@@ -23,11 +23,17 @@ import {
   listInstallableEditors,
   setEditorPreference,
 } from "@/lib/editor";
+import { errorMessage } from "@/lib/error";
 import { subscribe } from "@/lib/event";
 import { addCondaDeps } from "@/lib/pixi/workspace/add";
 import { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 import type { Task } from "@/lib/pixi/workspace/task";
-import { type PtyExitEvent, type PtyStartEvent, listPtys } from "@/lib/pty";
+import {
+  type PtyExitEvent,
+  type PtyStartEvent,
+  isOwnPty,
+  listPtys,
+} from "@/lib/pty";
 
 interface EnvironmentProps {
   name: string;
@@ -85,6 +91,7 @@ export function Environment({ name, tasks, filter }: EnvironmentProps) {
       const commands = new Map<string, { command: string; editor?: Editor }>();
       for (const pty of ptys) {
         if (
+          isOwnPty(pty) &&
           pty.invocation.cwd === workspace.root &&
           pty.invocation.kind.kind === "command" &&
           pty.invocation.kind.environment === name
@@ -183,15 +190,15 @@ export function Environment({ name, tasks, filter }: EnvironmentProps) {
 
       // Refresh editors after successful installation
       const [available, installable] = await Promise.all([
-        listAvailableEditors(workspace.root, name),
-        listInstallableEditors(workspace.root, name),
+        listAvailableEditors(workspace.root, name, true),
+        listInstallableEditors(workspace.root, name, true),
       ]);
       setAvailableEditors(available);
       setInstallableEditors(installable);
 
       toast.success(`Successfully installed ${packageName}`);
     } catch (error) {
-      toast.error(`Failed to install ${packageName}: ${error}`);
+      toast.error(`Failed to install ${packageName}: ${errorMessage(error)}`);
     }
   };
 
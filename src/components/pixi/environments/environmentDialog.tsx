@@ -16,6 +16,7 @@ import {
 } from "@/components/shadcn/dialog";
 import { Input } from "@/components/shadcn/input";
 
+import { errorMessage } from "@/lib/error";
 import {
   type Environment,
   type Feature,
@@ -97,7 +98,7 @@ export function EnvironmentDialog({
       onSuccess?.();
     } catch (error) {
       setSubmitError(
-        `Failed to ${isEditMode ? "edit" : "add"} environment: ${error}`,
+        `Failed to ${isEditMode ? "edit" : "add"} environment: ${errorMessage(error)}`,
       );
     }
   };
@@ -111,7 +112,7 @@ export function EnvironmentDialog({
       onOpenChange(false);
       onSuccess?.();
     } catch (error) {
-      setSubmitError(`Failed to delete environment: ${error}`);
+      setSubmitError(`Failed to delete environment: ${errorMessage(error)}`);
     }
   };
 
@@ -33,6 +33,7 @@ import {
 import { Input } from "@/components/shadcn/input";
 import { Spinner } from "@/components/shadcn/spinner";
 
+import { errorMessage } from "@/lib/error";
 import { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 import { type Workspace, setChannels } from "@/lib/pixi/workspace/workspace";
 
@@ -150,7 +151,7 @@ export function ChannelDialog({
       onSuccess?.();
       onOpenChange(false);
     } catch (err) {
-      setSubmitError(`Failed to update channels: ${err}`);
+      setSubmitError(`Failed to update channels: ${errorMessage(err)}`);
     } finally {
       setIsUpdating(false);
     }
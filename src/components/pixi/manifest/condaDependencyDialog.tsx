@@ -28,6 +28,7 @@ import {
 import { Input } from "@/components/shadcn/input";
 import { Spinner } from "@/components/shadcn/spinner";
 
+import { errorMessage } from "@/lib/error";
 import {
   type DependencyOptions,
   type MatchSpec,
@@ -46,6 +47,8 @@ import {
   type Workspace,
 } from "@/lib/pixi/workspace/workspace";
 
+const SEARCH_LIMIT = 100;
+
 interface CondaDependencyDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
@@ -128,16 +131,18 @@ export function CondaDependencyDialog({
 
       try {
         const searchTerm = `${packageSearch.trim()}*`;
-        const results = await searchWildcard(workspace.root, searchTerm);
+        const results = await searchWildcard(workspace.root, searchTerm, {
+          limit: SEARCH_LIMIT,
+        });
 
         // Only update if this is still the most recent search
         if (currentSearchId === searchIdRef.current) {
-          setSearchResults(results ?? []);
+          setSearchResults(results.packages.map((pkg) => pkg.record));
         }
       } catch (err) {
         // Only show error if this is still the most recent search
         if (currentSearchId === searchIdRef.current) {
-          setError(String(err));
+          setError(errorMessage(err));
         }
       } finally {
         // Only update loading state if this is still the most recent search
@@ -249,7 +254,7 @@ export function CondaDependencyDialog({
       onOpenChange(false);
       onSuccess?.();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setIsUpdating(false);
     }
@@ -279,7 +284,7 @@ export function CondaDependencyDialog({
       onOpenChange(false);
       onSuccess?.();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setIsUpdating(false);
     }
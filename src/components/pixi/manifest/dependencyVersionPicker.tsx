@@ -10,6 +10,7 @@ import {
   TooltipTrigger,
 } from "@/components/shadcn/tooltip";
 
+import { errorMessage } from "@/lib/error";
 import {
   type RepoDataRecord,
   getRepoDataRecordId,
@@ -77,7 +78,7 @@ export function DependencyVersionPicker({
           setAvailableVersions(uniqueVersions);
         }
       } catch (err) {
-        setError(`Failed to list available versions: ${err}`);
+        setError(`Failed to list available versions: ${errorMessage(err)}`);
       } finally {
         setIsLoading(false);
       }
@@ -22,6 +22,7 @@ import {
 } from "@/components/shadcn/dialog";
 import { Spinner } from "@/components/shadcn/spinner";
 
+import { errorMessage } from "@/lib/error";
 import {
   type Workspace,
   addPlatforms,
@@ -98,7 +99,7 @@ export function PlatformDialog({
       onSelectionChange(selectedPlatforms);
       onOpenChange(false);
     } catch (err) {
-      setSubmitError(`Failed to update platforms: ${err}`);
+      setSubmitError(`Failed to update platforms: ${errorMessage(err)}`);
     } finally {
       setIsUpdating(false);
     }
@@ -20,6 +20,7 @@ import {
 import { Input } from "@/components/shadcn/input";
 import { Spinner } from "@/components/shadcn/spinner";
 
+import { errorMessage } from "@/lib/error";
 import { type DependencyOptions, addPypiDeps } from "@/lib/pixi/workspace/add";
 import { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 import { removePypiDeps } from "@/lib/pixi/workspace/remove";
@@ -103,7 +104,7 @@ export function PypiDependencyDialog({
       onOpenChange(false);
       onSuccess?.();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setIsUpdating(false);
     }
@@ -132,7 +133,7 @@ export function PypiDependencyDialog({
       onOpenChange(false);
       onSuccess?.();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setIsUpdating(false);
     }
@@ -30,6 +30,7 @@ import {
   SelectValue,
 } from "@/components/shadcn/select";
 
+import { errorMessage } from "@/lib/error";
 import { GitAttributes, ManifestFormat, init } from "@/lib/pixi/workspace/init";
 import { getPlatformName } from "@/lib/utils";
 
@@ -75,7 +76,7 @@ export function NewWorkspaceDialog({
         setLocation(selectedPath);
       }
     } catch (error) {
-      setSubmitError(`Failed to open directory dialog: ${error}`);
+      setSubmitError(`Failed to open directory dialog: ${errorMessage(error)}`);
     }
   };
 
@@ -97,7 +98,7 @@ export function NewWorkspaceDialog({
       onOpenChange(false);
       onSuccess?.(fullPath);
     } catch (error) {
-      setSubmitError(`Failed to create workspace: ${error}`);
+      setSubmitError(`Failed to create workspace: ${errorMessage(error)}`);
     }
   };
 
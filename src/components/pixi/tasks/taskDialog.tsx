@@ -33,6 +33,7 @@ import {
   SelectValue,
 } from "@/components/shadcn/select";
 
+import { errorMessage } from "@/lib/error";
 import type { Task } from "@/lib/pixi/workspace/task";
 import {
   addTask,
@@ -254,18 +255,20 @@ export function TaskDialog({
         };
 
     try {
-      if (isEditMode) {
-        // When editing, remove the old task and add the new one
-        // (This handles task renaming as well)
-        await removeTask(workspace.manifest, editTaskName, feature.name);
-      }
-
-      await addTask(workspace.manifest, name.trim(), task, feature.name);
+      // When editing, the new task replaces the old one
+      // (This handles task renaming as well)
+      await addTask(
+        workspace.manifest,
+        name.trim(),
+        task,
+        feature.name,
+        isEditMode ? { replace: editTaskName } : {},
+      );
 
       onOpenChange(false);
       onSuccess?.(task, name.trim());
     } catch (error) {
-      setSubmitError(`Failed to ${isEditMode ? "edit" : "add"} task: ${error}`);
+      setSubmitError(`Failed to ${isEditMode ? "edit" : "add"} task: ${errorMessage(error)}`);
     }
   };
 
@@ -274,11 +277,11 @@ export function TaskDialog({
     setSubmitError("");
 
     try {
-      await removeTask(workspace.manifest, editTaskName, feature.name);
+      await removeTask(workspace.manifest, editTaskName, feature.name, true);
       onOpenChange(false);
       onDelete?.();
     } catch (error) {
-      setSubmitError(`Failed to delete task: ${error}`);
+      setSubmitError(`Failed to delete task: ${errorMessage(error)}`);
     }
   };
 
@@ -1,3 +1,5 @@
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+
 import { usePty } from "@/hooks/usePty";
 import { listTask } from "@/lib/pixi/workspace/task";
 import type { Workspace } from "@/lib/pixi/workspace/workspace";
@@ -65,6 +67,7 @@ export function useProcess(options: ProcessOptions): ProcessState {
           task: options.taskName,
           environment: envCount > 1 ? environment : undefined,
           args,
+          notify_on_exit: true,
         },
       },
       cols,
@@ -109,10 +112,12 @@ export async function startCommand(
   );
 }
 
+/** Unique per window, a workspace can be open in several windows */
 function getPtyId(options: ProcessOptions): string {
+  const window = getCurrentWebviewWindow().label;
   if ("taskName" in options) {
-    return `task-${options.workspace.root}-${options.environment}-${options.taskName}`;
+    return `${window}/task-${options.workspace.root}-${options.environment}-${options.taskName}`;
   } else {
-    return `command-${options.workspace.root}-${options.environment}-${options.command}`;
+    return `${window}/command-${options.workspace.root}-${options.environment}-${options.command}`;
   }
 }
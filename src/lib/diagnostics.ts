@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** The app and the system it runs on, for bug reports */
+export interface SystemInfo {
+  os: string;
+  arch: string;
+  app_version: string;
+  /** Version of the bundled pixi */
+  pixi_version: string;
+  tauri_version: string;
+  /** `null` if the webview doesn't report its version */
+  webview_version: string | null;
+  /** The conda platform, e.g. `linux-64` */
+  platform: string;
+}
+
+export async function getSystemInfo(): Promise<SystemInfo> {
+  return invoke<SystemInfo>("system_info");
+}
+
+export interface CacheInfo {
+  path: string;
+  size_bytes: number;
+  /** Packages extracted in the `pkgs` directory */
+  package_count: number;
+  /** Walking the cache took too long, `size_bytes` is only partial */
+  partial: boolean;
+}
+
+/** Where pixi caches the packages and how much space they take */
+export async function getCacheInfo(): Promise<CacheInfo> {
+  return invoke<CacheInfo>("cache_info");
+}
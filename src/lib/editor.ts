@@ -11,20 +11,24 @@ export interface Editor {
 export async function listAvailableEditors(
   workspace: string,
   environment: string,
+  forceRefresh: boolean = false,
 ): Promise<Editor[]> {
   return await invoke<Editor[]>("list_available_editors", {
     workspace,
     environment,
+    forceRefresh,
   });
 }
 
 export async function listInstallableEditors(
   workspace: string,
   environment: string,
+  forceRefresh: boolean = false,
 ): Promise<Editor[]> {
   return await invoke<Editor[]>("list_installable_editors", {
     workspace,
     environment,
+    forceRefresh,
   });
 }
 
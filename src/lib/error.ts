@@ -0,0 +1,36 @@
+/** Payload of a failed backend command */
+export interface CommandError {
+  message: string;
+  help: string | null;
+  /** Sources of the error, outermost first */
+  causes: string[];
+  code: string | null;
+  /** The whole error with its ANSI colors, e.g. for an xterm-based panel */
+  ansi: string;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    "message" in error &&
+    "causes" in error
+  );
+}
+
+/** Plain text of an error, including its causes and help */
+export function errorMessage(error: unknown): string {
+  if (!isCommandError(error)) return String(error);
+
+  const lines = [error.message];
+  for (const cause of error.causes) {
+    lines.push(`Caused by: ${cause}`);
+  }
+  if (error.help) lines.push(`Help: ${error.help}`);
+  return lines.join("\n");
+}
+
+/** Like `errorMessage`, but keeps the colors of the backend's messages */
+export function coloredErrorMessage(error: unknown): string {
+  return isCommandError(error) ? error.ansi : String(error);
+}
@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface WorkspaceOpenedEvent {
+  root: string;
+  manifest: string;
+}
+
+// Opening and closing are chained, so a close that is still running can't
+// tear down the workspace that was opened after it
+let pending: Promise<unknown> = Promise.resolve();
+
+function chain(command: string, args?: Record<string, unknown>): Promise<void> {
+  const result = pending.then(() => invoke<void>(command, args));
+  pending = result.catch(() => {});
+  return result;
+}
+
+/**
+ * Watches the manifest of the workspace for this window, caches the
+ * workspace and records it in the recent workspaces. Emits
+ * `workspace-opened` afterwards.
+ */
+export function workspaceOpened(workspace: string): Promise<void> {
+  return chain("workspace_opened", { workspace });
+}
+
+/**
+ * Kills the PTYs of this window, stops the manifest watcher and drops the
+ * cached workspace. Emits `workspace-closed` afterwards.
+ */
+export function workspaceClosed(): Promise<void> {
+  return chain("workspace_closed");
+}
@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type LogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
+export function getLogLevel(): Promise<LogLevel> {
+  return invoke<LogLevel>("log_level");
+}
+
+/** Applies until the app exits, e.g. `debug` to capture more details of a bug */
+export async function setLogLevel(level: LogLevel): Promise<void> {
+  await invoke("set_log_level", { level });
+}
+
+/** The file the logs are written to, e.g. to attach it to an issue report */
+export function getLogFilePath(): Promise<string> {
+  return invoke<string>("get_log_file_path");
+}
+
+/** The last `lines` lines of the log file, empty if nothing was logged yet */
+export function readAppLog(lines: number): Promise<string> {
+  return invoke<string>("read_app_log", { lines });
+}
@@ -0,0 +1,26 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface PinnedWorkspaceEntry {
+  manifest: string;
+  name: string;
+}
+
+/** In the order the user put them */
+export function listPinnedWorkspaces(): Promise<PinnedWorkspaceEntry[]> {
+  return invoke<PinnedWorkspaceEntry[]>("list_pinned_workspaces");
+}
+
+/** `path` is the manifest or the root of the workspace */
+export async function pinWorkspace(path: string): Promise<void> {
+  await invoke("pin_workspace", { path });
+}
+
+/** `path` is the manifest or the root of the workspace */
+export async function unpinWorkspace(path: string): Promise<void> {
+  await invoke("unpin_workspace", { path });
+}
+
+/** `order` has to contain the manifests of all pinned workspaces */
+export async function reorderPinned(order: string[]): Promise<void> {
+  await invoke("reorder_pinned", { order });
+}
@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface GlobalTool {
+  name: string;
+  /** `null` if the environment isn't installed (yet) */
+  version: string | null;
+  exposed_binaries: string[];
+}
+
+/** Tools installed with `pixi global install`, empty if there are none */
+export function listGlobalTools(): Promise<GlobalTool[]> {
+  return invoke<GlobalTool[]>("list_global_tools");
+}
@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api/core";
+
+import { isCommandError } from "@/lib/error";
+
+/** Returns false if the operation isn't running (anymore) */
+export function cancelOperation(id: string): Promise<boolean> {
+  return invoke<boolean>("cancel_operation", { id });
+}
+
+/** Whether a command failed because its operation was cancelled */
+export function isCancelled(error: unknown): boolean {
+  return isCommandError(error) && error.code === "pixi_gui::cancelled";
+}
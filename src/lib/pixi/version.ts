@@ -7,3 +7,11 @@ export async function getPixiVersion(): Promise<string> {
 export async function getAppVersion(): Promise<string> {
   return invoke<string>("app_version");
 }
+
+/**
+ * Version of the pixi binary that runs tasks and terminals, `null` if there
+ * is none. Can differ from the bundled `getPixiVersion`.
+ */
+export async function getResolvedPixiVersion(): Promise<string | null> {
+  return invoke<string | null>("resolved_pixi_version");
+}
@@ -5,6 +5,7 @@ import type { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 export interface DependencyOptions {
   feature: string;
   platforms: string[];
+  /** Only updates the manifest and lock file, like `pixi add --no-install` */
   no_install: boolean;
   lock_file_usage: LockFileUsage;
 }
@@ -17,15 +18,50 @@ export interface MatchSpec {
   subdir?: string;
 }
 
+export interface CondaDepOptions {
+  build?: string;
+  channel?: string;
+  feature?: string;
+  platform?: string;
+}
+
+/** Pass an `operationId` to be able to stop it with `cancelOperation` */
 export async function addCondaDeps(
   workspace: string,
   specs: Record<string, MatchSpec>,
   depOptions: DependencyOptions,
+  options: CondaDepOptions = {},
+  operationId?: string,
 ): Promise<void> {
   await invoke("add_conda_deps", {
     workspace,
     specs,
     depOptions,
+    options,
+    operationId,
+  });
+}
+
+/**
+ * How a dependency added from a search result is pinned, e.g. for 1.2.3:
+ * `Exact` is `==1.2.3`, `MinorCompatible` is `>=1.2.3,<1.3`, `Latest` is
+ * `>=1.2.3` and with `None` pixi pins the version it solves.
+ */
+export type PinMode = "Exact" | "MinorCompatible" | "Latest" | "None";
+
+/** Takes any search result with a name and version, e.g. a `PackageVersion` */
+export async function addFromSearchResult(
+  workspace: string,
+  result: { name: string; version: string },
+  pin: PinMode,
+  options: { feature?: string; platform?: string } = {},
+): Promise<void> {
+  await invoke("add_from_search_result", {
+    workspace,
+    result: { name: result.name, version: result.version },
+    pin,
+    feature: options.feature ?? null,
+    platform: options.platform ?? null,
   });
 }
 
@@ -42,3 +78,46 @@ export async function addPypiDeps(
     depOptions,
   });
 }
+
+export type PypiSource =
+  | { git: string; rev?: string; branch?: string; tag?: string }
+  | { path: string; editable: boolean }
+  | { version?: string };
+
+export async function addPypiSourceDep(
+  workspace: string,
+  name: string,
+  source: PypiSource,
+  depOptions: DependencyOptions,
+): Promise<void> {
+  await invoke("add_pypi_source_dep", {
+    workspace,
+    name,
+    source,
+    depOptions,
+  });
+}
+
+// Adds all specs at once, nothing is added if one of them is invalid
+export async function addCondaDepsBatch(
+  workspace: string,
+  specs: string[],
+  depOptions: DependencyOptions,
+): Promise<void> {
+  await invoke("add_conda_deps_batch", { workspace, specs, depOptions });
+}
+
+// Adds all requirements at once, nothing is added if one of them is invalid
+export async function addPypiDepsBatch(
+  workspace: string,
+  specs: string[],
+  editable: boolean,
+  depOptions: DependencyOptions,
+): Promise<void> {
+  await invoke("add_pypi_deps_batch", {
+    workspace,
+    specs,
+    editable,
+    depOptions,
+  });
+}
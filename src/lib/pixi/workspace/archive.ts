@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Packs the manifest, and optionally the lock file and the installed
+ * environments, into a `.tar.gz` in a temporary directory.
+ * Returns the path of the archive.
+ */
+export function exportArchive(
+  workspace: string,
+  includeLock: boolean,
+  includeEnv: boolean,
+): Promise<string> {
+  return invoke<string>("export_archive", {
+    workspace,
+    includeLock,
+    includeEnv,
+  });
+}
+
+/**
+ * Extracts an archive of `exportArchive` into the empty `destination`.
+ * Returns the root of the extracted workspace.
+ */
+export function importArchive(
+  path: string,
+  destination: string,
+): Promise<string> {
+  return invoke<string>("import_archive", { path, destination });
+}
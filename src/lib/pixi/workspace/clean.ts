@@ -0,0 +1,17 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Removes the installed environments (or only `environment`), they get reinstalled when used.
+ * Returns the number of bytes freed.
+ */
+export function cleanEnvironments(
+  workspace: string,
+  environment?: string,
+): Promise<number> {
+  return invoke<number>("clean_environments", { workspace, environment });
+}
+
+/** Removes the global package cache. Returns the number of bytes freed. */
+export function cleanCache(): Promise<number> {
+  return invoke<number>("clean_cache");
+}
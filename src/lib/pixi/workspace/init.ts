@@ -21,6 +21,18 @@ export interface InitOptions {
   scm?: GitAttributes | null;
 }
 
-export async function init(options: InitOptions): Promise<void> {
-  await invoke("init", { options });
+/**
+ * A file the dependencies are taken from, `conda_yaml` is an
+ * `environment.yml` and `requirements_txt` adds pypi dependencies.
+ */
+export interface InitImport {
+  kind: "conda_yaml" | "requirements_txt";
+  path: string;
+}
+
+export async function init(
+  options: InitOptions,
+  initImport?: InitImport,
+): Promise<void> {
+  await invoke("init", { options, import: initImport });
 }
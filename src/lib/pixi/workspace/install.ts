@@ -0,0 +1,17 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Installs the environment as locked, keeping the packages that are already
+ * installed. With `locked` it fails with the `isLockFileOutdated` error instead
+ * of updating the lock file.
+ *
+ * Pass an `operationId` to be able to stop it with `cancelOperation`
+ */
+export async function install(
+  workspace: string,
+  environment?: string,
+  locked: boolean = false,
+  operationId?: string,
+): Promise<void> {
+  await invoke("install", { workspace, environment, locked, operationId });
+}
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 
+import { isCommandError } from "@/lib/error";
 import type { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 
 export type PackageKind = "conda" | "pypi";
@@ -37,6 +38,10 @@ export interface ListPackagesOptions {
   environment?: string | null;
   explicit?: boolean;
   noInstall?: boolean;
+  /**
+   * With `Locked` only the lock file is read, nothing gets solved or installed
+   * and it fails if the lock file is out of date, see `isLockFileOutdated`
+   */
   lockFileUsage?: LockFileUsage;
 }
 
@@ -54,3 +59,10 @@ export function listPackages(
     lockFileUsage: options.lockFileUsage ?? "Update",
   });
 }
+
+/** Whether a command failed because it wasn't allowed to update the lock file */
+export function isLockFileOutdated(error: unknown): boolean {
+  return (
+    isCommandError(error) && error.code === "pixi_gui::lock_file_outdated"
+  );
+}
@@ -0,0 +1,53 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * The environments solved in `pixi.lock`, with their platforms.
+ * Environments that are missing still need to be installed.
+ */
+export function getLockfileEnvironments(
+  workspace: string,
+): Promise<Record<string, string[]>> {
+  return invoke<Record<string, string[]>>("lockfile_environments", {
+    workspace,
+  });
+}
+
+/** Current content of `pixi.lock`, `null` if there's none yet */
+export function getLockfileSnapshot(workspace: string): Promise<string | null> {
+  return invoke<string | null>("lockfile_snapshot", { workspace });
+}
+
+export interface PackageChange {
+  environment: string;
+  platform: string;
+  name: string;
+  kind: "conda" | "pypi";
+  change: "added" | "removed" | "version_changed";
+  previous_version: string | null;
+  version: string | null;
+}
+
+/**
+ * The packages that changed since the `previous` `getLockfileSnapshot`.
+ * Without a previous snapshot all packages are reported as added.
+ */
+export function getLockfileDiff(
+  workspace: string,
+  previous: string | null,
+): Promise<PackageChange[]> {
+  return invoke<PackageChange[]>("lockfile_diff", { workspace, previous });
+}
+
+export interface LockFileStatus {
+  up_to_date: boolean;
+  /** Why the lock file is out of date */
+  reason: string | null;
+}
+
+/**
+ * Whether `pixi.lock` still satisfies the manifest, without solving or
+ * installing anything
+ */
+export function getLockfileStatus(workspace: string): Promise<LockFileStatus> {
+  return invoke<LockFileStatus>("lockfile_status", { workspace });
+}
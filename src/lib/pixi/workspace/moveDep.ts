@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export async function moveCondaDep(
+  workspace: string,
+  pkg: string,
+  fromFeature: string,
+  toFeature: string,
+): Promise<void> {
+  await invoke("move_conda_dep", {
+    workspace,
+    package: pkg,
+    fromFeature,
+    toFeature,
+  });
+}
+
+export async function movePypiDep(
+  workspace: string,
+  pkg: string,
+  fromFeature: string,
+  toFeature: string,
+): Promise<void> {
+  await invoke("move_pypi_dep", {
+    workspace,
+    package: pkg,
+    fromFeature,
+    toFeature,
+  });
+}
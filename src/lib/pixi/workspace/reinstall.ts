@@ -9,12 +9,36 @@ export interface ReinstallOptions {
   reinstall_environments: ReinstallEnvironments;
 }
 
+export interface PlannedPackage {
+  name: string;
+  version: string;
+  build: string | null;
+}
+
+export interface EnvironmentPlan {
+  environment: string;
+  /** Installed conda packages that get removed */
+  remove: PlannedPackage[];
+  /** Conda packages that aren't in the package cache yet */
+  download: PlannedPackage[];
+  /** Locked packages that get installed */
+  link: PlannedPackage[];
+}
+
+export interface ReinstallPlan {
+  environments: EnvironmentPlan[];
+}
+
 export enum LockFileUsage {
+  /** Re-solve if the lock file is out of date with the manifest */
   Update = "Update",
+  /** Fail if the lock file is out of date with the manifest */
   Locked = "Locked",
+  /** Use the lock file as is, without checking it against the manifest */
   Frozen = "Frozen",
 }
 
+/** Pass an `operationId` to be able to stop it with `cancelOperation` */
 export async function reinstall(
   workspace: string,
   options: ReinstallOptions = {
@@ -22,10 +46,29 @@ export async function reinstall(
     reinstall_environments: "Default",
   },
   lockFileUsage: LockFileUsage = LockFileUsage.Update,
+  operationId?: string,
 ): Promise<void> {
   await invoke("reinstall", {
     workspace,
     options,
     lockFileUsage,
+    operationId,
+  });
+}
+
+/** What `reinstall` would change, without touching the environments */
+export function planReinstall(
+  workspace: string,
+  options: ReinstallOptions = {
+    reinstall_packages: "None",
+    reinstall_environments: "Default",
+  },
+  lockFileUsage: LockFileUsage = LockFileUsage.Update,
+): Promise<ReinstallPlan> {
+  return invoke<ReinstallPlan>("reinstall", {
+    workspace,
+    options,
+    lockFileUsage,
+    dryRun: true,
   });
 }
@@ -9,11 +9,14 @@ export async function removeCondaDeps(
   workspace: string,
   specs: Record<string, MatchSpec>,
   depOptions: DependencyOptions,
+  options: { feature?: string; platform?: string } = {},
 ): Promise<void> {
   await invoke("remove_conda_deps", {
     workspace,
     specs,
     depOptions,
+    feature: options.feature ?? null,
+    platform: options.platform ?? null,
   });
 }
 
@@ -28,3 +31,49 @@ export async function removePypiDeps(
     depOptions,
   });
 }
+
+// Removes all specs at once, nothing is removed if one of them is invalid
+export async function removeCondaDepsBatch(
+  workspace: string,
+  specs: string[],
+  depOptions: DependencyOptions,
+): Promise<void> {
+  await invoke("remove_conda_deps_batch", { workspace, specs, depOptions });
+}
+
+// Removes all requirements at once, nothing is removed if one of them is invalid
+export async function removePypiDepsBatch(
+  workspace: string,
+  specs: string[],
+  depOptions: DependencyOptions,
+): Promise<void> {
+  await invoke("remove_pypi_deps_batch", { workspace, specs, depOptions });
+}
+
+/** `platform` is set for the dependencies of a `[target.<platform>]` table */
+export interface DependencyLocation {
+  feature: string;
+  platform: string | null;
+}
+
+// Removes the package from every feature and target, returns where it was removed from
+export async function removeCondaDepEverywhere(
+  workspace: string,
+  pkg: string,
+): Promise<DependencyLocation[]> {
+  return await invoke("remove_conda_dep_everywhere", {
+    workspace,
+    package: pkg,
+  });
+}
+
+// Removes the package from every feature and target, returns where it was removed from
+export async function removePypiDepEverywhere(
+  workspace: string,
+  pkg: string,
+): Promise<DependencyLocation[]> {
+  return await invoke("remove_pypi_dep_everywhere", {
+    workspace,
+    package: pkg,
+  });
+}
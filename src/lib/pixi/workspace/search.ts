@@ -30,22 +30,131 @@ export function getRepoDataRecordId(record: RepoDataRecord): string {
   return `${record.name}-${record.channel || ""}-${record.subdir}-${record.version}-${record.build}-${record.build_number}`;
 }
 
+/**
+ * Without `channels` the channels of the default environment are searched,
+ * without `platform` the current platform.
+ */
+export interface SearchOptions {
+  channels?: string[];
+  platform?: string;
+}
+
+export interface PackageMetadata {
+  name: string;
+  version: string;
+  build: string;
+  subdir: string;
+  license: string | null;
+  size_bytes: number | null;
+  /** Milliseconds since the Unix epoch */
+  timestamp: number | null;
+}
+
+/** The latest version of a package together with all available versions */
+export interface SearchPackage extends PackageMetadata {
+  /** Newest first */
+  versions: string[];
+  record: RepoDataRecord;
+}
+
+export interface SearchResults {
+  packages: SearchPackage[];
+  /** Number of packages across all pages */
+  total_count: number;
+}
+
+export interface Pagination {
+  limit?: number;
+  offset?: number;
+}
+
 export async function searchWildcard(
   workspace: string,
   packageNameFilter: string,
-): Promise<RepoDataRecord[] | null> {
+  options: SearchOptions & Pagination = {},
+): Promise<SearchResults> {
   return await invoke("search_wildcard", {
     workspace,
     packageNameFilter,
+    ...options,
   });
 }
 
 export async function searchExact(
   workspace: string,
   matchSpec: MatchSpec,
+  options: SearchOptions = {},
 ): Promise<RepoDataRecord[] | null> {
   return await invoke("search_exact", {
     workspace,
     matchSpec,
+    ...options,
+  });
+}
+
+/** A build of a package to pick a pin from */
+export interface PackageVersion extends PackageMetadata {
+  channel: string | null;
+  depends: string[];
+}
+
+/**
+ * All builds of a package, newest version first and the newest build of a
+ * version first. Only the first `limit` are returned if it's given.
+ */
+export async function searchVersions(
+  workspace: string,
+  name: string,
+  options: SearchOptions & { limit?: number } = {},
+): Promise<PackageVersion[]> {
+  return await invoke("search_versions", {
+    workspace,
+    name,
+    ...options,
+  });
+}
+
+/**
+ * A package on the pypi index, `version` and `summary` are only known
+ * if the index also supports the JSON API of pypi.org.
+ */
+export interface PypiSearchResult {
+  name: string;
+  version: string | null;
+  summary: string | null;
+}
+
+export async function searchPypi(
+  workspace: string,
+  query: string,
+  limit?: number,
+): Promise<PypiSearchResult[]> {
+  return await invoke("search_pypi", { workspace, query, limit });
+}
+
+export interface PackageSupport {
+  package: string;
+  available: boolean;
+}
+
+export interface PlatformSupport {
+  packages: PackageSupport[];
+  /** Pypi dependencies aren't checked, they can be built from source */
+  skipped_pypi: string[];
+}
+
+/**
+ * Whether the conda dependencies of an environment have a build for
+ * `platform`, to check before the platform is added to the workspace.
+ */
+export async function checkPlatformSupport(
+  workspace: string,
+  environment: string,
+  platform: string,
+): Promise<PlatformSupport> {
+  return await invoke("check_platform_support", {
+    workspace,
+    environment,
+    platform,
   });
 }
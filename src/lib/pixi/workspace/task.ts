@@ -18,6 +18,14 @@ export interface TaskArgument {
   choices?: string[];
 }
 
+export type TaskArgumentType = "string" | "path";
+
+export interface TaskArgumentSpec {
+  name: string;
+  default?: string;
+  argType?: TaskArgumentType;
+}
+
 export interface ExecuteTask {
   cmd: CommandArguments;
   inputs?: string[];
@@ -47,6 +55,11 @@ export type Task =
   | { Alias: AliasTask }
   | { Custom: CustomTask };
 
+/** A task as returned by `listTask`, with the GUI specific types of its arguments. */
+export type ListedTask = Task & {
+  arg_types?: Record<string, TaskArgumentType>;
+};
+
 export function description(task: Task): string | undefined {
   if ("Execute" in task) {
     return task.Execute.description;
@@ -137,25 +150,80 @@ function formatCommand(commandArgs: CommandArguments): string {
 
 export function listTask(
   workspace: string,
-): Promise<Record<string, Record<string, Task>>> {
-  return invoke<Record<string, Record<string, Task>>>("list_tasks", {
+): Promise<Record<string, Record<string, ListedTask>>> {
+  return invoke<Record<string, Record<string, ListedTask>>>("list_tasks", {
     workspace,
   });
 }
 
+export interface AddTaskOptions {
+  dependsOn?: string[];
+  env?: Record<string, string>;
+  cwd?: string;
+  args?: TaskArgumentSpec[];
+  /** Task that gets replaced, e.g. when it's edited */
+  replace?: string;
+}
+
 export async function addTask(
   workspace: string,
   name: string,
   task: Task,
   feature: string,
+  options: AddTaskOptions = {},
 ): Promise<void> {
-  await invoke("add_task", { workspace, name, task, feature });
+  await invoke("add_task", {
+    workspace,
+    name,
+    task,
+    feature,
+    options,
+  });
 }
 
-export async function removeTask(
+/**
+ * With `cascade`, tasks depending on the removed task lose that dependency,
+ * without it the task isn't removed if anything depends on it.
+ * Returns the tasks whose dependencies were changed.
+ */
+export function removeTask(
   workspace: string,
   name: string,
-  feature: string,
+  feature: string | undefined,
+  cascade: boolean,
+): Promise<string[]> {
+  return invoke<string[]>("remove_task", {
+    workspace,
+    name,
+    feature,
+    cascade,
+  });
+}
+
+export async function renameTask(
+  workspace: string,
+  oldName: string,
+  newName: string,
+  feature?: string,
 ): Promise<void> {
-  await invoke("remove_task", { workspace, name, feature });
+  await invoke("rename_task", {
+    workspace,
+    old: oldName,
+    new: newName,
+    feature: feature ?? null,
+  });
+}
+
+export async function setTaskDescription(
+  workspace: string,
+  task: string,
+  description: string,
+  feature?: string,
+): Promise<void> {
+  await invoke("set_task_description", {
+    workspace,
+    task,
+    description,
+    feature: feature ?? null,
+  });
 }
@@ -3,9 +3,13 @@ import { invoke } from "@tauri-apps/api/core";
 import type { LockFileUsage } from "@/lib/pixi/workspace/reinstall";
 import type { Task } from "@/lib/pixi/workspace/task";
 
+/** A `pyproject.toml` has the pixi configuration under `[tool.pixi]` */
+export type ManifestKind = "pixi" | "pyproject";
+
 export interface Workspace {
   root: string;
   manifest: string;
+  manifestKind: ManifestKind;
   name: string;
   description: string | null;
 }
@@ -13,20 +17,51 @@ export interface Workspace {
 export async function getWorkspace(path: string): Promise<Workspace> {
   const root = await getRoot(path);
   const manifest = await getManifest(root);
+  const manifestKind = await getManifestKind(root);
   const name = await getName(root);
   const description = await getDescription(root);
 
-  return { root, manifest, name, description };
+  return { root, manifest, manifestKind, name, description };
 }
 
 export function getRoot(workspace: string): Promise<string> {
   return invoke<string>("root", { workspace });
 }
 
+/** Makes the next command of this window read the manifest from disk again */
+export async function reloadWorkspace(workspace: string): Promise<void> {
+  await invoke("reload_workspace", { workspace });
+}
+
 export function getManifest(workspace: string): Promise<string> {
   return invoke<string>("manifest", { workspace });
 }
 
+export function getManifestKind(workspace: string): Promise<ManifestKind> {
+  return invoke<ManifestKind>("manifest_kind", { workspace });
+}
+
+/** Opens the manifest with the OS default application for it */
+export async function openManifestExternal(workspace: string): Promise<void> {
+  await invoke("open_manifest_external", { workspace });
+}
+
+/**
+ * Tidies the whitespace of the manifest, e.g. after a batch of edits.
+ * Returns whether it changed, without triggering a `manifest-changed` event.
+ */
+export function formatManifest(workspace: string): Promise<boolean> {
+  return invoke<boolean>("format_manifest", { workspace });
+}
+
+/** Selects the workspace root, or `subpath` like `.pixi`, in the file manager */
+export async function revealInFileManager(
+  workspace: string,
+  subpath?: string,
+): Promise<void> {
+  await invoke("reveal_in_file_manager", { workspace, subpath });
+}
+
 export function getName(workspace: string): Promise<string> {
   return invoke<string>("name", { workspace });
 }
@@ -46,6 +81,85 @@ export async function setDescription(
   await invoke("set_description", { workspace, description });
 }
 
+export function getVersion(workspace: string): Promise<string | null> {
+  return invoke<string | null>("version", { workspace });
+}
+
+export async function setVersion(
+  workspace: string,
+  version: string,
+): Promise<void> {
+  await invoke("set_version", { workspace, version });
+}
+
+export function getRequiresPixi(workspace: string): Promise<string | null> {
+  return invoke<string | null>("requires_pixi", { workspace });
+}
+
+/** Resolves to true if the bundled pixi doesn't satisfy the constraint */
+export function setRequiresPixi(
+  workspace: string,
+  constraint: string,
+): Promise<boolean> {
+  return invoke<boolean>("set_requires_pixi", { workspace, constraint });
+}
+
+export interface PixiCompatibility {
+  /** Version of the pixi binary that runs tasks and terminals */
+  pixi_version: string;
+  /** The `requires-pixi` of the workspace */
+  required: string | null;
+  compatible: boolean;
+}
+
+/** Whether the installed pixi satisfies the workspace's `requires-pixi` */
+export function checkPixiCompatibility(
+  workspace: string,
+): Promise<PixiCompatibility> {
+  return invoke<PixiCompatibility>("check_pixi_compatibility", { workspace });
+}
+
+export interface PreviewFeatures {
+  /** Whether `preview = true` enables every preview feature */
+  all: boolean;
+  enabled: { name: string; recognized: boolean }[];
+  known: string[];
+}
+
+export function listPreviewFeatures(
+  workspace: string,
+): Promise<PreviewFeatures> {
+  return invoke<PreviewFeatures>("list_preview_features", { workspace });
+}
+
+export async function setPreviewFeature(
+  workspace: string,
+  feature: string,
+  enabled: boolean,
+): Promise<void> {
+  await invoke("set_preview_feature", { workspace, feature, enabled });
+}
+
+/** When setting, `undefined` fields are left as they are and empty values clear the field */
+export interface ProjectInfo {
+  authors?: string[] | null;
+  license?: string | null;
+  homepage?: string | null;
+  repository?: string | null;
+  documentation?: string | null;
+}
+
+export function getProjectInfo(workspace: string): Promise<ProjectInfo> {
+  return invoke<ProjectInfo>("project_info", { workspace });
+}
+
+export async function setProjectInfo(
+  workspace: string,
+  info: ProjectInfo,
+): Promise<void> {
+  await invoke("set_project_info", { workspace, info });
+}
+
 export function listChannels(
   workspace: string,
 ): Promise<Record<string, string[]>> {
@@ -54,6 +168,24 @@ export function listChannels(
   });
 }
 
+export interface ChannelInfo {
+  /** The channel as it is written in the manifest */
+  channel: string;
+  canonical_url: string;
+  display_name: string;
+  /** Whether the channel is resolved through the channel alias, e.g. `conda-forge` */
+  is_default: boolean;
+  description: string | null;
+}
+
+export function describeChannels(
+  workspace: string,
+): Promise<Record<string, ChannelInfo[]>> {
+  return invoke<Record<string, ChannelInfo[]>>("describe_channels", {
+    workspace,
+  });
+}
+
 export async function addChannel(
   workspace: string,
   options: ChannelOptions,
@@ -71,6 +203,48 @@ export async function removeChannel(
   await invoke("remove_channel", { workspace, options, priority });
 }
 
+/** A channel of the default feature if `feature` isn't given */
+export interface ChannelChange {
+  channel: string;
+  feature?: string;
+  /** Only used when adding */
+  priority?: number;
+}
+
+/**
+ * Adds all channels with a single solve. Nothing is changed if any of them
+ * is invalid or the solve fails.
+ */
+export async function addChannels(
+  workspace: string,
+  channels: ChannelChange[],
+  prepend: boolean = false,
+  noInstall: boolean = false,
+): Promise<void> {
+  await invoke("add_channels", { workspace, channels, prepend, noInstall });
+}
+
+/**
+ * Removes all channels with a single solve. Nothing is changed if any of
+ * them isn't a channel of its feature or the solve fails.
+ */
+export async function removeChannels(
+  workspace: string,
+  channels: ChannelChange[],
+  noInstall: boolean = false,
+): Promise<void> {
+  await invoke("remove_channels", { workspace, channels, noInstall });
+}
+
+/** `order` has to contain exactly the current channels of the feature */
+export async function reorderChannels(
+  workspace: string,
+  order: string[],
+  feature?: string,
+): Promise<void> {
+  await invoke("reorder_channels", { workspace, feature, order });
+}
+
 export async function setChannels(
   workspace: string,
   options: ChannelOptions,
@@ -88,6 +262,33 @@ export function currentPlatform(): Promise<string> {
   return invoke<string>("current_platform");
 }
 
+export interface DetectedVirtualPackage {
+  name: string;
+  version: string;
+  build_string: string;
+}
+
+/** Virtual packages of this machine like `__glibc`, not tied to a workspace */
+export function detectedVirtualPackages(): Promise<DetectedVirtualPackage[]> {
+  return invoke<DetectedVirtualPackage[]>("detected_virtual_packages");
+}
+
+/**
+ * Absolute path of an executable like `python` in the environment, `null` if
+ * the environment doesn't have it.
+ */
+export function whichInEnvironment(
+  workspace: string,
+  environment: string,
+  executable: string,
+): Promise<string | null> {
+  return invoke<string | null>("which_in_environment", {
+    workspace,
+    environment,
+    executable,
+  });
+}
+
 export async function addPlatforms(
   workspace: string,
   platforms: string[],
@@ -190,6 +391,93 @@ export function listFeaturePypiDependencies(
   );
 }
 
+export interface AllDependencies {
+  conda: Record<string, [feature: string, spec: PixiSpec][]>;
+  pypi: Record<string, [feature: string, spec: PixiPypiSpec][]>;
+}
+
+export function listAllDependencies(
+  workspace: string,
+  environment: string,
+): Promise<AllDependencies> {
+  return invoke<AllDependencies>("list_all_dependencies", {
+    workspace,
+    environment,
+  });
+}
+
+export type DiagnosticSeverity = "error" | "warning" | "advice";
+
+export interface ManifestDiagnostic {
+  message: string;
+  /** Byte offset and length in the manifest */
+  span: [number, number] | null;
+  severity: DiagnosticSeverity;
+}
+
+export function validateManifest(
+  workspace: string,
+): Promise<ManifestDiagnostic[]> {
+  return invoke<ManifestDiagnostic[]>("validate_manifest", { workspace });
+}
+
+export function getManifestText(workspace: string): Promise<string> {
+  return invoke<string>("get_manifest_text", { workspace });
+}
+
+/**
+ * Replaces the manifest with `contents`. Returns the problems if they're not
+ * a valid manifest, the manifest is unchanged then.
+ */
+export function saveManifestText(
+  workspace: string,
+  contents: string,
+): Promise<ManifestDiagnostic[]> {
+  return invoke<ManifestDiagnostic[]>("save_manifest_text", {
+    workspace,
+    contents,
+  });
+}
+
+/**
+ * Makes a path dependency editable or not, the environment has to be
+ * installed again afterwards.
+ */
+export async function setPypiEditable(
+  workspace: string,
+  pkg: string,
+  editable: boolean,
+  feature?: string,
+): Promise<void> {
+  await invoke("set_pypi_editable", {
+    workspace,
+    package: pkg,
+    editable,
+    feature,
+  });
+}
+
+export interface WorkspaceMember {
+  name: string;
+  path: string;
+}
+
+/**
+ * The workspace root followed by the packages it builds from source, i.e.
+ * the conda dependencies with a `path`.
+ */
+export function listMembers(workspace: string): Promise<WorkspaceMember[]> {
+  return invoke<WorkspaceMember[]>("list_members", { workspace });
+}
+
+/** The environments with a feature that defines the task */
+export function taskEnvironments(
+  workspace: string,
+  task: string,
+): Promise<string[]> {
+  return invoke<string[]>("task_environments", { workspace, task });
+}
+
 export async function featureByTask(
   workspace: string,
   task: string,
@@ -226,6 +514,15 @@ export async function removeFeature(
   return invoke<boolean>("remove_feature", { workspace, name });
 }
 
+/** Also updates the environments using the feature */
+export async function renameFeature(
+  workspace: string,
+  oldName: string,
+  newName: string,
+): Promise<void> {
+  await invoke("rename_feature", { workspace, old: oldName, new: newName });
+}
+
 export interface Environment {
   name: string;
   features: string[];
@@ -237,6 +534,15 @@ export function listEnvironments(workspace: string): Promise<Environment[]> {
   return invoke<Environment[]>("list_environments", { workspace });
 }
 
+/**
+ * Environments to copy the channels or platforms from, they are written to a
+ * new feature with the name of the environment
+ */
+export interface EnvironmentBase {
+  channels?: string;
+  platforms?: string;
+}
+
 export async function addEnvironment(
   workspace: string,
   name: string,
@@ -244,6 +550,7 @@ export async function addEnvironment(
   solveGroup?: string,
   noDefaultFeature: boolean = false,
   force: boolean = false,
+  copyFrom: EnvironmentBase = {},
 ): Promise<void> {
   await invoke("add_environment", {
     workspace,
@@ -252,6 +559,8 @@ export async function addEnvironment(
     solveGroup: solveGroup ?? null,
     noDefaultFeature,
     force,
+    copyChannelsFrom: copyFrom.channels ?? null,
+    copyPlatformsFrom: copyFrom.platforms ?? null,
   });
 }
 
@@ -262,6 +571,136 @@ export async function removeEnvironment(
   await invoke("remove_environment", { workspace, name });
 }
 
+/** Adds `newName` with the settings of `source` and the `extraFeatures` */
+/**
+ * Rewrites the environments in the given order, which has to contain exactly
+ * the existing environments
+ */
+export async function reorderEnvironments(
+  workspace: string,
+  order: string[],
+): Promise<void> {
+  await invoke("reorder_environments", { workspace, order });
+}
+export async function duplicateEnvironment(
+  workspace: string,
+  source: string,
+  newName: string,
+  extraFeatures?: string[],
+): Promise<void> {
+  await invoke("duplicate_environment", {
+    workspace,
+    source,
+    newName,
+    extraFeatures: extraFeatures ?? null,
+  });
+}
+
+/** Passing `null` removes the environment from its solve group */
+export async function setSolveGroup(
+  workspace: string,
+  environment: string,
+  solveGroup: string | null,
+): Promise<void> {
+  await invoke("set_solve_group", { workspace, environment, solveGroup });
+}
+
+export function listSolveGroups(
+  workspace: string,
+): Promise<Record<string, string[]>> {
+  return invoke<Record<string, string[]>>("list_solve_groups", { workspace });
+}
+
+export interface SystemRequirements {
+  linux?: string | null;
+  libc?: string | null;
+  cuda?: string | null;
+  macos?: string | null;
+  archspec?: string | null;
+}
+
+export function getSystemRequirements(
+  workspace: string,
+  feature?: string,
+): Promise<SystemRequirements> {
+  return invoke<SystemRequirements>("get_system_requirements", {
+    workspace,
+    feature,
+  });
+}
+
+export async function setSystemRequirements(
+  workspace: string,
+  reqs: SystemRequirements,
+  feature?: string,
+): Promise<void> {
+  await invoke("set_system_requirements", { workspace, feature, reqs });
+}
+
+export interface Activation {
+  scripts: string[];
+  /** Ordered, because values can reference the variables before them */
+  env: Record<string, string>;
+}
+
+export function listActivation(
+  workspace: string,
+  feature?: string,
+): Promise<Activation> {
+  return invoke<Activation>("list_activation", { workspace, feature });
+}
+
+export async function setActivationEnv(
+  workspace: string,
+  env: Record<string, string>,
+  feature?: string,
+): Promise<void> {
+  await invoke("set_activation_env", { workspace, feature, env });
+}
+
+export async function setActivationScripts(
+  workspace: string,
+  scripts: string[],
+  feature?: string,
+): Promise<void> {
+  await invoke("set_activation_scripts", { workspace, feature, scripts });
+}
+
+/** Variables of the activated environment, e.g. `PATH` and `CONDA_PREFIX` */
+export function activatedEnv(
+  workspace: string,
+  environment?: string,
+): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("activated_env", {
+    workspace,
+    environment,
+  });
+}
+
+export interface PypiOptions {
+  indexUrl?: string | null;
+  extraIndexUrls?: string[];
+  /** URLs or paths of directories with distributions */
+  findLinks?: string[];
+  /** `true` for all packages or the names of the packages */
+  noBuildIsolation?: boolean | string[] | null;
+}
+
+export function getPypiOptions(
+  workspace: string,
+  feature?: string,
+): Promise<PypiOptions> {
+  return invoke<PypiOptions>("get_pypi_options", { workspace, feature });
+}
+
+export async function setPypiOptions(
+  workspace: string,
+  options: PypiOptions,
+  feature?: string,
+): Promise<void> {
+  await invoke("set_pypi_options", { workspace, feature, options });
+}
+
 export interface ChannelOptions {
   channels: string[];
   feature?: string | null;
@@ -0,0 +1,36 @@
+import { subscribe } from "@/lib/event";
+
+export interface Progress {
+  phase: string;
+  current: number;
+  total: number;
+  message: string | null;
+}
+
+/** Progress of adding dependencies and (re)installing environments in this window */
+export function onSolveProgress(
+  handler: (progress: Progress) => void,
+): () => void {
+  return subscribe<Progress>("solve-progress", handler);
+}
+
+/** Milliseconds pixi spent in each phase (solve, fetch, link), emitted when an operation finishes */
+export type OperationMetrics = Record<string, number>;
+
+export function onOperationMetrics(
+  handler: (metrics: OperationMetrics) => void,
+): () => void {
+  return subscribe<OperationMetrics>("operation-metrics", handler);
+}
+
+/** Bytes of all packages pixi is downloading, `total` is null while a size is unknown */
+export interface DownloadProgress {
+  downloaded: number;
+  total: number | null;
+}
+
+export function onDownloadProgress(
+  handler: (progress: DownloadProgress) => void,
+): () => void {
+  return subscribe<DownloadProgress>("download-progress", handler);
+}
@@ -1,15 +1,21 @@
 import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 
 export interface PtyInvocation {
   cwd: string;
   manifest: string;
+  /** `TERM` of the process, `xterm-256color` by default */
+  term?: string;
+  /** `COLORTERM` of the process, e.g. `truecolor` */
+  colorterm?: string;
   kind: PtyInvocationKind;
 }
 
 export type PtyInvocationKind =
   | ({ kind: "shell" } & PtyShellInvocation)
   | ({ kind: "task" } & PtyTaskInvocation)
-  | ({ kind: "command" } & PtyCommandInvocation);
+  | ({ kind: "command" } & PtyCommandInvocation)
+  | ({ kind: "init" } & PtyInitInvocation);
 
 export interface PtyShellInvocation {
   environment: string;
@@ -19,6 +25,10 @@ export interface PtyTaskInvocation {
   task: string;
   environment?: string;
   args: string[];
+  /** Directory to run the task in instead of `cwd`, must be inside the workspace */
+  cwd_override?: string;
+  /** Notifies about the outcome when the task exits while the window isn't focused */
+  notify_on_exit?: boolean;
 }
 
 export interface PtyCommandInvocation {
@@ -26,9 +36,24 @@ export interface PtyCommandInvocation {
   environment: string;
 }
 
+/** Streams `pixi init <args>`, `manifest` is ignored as it doesn't exist yet */
+export interface PtyInitInvocation {
+  args: string[];
+}
+
 export interface PtyHandle {
   id: string;
   invocation: PtyInvocation;
+  /** The window that started the PTY, it gets the events of the PTY */
+  window_label: string;
+}
+
+/**
+ * Whether this window started the PTY, a workspace can be open in several
+ * windows
+ */
+export function isOwnPty(handle: PtyHandle): boolean {
+  return handle.window_label === getCurrentWebviewWindow().label;
 }
 
 export interface PtyStartEvent {
@@ -36,6 +61,13 @@ export interface PtyStartEvent {
   invocation: PtyInvocation;
 }
 
+/** Emitted after a resize with the applied, possibly clamped, size */
+export interface PtyResizeEvent {
+  id: string;
+  cols: number;
+  rows: number;
+}
+
 export interface PtyDataEvent {
   id: string;
   data: string;
@@ -71,6 +103,17 @@ export async function writePty(id: string, data: string): Promise<void> {
   });
 }
 
+/** Writes `data` as is, for input that isn't text */
+export async function writePtyBytes(
+  id: string,
+  data: Uint8Array,
+): Promise<void> {
+  await invoke<void>("pty_write_bytes", {
+    id,
+    data: Array.from(data),
+  });
+}
+
 export async function resizePty(
   id: string,
   cols: number,
@@ -89,12 +132,24 @@ export async function getPtyBuffer(id: string): Promise<string> {
   });
 }
 
+/** The buffered output without colors and other ANSI escape codes */
+export async function getPtyBufferPlain(id: string): Promise<string> {
+  return invoke<string>("pty_get_buffer_plain", {
+    id,
+  });
+}
+
 export async function killPty(id: string): Promise<void> {
   await invoke<void>("pty_kill", {
     id,
   });
 }
 
+/** Kills all running PTYs of the current window */
+export async function killAllPtys(): Promise<void> {
+  await invoke<void>("pty_kill_all");
+}
+
 export async function isPtyRunning(id: string): Promise<boolean> {
   return invoke<boolean>("pty_is_running", {
     id,
@@ -104,3 +159,24 @@ export async function isPtyRunning(id: string): Promise<boolean> {
 export async function listPtys(): Promise<PtyHandle[]> {
   return invoke<PtyHandle[]>("pty_list");
 }
+
+/** Output of exited terminals saved before the last restart, most recent first */
+export async function loadSavedPtys(
+  workspace: string,
+): Promise<PtyExitEvent[]> {
+  return invoke<PtyExitEvent[]>("pty_load_saved", {
+    workspace,
+  });
+}
+
+/** Whether the output of exited terminals is saved across restarts */
+export function isScrollbackPersistenceEnabled(): Promise<boolean> {
+  return invoke<boolean>("scrollback_persistence_enabled");
+}
+
+/** Disabling it also removes the saved output */
+export async function setScrollbackPersistence(
+  enabled: boolean,
+): Promise<void> {
+  await invoke("set_scrollback_persistence", { enabled });
+}
@@ -1,31 +1,26 @@
-import { LazyStore } from "@tauri-apps/plugin-store";
-
-import type { Workspace } from "@/lib/pixi/workspace/workspace";
+import { invoke } from "@tauri-apps/api/core";
 
 export interface RecentWorkspaceEntry {
   manifest: string;
+  name: string;
+  /** Milliseconds since the Unix epoch */
+  lastOpened: number;
 }
 
-const store = new LazyStore("recent-workspaces.json");
-
-export async function listRecentWorkspaces(): Promise<RecentWorkspaceEntry[]> {
-  return (await store.get<RecentWorkspaceEntry[]>("recentWorkspaces")) ?? [];
+/** Most recently opened first */
+export function listRecentWorkspaces(): Promise<RecentWorkspaceEntry[]> {
+  return invoke<RecentWorkspaceEntry[]>("list_recent_workspaces");
 }
 
-export async function addRecentWorkspace(workspace: Workspace): Promise<void> {
-  const recents = await listRecentWorkspaces();
-  const updated = [
-    { manifest: workspace.manifest },
-    ...recents.filter(({ manifest }) => manifest !== workspace.manifest),
-  ];
-
-  await store.set("recentWorkspaces", updated);
-  await store.save();
+/** Moves the workspace to the top, `path` can be its manifest or directory */
+export async function addRecentWorkspace(path: string): Promise<void> {
+  await invoke("add_recent_workspace", { path });
 }
 
 export async function removeRecentWorkspace(manifest: string): Promise<void> {
-  const recents = await listRecentWorkspaces();
-  const remaining = recents.filter((entry) => entry.manifest !== manifest);
-  await store.set("recentWorkspaces", remaining);
-  await store.save();
+  await invoke("remove_recent_workspace", { path: manifest });
+}
+
+export async function clearRecentWorkspaces(): Promise<void> {
+  await invoke("clear_recent_workspaces");
 }
@@ -1,9 +0,0 @@
-import { invoke } from "@tauri-apps/api/core";
-
-export async function watchManifest(manifestPath: string): Promise<void> {
-  await invoke("watch_manifest", { manifestPath });
-}
-
-export async function unwatchManifest(): Promise<void> {
-  await invoke("unwatch_manifest");
-}
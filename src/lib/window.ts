@@ -3,3 +3,60 @@ import { invoke } from "@tauri-apps/api/core";
 export async function openNewWindow(): Promise<void> {
   await invoke("open_new_window");
 }
+
+/** Inner size in logical pixels */
+export interface WindowSize {
+  width: number;
+  height: number;
+}
+
+/** The size only applies if the start screen has no saved geometry */
+export async function openNewWindowSized(size: WindowSize): Promise<void> {
+  await invoke("open_new_window_sized", { ...size });
+}
+
+/** Size of new windows that have no saved geometry yet */
+export function getDefaultWindowSize(): Promise<WindowSize> {
+  return invoke<WindowSize>("get_default_window_size");
+}
+
+/** `null` restores the built-in size */
+export async function setDefaultWindowSize(
+  size: WindowSize | null,
+): Promise<void> {
+  await invoke("set_default_window_size", { size });
+}
+
+export interface WindowInfo {
+  label: string;
+  workspace_path: string | null;
+  title: string;
+}
+
+export function listWindows(): Promise<WindowInfo[]> {
+  return invoke<WindowInfo[]>("list_windows");
+}
+
+/** Also kills the terminals of the window and stops watching its manifest */
+export async function closeWindow(label: string): Promise<void> {
+  await invoke("close_window", { label });
+}
+
+/** Whether the windows of the last session are reopened on launch */
+export function isSessionRestoreEnabled(): Promise<boolean> {
+  return invoke<boolean>("session_restore_enabled");
+}
+
+export async function setSessionRestore(enabled: boolean): Promise<void> {
+  await invoke("set_session_restore", { enabled });
+}
+
+/**
+ * Opens another window of the workspace even if one is open already, each
+ * window has its own terminals and tasks
+ */
+export async function duplicateWorkspaceWindow(
+  workspace: string,
+): Promise<void> {
+  await invoke("duplicate_workspace_window", { workspace });
+}
@@ -7,15 +7,11 @@ import {
 } from "@tanstack/react-router";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { confirm, message } from "@tauri-apps/plugin-dialog";
-import {
-  isPermissionGranted,
-  requestPermission,
-  sendNotification,
-} from "@tauri-apps/plugin-notification";
 import { useCallback, useEffect } from "react";
 import { toast } from "sonner";
 
 import { subscribe } from "@/lib/event";
+import { workspaceClosed, workspaceOpened } from "@/lib/lifecycle";
 import type { PixiNotification } from "@/lib/pixi/notification";
 import { type Task, listTask } from "@/lib/pixi/workspace/task";
 import {
@@ -29,9 +25,7 @@ import {
   listFeatures,
   listPlatforms,
 } from "@/lib/pixi/workspace/workspace";
-import { type PtyExitEvent, killPty, listPtys } from "@/lib/pty";
-import { addRecentWorkspace } from "@/lib/recentWorkspaces";
-import { unwatchManifest, watchManifest } from "@/lib/watcher";
+import { isOwnPty, killPty, listPtys } from "@/lib/pty";
 
 export interface WorkspaceLoaderData {
   workspace: Workspace;
@@ -56,7 +50,6 @@ export const Route = createFileRoute("/workspace/$path")({
         listPlatforms(workspace.root),
         currentPlatform(),
       ]);
-    await addRecentWorkspace(workspace);
     return {
       workspace,
       tasks,
@@ -94,8 +87,8 @@ function WorkspaceLayout() {
 
   // Auto refresh when manifest changes
   useEffect(() => {
-    watchManifest(workspace.manifest).catch((error) => {
-      console.error("Failed to start manifest watcher:", error);
+    workspaceOpened(workspace.root).catch((error) => {
+      console.error("Failed to open workspace:", error);
     });
 
     const unsubscribe = subscribe("manifest-changed", async () => {
@@ -105,11 +98,11 @@ function WorkspaceLayout() {
 
     return () => {
       unsubscribe();
-      unwatchManifest().catch((error) => {
-        console.error("Failed to stop manifest watcher:", error);
+      workspaceClosed().catch((error) => {
+        console.error("Failed to close workspace:", error);
       });
     };
-  }, [workspace.manifest, router]);
+  }, [workspace.root, router]);
 
   // Listen for messages from pixi-api
   useEffect(() => {
@@ -138,60 +131,11 @@ function WorkspaceLayout() {
     };
   }, []);
 
-  // Receive pty/task events
-  useEffect(() => {
-    const appWebview = getCurrentWebviewWindow();
-
-    const sendTaskNotification = async (title: string, body: string) => {
-      let permissionGranted = await isPermissionGranted();
-
-      if (!permissionGranted) {
-        const permission = await requestPermission();
-        permissionGranted = permission === "granted";
-      }
-
-      // Only show task notifications when the window is not focused
-      if ((await appWebview.isFocused()) || !permissionGranted) {
-        return;
-      }
-
-      sendNotification({ title, body });
-    };
-
-    const unsubscribe = subscribe<PtyExitEvent>("pty-exit", (payload) => {
-      if (payload.invocation.kind.kind !== "task") {
-        return;
-      }
-
-      const title = payload.success ? "Task completed" : "Task failed";
-      let body = `"${payload.invocation.kind.task}"`;
-      if (payload.success) {
-        body += " completed successfully.";
-      } else if (payload.signal) {
-        body += ` ended due to signal ${payload.signal}.`;
-      } else if (payload.exit_code !== null) {
-        body += ` exited with code ${payload.exit_code}.`;
-      } else {
-        body += " was terminated.";
-      }
-
-      try {
-        sendTaskNotification(title, body);
-      } catch (error) {
-        console.error("Failed to send task notification:", error);
-      }
-    });
-
-    return () => {
-      unsubscribe();
-    };
-  }, []);
-
   // Before closing a workspace, ensure that it has no active PTYs anymore
   const closeWorkspace = useCallback(async (): Promise<boolean> => {
     const handles = await listPtys();
     const workspaceHandles = handles.filter(
-      (handle) => handle.invocation.cwd === workspace.root,
+      (handle) => handle.invocation.cwd === workspace.root && isOwnPty(handle),
     );
 
     if (workspaceHandles.length === 0) {